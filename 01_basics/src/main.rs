@@ -2,6 +2,7 @@
 // 文件：main.rs - 演示如何组合多个视图
 
 use gpui::*;
+use gpui_components::{watch_render_churn, ChildCache, RenderChurnWarnings};
 
 // 定义欢迎视图
 struct WelcomeView {
@@ -25,10 +26,65 @@ impl Render for WelcomeView {
 struct HelloView {
     // 💡 方式1：在结构体中持有子视图的数据
     user_names: Vec<String>,
+    // 💡 方式2：固定的子视图在构造时创建一次，render 中只克隆句柄
+    featured: Vec<Entity<WelcomeView>>,
+    // 💡 方式3：随数据变化的子视图按名字缓存，名字不变就复用同一个实体
+    welcome_views: ChildCache<String, WelcomeView>,
+}
+
+impl HelloView {
+    fn new(user_names: Vec<String>, cx: &mut Context<Self>) -> Self {
+        let featured = ["张三", "李四"]
+            .into_iter()
+            .map(|name| {
+                cx.new(|_| WelcomeView {
+                    name: name.to_string(),
+                })
+            })
+            .collect();
+        // 出现新的抖动警告时重新渲染，把警告显示出来
+        cx.observe_global::<RenderChurnWarnings>(|_, cx| cx.notify())
+            .detach();
+        Self {
+            user_names,
+            featured,
+            welcome_views: ChildCache::new(),
+        }
+    }
+}
+
+/// 调试构建中 watch_render_churn 记录的警告，显示在窗口顶部
+fn render_churn_warnings(cx: &App) -> Vec<Div> {
+    cx.try_global::<RenderChurnWarnings>()
+        .map(|warnings| {
+            warnings
+                .0
+                .iter()
+                .map(|warning| {
+                    div()
+                        .px_4()
+                        .py_2()
+                        .bg(rgb(0xFEE2E2))
+                        .text_color(rgb(0x991B1B))
+                        .text_sm()
+                        .rounded_lg()
+                        .child(warning.clone())
+                })
+                .collect()
+        })
+        .unwrap_or_default()
 }
 
 impl Render for HelloView {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        // ⚠️ 不要在 render 中直接 cx.new()：每一帧都会创建新实体，子视图的状态随之丢失
+        // sync 只为新出现的名字创建实体，消失的名字对应的实体被丢弃
+        let welcome_views = self
+            .welcome_views
+            .sync(self.user_names.iter().cloned(), cx, |name, _| WelcomeView {
+                name: name.clone(),
+            });
+
         div()
             .size_full()
             .flex()
@@ -38,6 +94,7 @@ impl Render for HelloView {
             .gap_4()
             .bg(rgb(0xF8FAFC))
             .p_8()
+            .children(render_churn_warnings(cx))
             // 主标题
             .child(
                 div()
@@ -73,19 +130,14 @@ impl Render for HelloView {
                     ),
             )
             .child(div().w(px(200.0)).h(px(1.0)).bg(rgb(0xE2E8F0)).my_2())
-            // ✨ 方式2：使用 cx.new() 创建子视图实例
+            // ✨ 方式2：渲染在 new() 中用 cx.new() 创建的子视图实例
             .child(
                 div()
                     .flex()
                     .flex_col()
                     .gap_2()
                     .child("【方式2：使用 View (推荐)】")
-                    .child(cx.new(|_| WelcomeView {
-                        name: "张三".to_string(),
-                    }))
-                    .child(cx.new(|_| WelcomeView {
-                        name: "李四".to_string(),
-                    })),
+                    .children(self.featured.iter().cloned()),
             )
             .child(div().w(px(200.0)).h(px(1.0)).bg(rgb(0xE2E8F0)).my_2())
             // ✨ 方式3：使用循环动态生成多个子视图
@@ -95,11 +147,7 @@ impl Render for HelloView {
                     .flex_col()
                     .gap_2()
                     .child("【方式3：循环生成】")
-                    .children(
-                        self.user_names
-                            .iter()
-                            .map(|name| cx.new(|_| WelcomeView { name: name.clone() })),
-                    ),
+                    .children(welcome_views),
             )
             // 底部说明
             .child(
@@ -114,6 +162,9 @@ impl Render for HelloView {
 
 fn main() {
     Application::new().run(|cx| {
+        // 调试构建中检查 WelcomeView 是否在 render 中被反复创建
+        watch_render_churn::<WelcomeView>(cx);
+
         cx.open_window(
             WindowOptions {
                 window_bounds: Some(WindowBounds::Windowed(Bounds {
@@ -134,8 +185,11 @@ fn main() {
                 ..Default::default()
             },
             |_window, cx| {
-                cx.new(|_cx| HelloView {
-                    user_names: vec!["王五".to_string(), "赵六".to_string(), "孙七".to_string()],
+                cx.new(|cx| {
+                    HelloView::new(
+                        vec!["王五".to_string(), "赵六".to_string(), "孙七".to_string()],
+                        cx,
+                    )
                 })
             },
         )
@@ -153,7 +207,8 @@ fn main() {
 
 方式2：使用 cx.new() 创建 View 实例（推荐）
 ----------------------------------------
-使用 cx.new(|_| WelcomeView { ... }) 创建独立的视图组件。
+在父视图的构造函数中用 cx.new(|_| WelcomeView { ... }) 创建独立的视图组件，
+把 Entity<WelcomeView> 保存在字段里，render 中只克隆句柄。
 优点：
   - 代码复用性强
   - 每个组件有自己的状态和生命周期
@@ -161,6 +216,11 @@ fn main() {
   - 组件可以独立更新（通过 cx.notify()）
 缺点：稍微复杂一点
 
+⚠️ 不要在 render 中调用 cx.new()：
+  - render 每次重新渲染都会执行，每一帧都会创建新的实体
+  - 上一帧的实体随旧的元素树释放，子视图的状态全部丢失
+  - 调试构建中 watch_render_churn::<WelcomeView>(cx) 会检查这种情况，并把警告显示在窗口顶部
+
 方式3：使用 .children() 批量添加
 --------------------------------
 使用迭代器和 .children() 方法动态生成多个子元素。
@@ -170,11 +230,17 @@ fn main() {
   - 容易处理动态数量的元素
 缺点：需要理解迭代器
 
+子元素是 View 时，用 ChildCache 按 key 缓存实体：
+  let views = self.welcome_views.sync(self.user_names.iter().cloned(), cx, |name, _| WelcomeView { .. });
+  - 同一个 key 只创建一次，后续渲染复用原来的实体
+  - key 从数据中消失时，对应的实体被丢弃
+
 关键 API：
 ----------
 - .child(element)       : 添加单个子元素
 - .children(iterator)   : 添加多个子元素（接收迭代器）
-- cx.new(|_| View)      : 创建新的视图实例
+- cx.new(|_| View)      : 创建新的视图实例（在构造函数或事件处理中调用）
+- ChildCache::sync()    : 按 key 复用子视图实例，可以在 render 中调用
 - .map()                : 将数据转换为元素
 
 最佳实践：
@@ -183,6 +249,7 @@ fn main() {
 2. 简单的一次性 UI → 直接使用 div()
 3. 列表数据 → 使用 .children() + .map()
 4. 需要独立状态的组件 → 必须使用 View + cx.new()
+5. 子视图实体只创建一次 → 保存在字段或 ChildCache 中，不在 render 中 cx.new()
 
 运行命令：
 ----------
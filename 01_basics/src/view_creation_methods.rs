@@ -2,6 +2,7 @@
 // 文件：view_creation_methods.rs - 演示创建子视图的所有方式
 
 use gpui::*;
+use gpui_components::{watch_render_churn, ChildCache, RenderChurnWarnings};
 
 // ============================================================================
 // 方式 1: 使用 cx.new() 创建 View<T> (最常用) ⭐⭐⭐⭐⭐
@@ -17,6 +18,10 @@ use gpui::*;
 // - 需要独立状态的组件
 // - 可复用的 UI 组件
 // - 复杂的交互组件
+//
+// 注意：
+// - 在父视图的构造函数（或事件处理）中 cx.new()，把 Entity<T> 保存在字段里
+// - 不要在 render 中 cx.new()：每一帧都会创建新实体，状态随之丢失
 
 struct StatefulComponent {
     title: String,
@@ -71,6 +76,7 @@ fn inline_div_example() -> Div {
 // - 纯展示型组件
 // - 不需要更新的组件
 
+#[derive(IntoElement)]
 struct OnceComponent {
     message: String,
     color: Hsla,
@@ -202,27 +208,41 @@ fn list_from_data(items: &[String]) -> impl IntoElement {
 // 适用场景：
 // - 复杂的应用结构
 // - 组件需要相互通信
+//
+// 子 View 由数据决定时，用 ChildCache 按 key 缓存：
+// - 同一个 key 只创建一次，之后的渲染复用同一个实体（状态得以保留）
+// - key 从数据中消失时，对应的实体被丢弃
 
 struct ParentView {
     title: String,
+    /// 子组件的数据：(标题, 初始计数)，标题作为缓存的 key
+    children: Vec<(String, i32)>,
+    /// 按标题缓存的子组件实体
+    child_views: ChildCache<String, StatefulComponent>,
 }
 
 impl Render for ParentView {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        // 嵌套其他 View：只为新出现的标题创建实体
+        let children = &self.children;
+        let child_views = self.child_views.sync(
+            children.iter().map(|(title, _)| title.clone()),
+            cx,
+            |title, _| StatefulComponent {
+                title: title.clone(),
+                count: children
+                    .iter()
+                    .find_map(|(key, count)| (key == title).then_some(*count))
+                    .unwrap_or_default(),
+            },
+        );
+
         div()
             .flex()
             .flex_col()
             .gap_4()
             .child(self.title.clone())
-            // 嵌套其他 View
-            .child(cx.new(|_| StatefulComponent {
-                title: "子组件 1".to_string(),
-                count: 10,
-            }))
-            .child(cx.new(|_| StatefulComponent {
-                title: "子组件 2".to_string(),
-                count: 20,
-            }))
+            .children(child_views)
     }
 }
 
@@ -233,6 +253,53 @@ impl Render for ParentView {
 struct AllMethodsDemo {
     show_optional: bool,
     items: Vec<String>,
+    /// 方式 1 的有状态组件，在 new() 中创建一次
+    stateful: Entity<StatefulComponent>,
+    /// 方式 8 的嵌套 View，在 new() 中创建一次
+    parent_view: Entity<ParentView>,
+}
+
+impl AllMethodsDemo {
+    fn new(items: Vec<String>, cx: &mut Context<Self>) -> Self {
+        // 出现新的抖动警告时重新渲染，把警告显示出来
+        cx.observe_global::<RenderChurnWarnings>(|_, cx| cx.notify())
+            .detach();
+        Self {
+            show_optional: true,
+            items,
+            stateful: cx.new(|_| StatefulComponent {
+                title: "有状态组件".to_string(),
+                count: 42,
+            }),
+            parent_view: cx.new(|_| ParentView {
+                title: "父组件包含子组件".to_string(),
+                children: vec![("子组件 1".to_string(), 10), ("子组件 2".to_string(), 20)],
+                child_views: ChildCache::new(),
+            }),
+        }
+    }
+}
+
+/// 调试构建中 watch_render_churn 记录的警告，显示在窗口顶部
+fn render_churn_warnings(cx: &App) -> Vec<Div> {
+    cx.try_global::<RenderChurnWarnings>()
+        .map(|warnings| {
+            warnings
+                .0
+                .iter()
+                .map(|warning| {
+                    div()
+                        .px_4()
+                        .py_2()
+                        .bg(rgb(0xFEE2E2))
+                        .text_color(rgb(0x991B1B))
+                        .text_sm()
+                        .rounded_lg()
+                        .child(warning.clone())
+                })
+                .collect()
+        })
+        .unwrap_or_default()
 }
 
 impl Render for AllMethodsDemo {
@@ -244,6 +311,7 @@ impl Render for AllMethodsDemo {
             .gap_4()
             .p_8()
             .bg(rgb(0xF9FAFB))
+            .children(render_churn_warnings(cx))
             // 标题
             .child(
                 div()
@@ -258,10 +326,7 @@ impl Render for AllMethodsDemo {
                     .flex_col()
                     .gap_2()
                     .child("【方式 1】使用 cx.new() 创建 View：")
-                    .child(cx.new(|_| StatefulComponent {
-                        title: "有状态组件".to_string(),
-                        count: 42,
-                    })),
+                    .child(self.stateful.clone()),
             )
             // 方式 2: 内联 div
             .child(
@@ -279,13 +344,10 @@ impl Render for AllMethodsDemo {
                     .flex_col()
                     .gap_2()
                     .child("【方式 3】RenderOnce trait：")
-                    .child(
-                        div()
-                            .p_4()
-                            .bg(rgb(0xFEF3C7))
-                            .rounded_lg()
-                            .child("一次性渲染组件（简化版）"),
-                    ),
+                    .child(OnceComponent {
+                        message: "一次性渲染组件".to_string(),
+                        color: rgb(0xFEF3C7).into(),
+                    }),
             )
             // 方式 4: 函数返回元素
             .child(
@@ -361,9 +423,7 @@ impl Render for AllMethodsDemo {
                     .flex_col()
                     .gap_2()
                     .child("【方式 8】嵌套 View 组合：")
-                    .child(cx.new(|_| ParentView {
-                        title: "父组件包含子组件".to_string(),
-                    })),
+                    .child(self.parent_view.clone()),
             )
     }
 }
@@ -374,6 +434,10 @@ impl Render for AllMethodsDemo {
 
 pub fn main() {
     Application::new().run(|cx| {
+        // 调试构建中检查子视图是否在 render 中被反复创建
+        watch_render_churn::<StatefulComponent>(cx);
+        watch_render_churn::<ParentView>(cx);
+
         cx.open_window(
             WindowOptions {
                 window_bounds: Some(WindowBounds::Windowed(Bounds {
@@ -394,13 +458,15 @@ pub fn main() {
                 ..Default::default()
             },
             |_window, cx| {
-                cx.new(|_cx| AllMethodsDemo {
-                    show_optional: true,
-                    items: vec![
-                        "列表项 1".to_string(),
-                        "列表项 2".to_string(),
-                        "列表项 3".to_string(),
-                    ],
+                cx.new(|cx| {
+                    AllMethodsDemo::new(
+                        vec![
+                            "列表项 1".to_string(),
+                            "列表项 2".to_string(),
+                            "列表项 3".to_string(),
+                        ],
+                        cx,
+                    )
                 })
             },
         )
@@ -424,6 +490,9 @@ pub fn main() {
 | 7. .children() | .children(iter) | - | ⭐⭐⭐⭐ | ⭐⭐⭐⭐ | ⭐⭐⭐⭐⭐ |
 | 8. 嵌套 View | View { child: View } | ✅ | ⭐⭐⭐⭐⭐ | ⭐⭐⭐ | ⭐⭐⭐⭐ |
 
+⚠️ 表中的 cx.new() 都应在构造函数或事件处理中调用，不要写在 render 里；
+   render 中需要按数据生成子 View 时使用 ChildCache::sync()。
+
 ===============================================================================
 🎯 选择建议
 ===============================================================================
@@ -446,7 +515,7 @@ pub fn main() {
    - 包括：View<T>、Div、String、&str、RenderOnce 等
 
 2. 选择合适的方式
-   - 有状态 → View (cx.new)
+   - 有状态 → View (cx.new，只创建一次)
    - 无状态 → RenderOnce 或函数
    - 简单内容 → 内联或字符串
 
@@ -0,0 +1,1251 @@
+// 第三章：GPUI 状态管理
+// =====================
+// 本章学习 GPUI 中的状态传递机制：
+// 1. 组件自身状态管理
+// 2. 父组件向子组件传递状态
+// 3. 子组件向父组件传递状态（回调 + 事件）
+// 4. 兄弟组件之间的通信（事件总线：按消息类型发布/订阅，不经过父组件）
+// 5. 使用 observe 观察其他实体的变化
+// 6. 撤销/重做：把跨实体的修改记录为可逆的事务
+// 7. 动态子组件列表：运行时添加、删除、重命名，订阅随子组件创建和丢弃
+// 8. 派生状态：总计声明自己的输入，从输入的当前状态重新计算
+// 9. Store：计数保存在 Store 中，只通过 dispatch → reduce 修改，视图渲染 select 选出的值
+
+use gpui::*;
+use gpui_components::history::{self, Redo, Undo};
+use gpui_components::{
+    text_input, ButtonSize, ButtonVariant, EventBusContext, LogLevel, LogPanel, Store,
+    StyledButton, TextInput, TextInputEvent, Transaction, UndoHistory, Undoable,
+};
+
+/// 注册本章的快捷键（撤销/重做、重命名输入框），main 和测试都调用它
+pub fn init(cx: &mut App) {
+    history::init(cx);
+    text_input::init(cx);
+}
+
+// ============================================================================
+// 第一部分：事件定义
+// ============================================================================
+
+/// 子组件发出的事件 - 用于子传父通信
+#[derive(Clone, Debug)]
+struct CounterChangedEvent {
+    new_value: i32,
+    delta: i32,
+}
+
+/// 为 ChildCounter 实现 EventEmitter，使其能够发出 CounterChangedEvent
+impl EventEmitter<CounterChangedEvent> for ChildCounter {}
+
+// 下面是事件总线上的消息：发布者不需要知道谁在监听，订阅者也不需要持有发布者的 Entity
+
+/// 总线消息：计数器加入了列表（由父组件发布，列表归父组件管理）
+#[derive(Clone, Debug)]
+struct CounterAdded {
+    counter: Entity<ChildCounter>,
+}
+
+/// 总线消息：计数器从列表中移除
+#[derive(Clone, Debug)]
+struct CounterRemoved {
+    counter: EntityId,
+}
+
+/// 总线消息：其余计数器都改为 source 的值（兄弟组件通信）
+#[derive(Clone, Debug)]
+struct SyncCounters {
+    source: EntityId,
+    value: i32,
+}
+
+// ============================================================================
+// 第二部分：子组件定义
+// ============================================================================
+
+/// 计数器 Store 中的全部状态
+#[derive(Clone, Debug, PartialEq)]
+struct CounterState {
+    count: i32,
+}
+
+/// 修改 CounterState 的消息
+#[derive(Clone, Debug, PartialEq)]
+enum CounterMsg {
+    Increment,
+    Decrement,
+    /// 直接设为某个值：同步、重置、撤销
+    Set(i32),
+}
+
+/// 纯函数：新状态只由旧状态和消息决定
+fn reduce_counter(state: &CounterState, msg: &CounterMsg) -> CounterState {
+    let count = match *msg {
+        CounterMsg::Increment => state.count + 1,
+        CounterMsg::Decrement => state.count - 1,
+        CounterMsg::Set(count) => count,
+    };
+    CounterState { count }
+}
+
+type CounterStore = Store<CounterState, CounterMsg>;
+
+/// 子计数器组件 - 展示子组件如何：
+/// 1. 接收父组件传递的初始值
+/// 2. 管理自身状态（保存在自己的 Store 中，只通过 dispatch 修改）
+/// 3. 通过事件通知父组件状态变化
+/// 4. 通过事件总线与兄弟组件通信
+struct ChildCounter {
+    /// 计数器名称（从父组件传入，可以被父组件重命名），标题显示为"计数器 {name}"
+    name: String,
+    /// 计数的唯一来源：按钮、同步、撤销都向它发送消息，由 reduce_counter 计算新状态
+    store: Entity<CounterStore>,
+    /// Store::select 选出的计数，用于渲染和撤销快照；只在 Store 的计数变化后更新
+    count: i32,
+    /// 背景颜色（从父组件传入的样式配置）
+    color: Rgba,
+    /// Store 的 select 订阅和总线上 SyncCounters 的订阅，随计数器一起释放
+    _subscriptions: Vec<Subscription>,
+}
+
+impl ChildCounter {
+    /// 创建新的子计数器
+    /// 参数从父组件传入，这是父传子的主要方式
+    fn new(name: impl Into<String>, initial_count: i32, color: Rgba, cx: &mut Context<Self>) -> Self {
+        let store = cx.new(|_| Store::new(CounterState { count: initial_count }, reduce_counter));
+        // 计数变化时更新自己的副本并 notify()，观察它的 TotalDisplay 随之重新计算
+        let (count, select_subscription) = Store::select(
+            &store,
+            cx,
+            |state: &CounterState| state.count,
+            |counter: &mut Self, count, cx| {
+                counter.count = *count;
+                cx.notify();
+            },
+        );
+        // 兄弟组件发布的同步消息，直接修改自己，不经过父组件
+        let sync_subscription = cx.subscribe_bus(|counter: &mut Self, message: &SyncCounters, cx| {
+            if message.source != cx.entity_id() {
+                counter.set_count(message.value, cx);
+            }
+        });
+        Self {
+            name: name.into(),
+            store,
+            count,
+            color,
+            _subscriptions: vec![select_subscription, sync_subscription],
+        }
+    }
+
+    /// 修改计数的唯一入口：把消息交给 Store，返回 reduce 之后的计数
+    ///
+    /// self.count 要等 Store 发出的 StateChanged 被处理后才更新，这里从 Store 读取最新值
+    fn dispatch(&mut self, msg: CounterMsg, cx: &mut Context<Self>) -> i32 {
+        self.store.update(cx, |store, cx| {
+            store.dispatch(msg, cx);
+            store.state().count
+        })
+    }
+
+    /// 直接设为某个值（同步、重置、撤销），不发出 CounterChangedEvent
+    fn set_count(&mut self, count: i32, cx: &mut Context<Self>) {
+        self.dispatch(CounterMsg::Set(count), cx);
+    }
+
+    /// 增加计数
+    fn increment(&mut self, cx: &mut Context<Self>) {
+        let new_value = self.dispatch(CounterMsg::Increment, cx);
+        // 发出事件通知父组件（用户的操作，父组件据此记录撤销历史）
+        cx.emit(CounterChangedEvent { new_value, delta: 1 });
+    }
+
+    /// 减少计数
+    fn decrement(&mut self, cx: &mut Context<Self>) {
+        let new_value = self.dispatch(CounterMsg::Decrement, cx);
+        cx.emit(CounterChangedEvent { new_value, delta: -1 });
+    }
+
+    /// 把自己的值发布给所有兄弟计数器
+    fn broadcast(&mut self, cx: &mut Context<Self>) {
+        cx.publish(SyncCounters {
+            source: cx.entity_id(),
+            value: self.count,
+        });
+    }
+}
+
+/// 撤销时直接恢复计数，不发出 CounterChangedEvent（不会再被记录为新的一步），
+/// 计数变化后 select 回调中的 notify() 让观察它的 TotalDisplay 重新计算总计
+impl Undoable for ChildCounter {
+    type Snapshot = i32;
+
+    fn snapshot(&self) -> i32 {
+        self.count
+    }
+
+    fn restore(&mut self, count: i32, cx: &mut Context<Self>) {
+        self.set_count(count, cx);
+    }
+}
+
+impl Render for ChildCounter {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .p_4()
+            .rounded_lg()
+            .bg(self.color)
+            .child(
+                // 标题
+                div()
+                    .text_lg()
+                    .font_weight(FontWeight::BOLD)
+                    .text_color(rgb(0x1F2937))
+                    .child(format!("计数器 {}", self.name)),
+            )
+            .child(
+                // 计数显示和按钮
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_3()
+                    .child(
+                        // 减少按钮
+                        StyledButton::new("dec", "-")
+                            .variant(ButtonVariant::Danger)
+                            .size(ButtonSize::Small)
+                            .w(px(32.0))
+                            .h(px(32.0))
+                            .on_click(cx.listener(|view, _event, _window, cx| {
+                                view.decrement(cx);
+                            })),
+                    )
+                    .child(
+                        // 计数显示
+                        div()
+                            .min_w(px(60.0))
+                            .text_center()
+                            .text_xl()
+                            .font_weight(FontWeight::BOLD)
+                            .text_color(rgb(0x374151))
+                            .child(format!("{}", self.count)),
+                    )
+                    .child(
+                        // 增加按钮
+                        StyledButton::new("inc", "+")
+                            .variant(ButtonVariant::Success)
+                            .size(ButtonSize::Small)
+                            .w(px(32.0))
+                            .h(px(32.0))
+                            .on_click(cx.listener(|view, _event, _window, cx| {
+                                view.increment(cx);
+                            })),
+                    ),
+            )
+    }
+}
+
+// ============================================================================
+// 第三部分：派生状态 - 展示 observe 机制
+// ============================================================================
+
+/// 总计显示组件 - 派生状态
+///
+/// 总计不是靠累加事件中的 delta 维护的，而是观察输入（一组计数器），
+/// 任一输入调用 notify() 时从所有输入的当前状态重新计算。
+/// 因此不发出事件的修改（同步、撤销、直接赋值）和初始值都会被计入，总计不可能与计数器不一致。
+/// 输入列表由父组件管理，通过事件总线上的 CounterAdded / CounterRemoved 告诉 TotalDisplay。
+struct TotalDisplay {
+    /// 输入：参与求和的计数器和对它的 observe，按加入顺序
+    inputs: Vec<(Entity<ChildCounter>, Subscription)>,
+    /// 派生值：只在 recompute 中写入
+    total: i32,
+    /// 最后一次变化
+    last_change: String,
+    /// 总线订阅，随 TotalDisplay 一起丢弃
+    _subscriptions: Vec<Subscription>,
+}
+
+impl TotalDisplay {
+    fn new(cx: &mut Context<Self>) -> Self {
+        let subscriptions = vec![
+            cx.subscribe_bus(|display: &mut Self, message: &CounterAdded, cx| {
+                let observation = cx.observe(&message.counter, |display, counter, cx| {
+                    let name = counter.read(cx).name.clone();
+                    display.recompute(Some(&name), cx);
+                });
+                display.inputs.push((message.counter.clone(), observation));
+                display.recompute(None, cx);
+            }),
+            cx.subscribe_bus(|display: &mut Self, message: &CounterRemoved, cx| {
+                // 丢弃 observe，之后这个计数器的变化不再计入总计
+                display.inputs.retain(|(counter, _)| counter.entity_id() != message.counter);
+                display.recompute(None, cx);
+            }),
+        ];
+        Self {
+            inputs: Vec::new(),
+            total: 0,
+            last_change: "等待变化...".to_string(),
+            _subscriptions: subscriptions,
+        }
+    }
+
+    /// 从输入的当前状态重新计算总计；source 是触发重新计算的计数器名称
+    fn recompute(&mut self, source: Option<&str>, cx: &mut Context<Self>) {
+        let total = self.inputs.iter().map(|(counter, _)| counter.read(cx).count).sum();
+        let delta = total - self.total;
+        self.total = total;
+        if delta != 0 {
+            self.last_change = match source {
+                Some(name) => format!("{}: {:+}", name, delta),
+                None => format!("计数器列表变化: {:+}", delta),
+            };
+        }
+        cx.notify();
+    }
+}
+
+impl Render for TotalDisplay {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .p_4()
+            .rounded_lg()
+            .bg(rgb(0xFEF3C7))
+            .border_2()
+            .border_color(rgb(0xF59E0B))
+            .child(
+                div()
+                    .text_lg()
+                    .font_weight(FontWeight::BOLD)
+                    .text_color(rgb(0x92400E))
+                    .child("📊 观察者面板"),
+            )
+            .child(
+                div()
+                    .flex()
+                    .gap_4()
+                    .child(
+                        div()
+                            .text_color(rgb(0x78350F))
+                            .child(format!("总计: {}（{} 个计数器之和）", self.total, self.inputs.len())),
+                    )
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(rgb(0xA16207))
+                            .child(format!("最后变化: {}", self.last_change)),
+                    ),
+            )
+    }
+}
+
+// ============================================================================
+// 第四部分：父组件/根视图
+// ============================================================================
+
+/// 事件日志最多保存的条目数
+const LOG_CAPACITY: usize = 200;
+
+/// 事件日志"导出"写入的文件（相对于当前目录）
+const DEFAULT_LOG_FILE: &str = "state_management_log.txt";
+
+// 日志来源，可以在日志面板中按来源过滤
+const LOG_COUNTER: &str = "计数器";
+const LOG_LIST: &str = "列表";
+const LOG_OPERATION: &str = "操作";
+const LOG_HISTORY: &str = "历史";
+
+/// 新计数器的背景颜色，按创建顺序循环使用
+const COUNTER_COLORS: [u32; 4] = [0xBFDBFEFF, 0xBBF7D0FF, 0xFDE68AFF, 0xFBCFE8FF];
+
+/// 按创建顺序生成计数器名称：A、B ... Z、A2、B2 ...
+fn counter_name(serial: usize) -> String {
+    let letter = char::from(b'A' + (serial % 26) as u8);
+    match serial / 26 {
+        0 => letter.to_string(),
+        round => format!("{}{}", letter, round + 1),
+    }
+}
+
+/// 列表中的一个计数器
+///
+/// 订阅保存在这里而不是父组件的公共列表中：
+/// 计数器从列表中移除时，订阅随 CounterSlot 一起被丢弃，不会再收到它的事件
+struct CounterSlot {
+    /// 创建序号，用于生成唯一的元素 id（名称可以被修改，不能用作 id）
+    serial: usize,
+    counter: Entity<ChildCounter>,
+    /// 重命名输入框，按 Enter 提交
+    name_input: Entity<TextInput>,
+    _subscriptions: Vec<Subscription>,
+}
+
+/// 主应用视图 - 父组件
+/// 展示如何：
+/// 1. 持有子组件的 Entity 句柄（数量在运行时变化）
+/// 2. 订阅子组件的事件
+/// 3. 观察子组件的状态变化
+/// 4. 协调多个子组件之间的通信
+pub struct StateManagementApp {
+    /// 子计数器列表（Entity<T> 是对实体的引用）
+    counters: Vec<CounterSlot>,
+    /// 已经创建过的计数器数量，用于生成名称、颜色和 id
+    next_serial: usize,
+    /// 总计显示组件
+    total_display: Entity<TotalDisplay>,
+
+    /// 父组件自身的状态：记录总变化次数
+    total_changes: i32,
+    /// 事件日志：计数器变化、列表增删、同步/重置、撤销/重做
+    logs: Entity<LogPanel>,
+    /// 撤销/重做历史：只记录计数器的修改，总计随之重新计算
+    history: UndoHistory,
+    /// 根视图的焦点，让撤销/重做快捷键在没有其他元素聚焦时也能生效
+    focus_handle: FocusHandle,
+}
+
+impl StateManagementApp {
+    pub fn new(cx: &mut Context<Self>) -> Self {
+        // 1. 创建观察者组件：它自己订阅事件总线，父组件不需要把计数器交给它
+        let total_display = cx.new(TotalDisplay::new);
+
+        let logs = cx.new(|cx| LogPanel::new(LOG_CAPACITY, cx).export_path(DEFAULT_LOG_FILE));
+
+        let mut app = Self {
+            counters: Vec::new(),
+            next_serial: 0,
+            total_display,
+            total_changes: 0,
+            logs,
+            history: UndoHistory::new(),
+            focus_handle: cx.focus_handle(),
+        };
+
+        // 2. 创建初始的两个子组件 - 通过构造函数参数传递初始状态（父传子）
+        app.add_counter(0, cx);
+        app.add_counter(10, cx);
+        app
+    }
+
+    /// 添加一个计数器，并为它创建订阅
+    fn add_counter(&mut self, initial_count: i32, cx: &mut Context<Self>) -> Entity<ChildCounter> {
+        let serial = self.next_serial;
+        self.next_serial += 1;
+        let name = counter_name(serial);
+        let color = rgba(COUNTER_COLORS[serial % COUNTER_COLORS.len()]);
+        let counter = cx.new(|cx| ChildCounter::new(name.clone(), initial_count, color, cx));
+        let name_input = cx.new(|cx| TextInput::new(cx).placeholder("新名称，Enter 确认"));
+
+        let subscriptions = vec![
+            // 订阅子组件事件（子传父的事件方式）
+            // subscribe 用于监听 EventEmitter 发出的事件，emitter 是发出事件的实体
+            cx.subscribe(&counter, |parent, emitter, event: &CounterChangedEvent, cx| {
+                parent.on_counter_changed(&emitter, event, cx);
+            }),
+            // 使用 observe 观察子组件（另一种子传父方式）
+            // observe 用于监听 notify() 调用，而不是特定事件
+            cx.observe(&counter, |_parent, counter, cx| {
+                // 可以读取计数器的当前状态
+                let counter = counter.read(cx);
+                println!("观察到计数器 {} 变化: {}", counter.name, counter.count);
+            }),
+            // 重命名输入框提交时修改子组件的名称
+            cx.subscribe(&name_input, {
+                let counter = counter.downgrade();
+                move |parent, input, event: &TextInputEvent, cx| {
+                    if let TextInputEvent::Submitted(name) = event
+                        && let Some(counter) = counter.upgrade()
+                    {
+                        parent.rename_counter(&counter, name.trim(), cx);
+                        input.update(cx, |input, cx| input.clear(cx));
+                    }
+                }
+            }),
+        ];
+
+        self.counters.push(CounterSlot {
+            serial,
+            counter: counter.clone(),
+            name_input,
+            _subscriptions: subscriptions,
+        });
+        cx.publish(CounterAdded {
+            counter: counter.clone(),
+        });
+        cx.notify();
+        counter
+    }
+
+    /// 移除计数器：丢弃 CounterSlot 即取消它的订阅，实体随最后一个 Entity 句柄释放
+    fn remove_counter(&mut self, counter: &Entity<ChildCounter>, cx: &mut Context<Self>) {
+        let Some(ix) = self.counters.iter().position(|slot| slot.counter == *counter) else {
+            return;
+        };
+        let slot = self.counters.remove(ix);
+        let (name, count) = {
+            let counter = slot.counter.read(cx);
+            (counter.name.clone(), counter.count)
+        };
+
+        // 总计不再包含这个计数器
+        cx.publish(CounterRemoved {
+            counter: slot.counter.entity_id(),
+        });
+        self.push_log(LogLevel::Warn, LOG_LIST, format!("删除: {} ({})", name, count), cx);
+        cx.notify();
+    }
+
+    /// 重命名计数器 - 父组件修改子组件状态
+    fn rename_counter(&mut self, counter: &Entity<ChildCounter>, name: &str, cx: &mut Context<Self>) {
+        if name.is_empty() {
+            return;
+        }
+        let old_name = counter.update(cx, |counter, cx| {
+            cx.notify();
+            std::mem::replace(&mut counter.name, name.to_string())
+        });
+        self.push_log(LogLevel::Info, LOG_LIST, format!("重命名: {} -> {}", old_name, name), cx);
+        cx.notify();
+    }
+
+    /// 处理子计数器的变化事件：更新父组件状态，并记录为可撤销的一步
+    ///
+    /// 总计不在这里处理：TotalDisplay 用 cx.observe 观察各计数器，自己重新计算；
+    /// 事件总线只传递 CounterAdded / CounterRemoved，告诉它观察哪些计数器
+    fn on_counter_changed(
+        &mut self,
+        counter: &Entity<ChildCounter>,
+        event: &CounterChangedEvent,
+        cx: &mut Context<Self>,
+    ) {
+        // 名称可能被修改过，每次都从子组件读取
+        let name = counter.read(cx).name.clone();
+
+        // 更新父组件自身状态
+        self.total_changes += 1;
+        self.push_log(
+            LogLevel::Info,
+            LOG_COUNTER,
+            format!("{}: {} ({:+})", name, event.new_value, event.delta),
+            cx,
+        );
+
+        // 子组件已经修改了自己，用事件中的数据还原出修改前的值
+        let mut transaction = Transaction::new(format!("修改计数器 {}", name))
+            // 连续点击同一个计数器的按钮合并为一步撤销（按实体分组，重命名不影响合并）
+            .group(format!("counter-{}", counter.entity_id()));
+        transaction.record(counter, event.new_value - event.delta, event.new_value);
+        self.history.push(transaction);
+
+        cx.notify(); // 通知父视图需要重新渲染
+    }
+
+    /// 追加日志，超出容量时日志面板丢弃最早的条目
+    fn push_log(&mut self, level: LogLevel, source: &'static str, entry: String, cx: &mut Context<Self>) {
+        self.logs.update(cx, |logs, cx| logs.log(level, source, entry, cx));
+    }
+
+    /// 重置所有计数器 - 父组件修改子组件状态
+    ///
+    /// 所有实体的修改放在同一个事务中，一次撤销就能全部恢复
+    fn reset_all(&mut self, cx: &mut Context<Self>) {
+        let mut transaction = Transaction::new("重置所有");
+
+        // 使用 update 方法修改子组件状态。计数器的 count 要等 Store 的事件处理后才更新，
+        // 所以不用 transaction.update 读取修改后的快照，而是按修改前后的值记录
+        for slot in &self.counters {
+            let before = slot.counter.read(cx).count;
+            slot.counter.update(cx, |counter, cx| counter.set_count(0, cx));
+            transaction.record(&slot.counter, before, 0);
+        }
+
+        self.history.push(transaction);
+        self.total_changes = 0;
+        self.logs.update(cx, |logs, cx| logs.clear(cx));
+        cx.notify();
+    }
+
+    /// 同步计数器 - 演示兄弟组件通信（通过事件总线）
+    ///
+    /// 第一个计数器在总线上发布自己的值，其余计数器收到后修改自己；
+    /// 父组件只负责记录撤销历史和日志，不再逐个 update 兄弟组件
+    fn sync_counters(&mut self, cx: &mut Context<Self>) {
+        let Some((first, rest)) = self.counters.split_first() else {
+            return;
+        };
+        let (source, value) = {
+            let first = first.counter.read(cx);
+            (first.name.clone(), first.count)
+        };
+
+        // 修改由兄弟组件自己完成，这里按修改前后的值补记
+        let mut transaction = Transaction::new(format!("同步 <- {}", source));
+        let mut logs = Vec::new();
+        for slot in rest {
+            let target = slot.counter.read(cx);
+            logs.push(format!("同步: {} <- {} ({})", target.name, source, value));
+            transaction.record(&slot.counter, target.count, value);
+        }
+        self.history.push(transaction);
+        first.counter.update(cx, |counter, cx| counter.broadcast(cx));
+
+        for log in logs {
+            self.push_log(LogLevel::Info, LOG_OPERATION, log, cx);
+        }
+        cx.notify();
+    }
+
+    fn undo(&mut self, _: &Undo, _window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(label) = self.history.undo(cx) {
+            self.push_log(LogLevel::Info, LOG_HISTORY, format!("撤销: {}", label), cx);
+            cx.notify();
+        }
+    }
+
+    fn redo(&mut self, _: &Redo, _window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(label) = self.history.redo(cx) {
+            self.push_log(LogLevel::Info, LOG_HISTORY, format!("重做: {}", label), cx);
+            cx.notify();
+        }
+    }
+
+    /// 一行：子组件 + 父组件提供的重命名/删除控件
+    fn render_counter_slot(&self, slot: &CounterSlot, cx: &mut Context<Self>) -> Div {
+        let counter = slot.counter.clone();
+        div()
+            .flex()
+            .items_center()
+            .gap_3()
+            // 渲染子组件 - 直接将 Entity<T> 作为 child
+            .child(div().flex_1().child(slot.counter.clone()))
+            .child(div().w(px(160.0)).child(slot.name_input.clone()))
+            .child(
+                StyledButton::new(
+                    SharedString::from(format!("remove-counter-{}", slot.serial)),
+                    "删除",
+                )
+                .variant(ButtonVariant::Danger)
+                .size(ButtonSize::Small)
+                .on_click(cx.listener(move |view, _event, _window, cx| {
+                    view.remove_counter(&counter, cx);
+                })),
+            )
+    }
+}
+
+impl Focusable for StateManagementApp {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for StateManagementApp {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let undo_label = match self.history.undo_label() {
+            Some(label) => format!("↶ 撤销「{}」 ({})", label, window.keystroke_text_for(&Undo)),
+            None => "↶ 撤销".to_string(),
+        };
+        let redo_label = match self.history.redo_label() {
+            Some(label) => format!("↷ 重做「{}」 ({})", label, window.keystroke_text_for(&Redo)),
+            None => "↷ 重做".to_string(),
+        };
+        let counter_rows: Vec<_> = self
+            .counters
+            .iter()
+            .map(|slot| self.render_counter_slot(slot, cx))
+            .collect();
+
+        div()
+            .size_full()
+            .flex()
+            .flex_col()
+            // history::init 的快捷键谓词需要至少一层 key_context
+            .key_context("StateManagementApp")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::undo))
+            .on_action(cx.listener(Self::redo))
+            .bg(rgb(0xF1F5F9))
+            .p_6()
+            .gap_6()
+            // 标题
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_2()
+                    .child(
+                        div()
+                            .text_2xl()
+                            .font_weight(FontWeight::EXTRA_BOLD)
+                            .text_color(rgb(0x1E293B))
+                            .child("第三章：状态管理 🔄"),
+                    )
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(rgb(0x64748B))
+                            .child("学习父子组件通信、事件系统和观察者模式"),
+                    ),
+            )
+            // 主内容区
+            .child(
+                div()
+                    .flex()
+                    .gap_6()
+                    // 左侧：子组件区域
+                    .child(
+                        div()
+                            .flex_1()
+                            .flex()
+                            .flex_col()
+                            .gap_4()
+                            .child(
+                                div()
+                                    .text_lg()
+                                    .font_weight(FontWeight::SEMIBOLD)
+                                    .text_color(rgb(0x475569))
+                                    .child("子组件（点击按钮修改状态）"),
+                            )
+                            .children(counter_rows)
+                            .child(
+                                StyledButton::new("add-counter", "➕ 添加计数器")
+                                    .variant(ButtonVariant::Success)
+                                    .rounded_lg()
+                                    .on_click(cx.listener(|view, _event, _window, cx| {
+                                        let counter = view.add_counter(0, cx);
+                                        let name = counter.read(cx).name.clone();
+                                        view.push_log(LogLevel::Info, LOG_LIST, format!("添加: {}", name), cx);
+                                    })),
+                            ),
+                    )
+                    // 右侧：父组件状态显示
+                    .child(
+                        div()
+                            .flex_1()
+                            .flex()
+                            .flex_col()
+                            .gap_4()
+                            .child(
+                                div()
+                                    .text_lg()
+                                    .font_weight(FontWeight::SEMIBOLD)
+                                    .text_color(rgb(0x475569))
+                                    .child("父组件状态（通过事件接收）"),
+                            )
+                            .child(
+                                div()
+                                    .flex()
+                                    .flex_col()
+                                    .gap_2()
+                                    .p_4()
+                                    .rounded_lg()
+                                    .bg(rgb(0xE0E7FF))
+                                    .border_2()
+                                    .border_color(rgb(0x6366F1))
+                                    .child(
+                                        div()
+                                            .text_color(rgb(0x3730A3))
+                                            .child(format!("总变化次数: {}", self.total_changes)),
+                                    )
+                                    .child(
+                                        div()
+                                            .text_sm()
+                                            .text_color(rgb(0x4F46E5))
+                                            .child("事件日志:"),
+                                    )
+                                    .child(div().h(px(220.0)).child(self.logs.clone())),
+                            ),
+                    ),
+            )
+            // 观察者面板
+            .child(self.total_display.clone())
+            // 操作按钮
+            .child(
+                div()
+                    .flex()
+                    .gap_4()
+                    .child(
+                        StyledButton::new("reset-all", "🔄 重置所有")
+                            .variant(ButtonVariant::Danger)
+                            .rounded_lg()
+                            .on_click(cx.listener(|view, _event, _window, cx| {
+                                view.reset_all(cx);
+                            })),
+                    )
+                    .child(
+                        // 紫色区别于其他操作：同步会改写除第一个以外的所有计数器
+                        StyledButton::new("sync", "🔗 同步为第一个")
+                            .colors(rgb(0x8B5CF6), rgb(0x7C3AED))
+                            .rounded_lg()
+                            .on_click(cx.listener(|view, _event, _window, cx| {
+                                view.sync_counters(cx);
+                            })),
+                    )
+                    .child(
+                        StyledButton::new("undo", undo_label)
+                            .variant(ButtonVariant::Secondary)
+                            .rounded_lg()
+                            .disabled(!self.history.can_undo())
+                            .on_click(cx.listener(|view, _event, window, cx| {
+                                view.undo(&Undo, window, cx);
+                            })),
+                    )
+                    .child(
+                        StyledButton::new("redo", redo_label)
+                            .variant(ButtonVariant::Secondary)
+                            .rounded_lg()
+                            .disabled(!self.history.can_redo())
+                            .on_click(cx.listener(|view, _event, window, cx| {
+                                view.redo(&Redo, window, cx);
+                            })),
+                    ),
+            )
+            // 知识点说明
+            .child(
+                div()
+                    .mt_4()
+                    .p_4()
+                    .rounded_lg()
+                    .bg(rgb(0xFFFBEB))
+                    .border_1()
+                    .border_color(rgb(0xFCD34D))
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(rgb(0x92400E))
+                            .child("💡 本示例演示：1) 父传子：构造函数参数 2) 子传父：EventEmitter + subscribe 3) 观察变化：observe 4) 兄弟通信：事件总线 5) 撤销/重做：Transaction 记录跨实体的修改 6) 动态列表：每个子组件的订阅随它一起创建和丢弃 7) Store：计数只通过 dispatch → reduce 修改"),
+                    ),
+            )
+    }
+}
+
+// ============================================================================
+// 测试：在无界面的 TestAppContext 中构造视图、分发点击并断言状态
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    // 不能 use super::*：gpui::* 中的 test 宏会遮蔽内置的 #[test]
+    use super::{
+        counter_name, init, reduce_counter, ChildCounter, CounterAdded, CounterChangedEvent,
+        CounterMsg, CounterRemoved, CounterState, Redo, StateManagementApp, SyncCounters,
+        TotalDisplay, Undo,
+    };
+    use gpui::{rgba, App, AppContext, Entity, Focusable, Modifiers, TestAppContext, VisualTestContext};
+    use gpui_components::EventBus;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::time::Duration;
+
+    /// (A, B, 总计)
+    fn counts(app: &Entity<StateManagementApp>, cx: &mut VisualTestContext) -> (i32, i32, i32) {
+        app.read_with(cx, |app, cx| {
+            (
+                app.counters[0].counter.read(cx).count,
+                app.counters[1].counter.read(cx).count,
+                app.total_display.read(cx).total,
+            )
+        })
+    }
+
+    /// 日志面板中的全部消息，从旧到新
+    fn event_log(app: &StateManagementApp, cx: &App) -> Vec<String> {
+        app.logs.read(cx).entries().map(|entry| entry.message.to_string()).collect()
+    }
+
+    /// 初始的两个计数器 A、B
+    fn counters_a_b(
+        app: &Entity<StateManagementApp>,
+        cx: &mut VisualTestContext,
+    ) -> (Entity<ChildCounter>, Entity<ChildCounter>) {
+        app.read_with(cx, |app, _| (app.counters[0].counter.clone(), app.counters[1].counter.clone()))
+    }
+
+    #[gpui::test]
+    fn child_counter_emits_change_events(cx: &mut TestAppContext) {
+        let counter = cx.new(|cx| ChildCounter::new("A", 5, rgba(0xBFDBFEFF), cx));
+
+        // 记录 ChildCounter 发出的每个事件
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let _subscription = cx.update(|cx| {
+            let events = events.clone();
+            cx.subscribe(&counter, move |_, event: &CounterChangedEvent, _| {
+                events.borrow_mut().push((event.new_value, event.delta));
+            })
+        });
+
+        counter.update(cx, |counter, cx| {
+            counter.increment(cx);
+            counter.increment(cx);
+            counter.decrement(cx);
+        });
+
+        assert_eq!(counter.read_with(cx, |counter, _| counter.count), 6);
+        assert_eq!(*events.borrow(), vec![(6, 1), (7, 1), (6, -1)]);
+    }
+
+    #[gpui::test]
+    fn clicking_child_counter_buttons(cx: &mut TestAppContext) {
+        let (counter, cx) =
+            cx.add_window_view(|_, cx| ChildCounter::new("A", 0, rgba(0xBFDBFEFF), cx));
+
+        // StyledButton 在 test-support 下带有 debug_selector("button-<id>")
+        let inc = cx.debug_bounds("button-inc").expect("找不到 + 按钮");
+        cx.simulate_click(inc.center(), Modifiers::none());
+        cx.simulate_click(inc.center(), Modifiers::none());
+        assert_eq!(counter.read_with(cx, |counter, _| counter.count), 2);
+
+        let dec = cx.debug_bounds("button-dec").expect("找不到 - 按钮");
+        cx.simulate_click(dec.center(), Modifiers::none());
+        assert_eq!(counter.read_with(cx, |counter, _| counter.count), 1);
+    }
+
+    #[gpui::test]
+    fn parent_receives_child_events(cx: &mut TestAppContext) {
+        let (app, cx) = cx.add_window_view(|_, cx| StateManagementApp::new(cx));
+        let (counter_a, counter_b) = counters_a_b(&app, cx);
+
+        counter_a.update(cx, |counter, cx| counter.increment(cx));
+        counter_b.update(cx, |counter, cx| counter.decrement(cx));
+
+        app.read_with(cx, |app, cx| {
+            assert_eq!(app.total_changes, 2);
+            assert_eq!(event_log(app, cx), vec!["A: 1 (+1)", "B: 9 (-1)"]);
+            assert_eq!(app.total_display.read(cx).total, 10);
+            assert_eq!(app.total_display.read(cx).last_change, "B: -1");
+        });
+    }
+
+    #[gpui::test]
+    fn sync_counters_copies_a_into_b(cx: &mut TestAppContext) {
+        let (app, cx) = cx.add_window_view(|_, cx| StateManagementApp::new(cx));
+        let (counter_a, _) = counters_a_b(&app, cx);
+
+        counter_a.update(cx, |counter, cx| {
+            for _ in 0..3 {
+                counter.increment(cx);
+            }
+        });
+        app.update(cx, |app, cx| app.sync_counters(cx));
+
+        app.read_with(cx, |app, cx| {
+            assert_eq!(app.counters[1].counter.read(cx).count, 3);
+            assert_eq!(event_log(app, cx).last().map(String::as_str), Some("同步: B <- A (3)"));
+        });
+    }
+
+    #[gpui::test]
+    fn reset_all_clears_children_and_log(cx: &mut TestAppContext) {
+        let (app, cx) = cx.add_window_view(|_, cx| StateManagementApp::new(cx));
+        let (counter_a, _) = counters_a_b(&app, cx);
+        counter_a.update(cx, |counter, cx| counter.increment(cx));
+
+        app.update(cx, |app, cx| app.reset_all(cx));
+
+        app.read_with(cx, |app, cx| {
+            assert!(app.counters.iter().all(|slot| slot.counter.read(cx).count == 0));
+            assert_eq!(app.total_display.read(cx).total, 0);
+            assert_eq!(app.total_changes, 0);
+            assert!(event_log(app, cx).is_empty());
+        });
+    }
+
+    #[gpui::test]
+    fn undo_and_redo_counter_changes(cx: &mut TestAppContext) {
+        let (app, cx) = cx.add_window_view(|_, cx| StateManagementApp::new(cx));
+        // 关闭合并，每次点击都是单独的一步
+        app.update(cx, |app, _| app.history.set_group_interval(Duration::ZERO));
+        let (counter_a, counter_b) = counters_a_b(&app, cx);
+
+        counter_a.update(cx, |counter, cx| counter.increment(cx));
+        counter_a.update(cx, |counter, cx| counter.increment(cx));
+        counter_b.update(cx, |counter, cx| counter.decrement(cx));
+        assert_eq!(counts(&app, cx), (2, 9, 11));
+
+        app.update_in(cx, |app, window, cx| app.undo(&Undo, window, cx));
+        assert_eq!(counts(&app, cx), (2, 10, 12));
+        app.update_in(cx, |app, window, cx| app.undo(&Undo, window, cx));
+        assert_eq!(counts(&app, cx), (1, 10, 11));
+
+        app.update_in(cx, |app, window, cx| app.redo(&Redo, window, cx));
+        assert_eq!(counts(&app, cx), (2, 10, 12));
+        app.read_with(cx, |app, cx| {
+            assert_eq!(event_log(app, cx).last().map(String::as_str), Some("重做: 修改计数器 A"));
+            assert_eq!(app.history.redo_label().map(|label| label.as_ref()), Some("修改计数器 B"));
+        });
+
+        // 新的修改清空重做栈
+        counter_b.update(cx, |counter, cx| counter.increment(cx));
+        assert!(!app.read_with(cx, |app, _| app.history.can_redo()));
+    }
+
+    #[gpui::test]
+    fn rapid_edits_are_grouped_into_one_step(cx: &mut TestAppContext) {
+        let (app, cx) = cx.add_window_view(|_, cx| StateManagementApp::new(cx));
+        let (counter_a, counter_b) = counters_a_b(&app, cx);
+
+        for _ in 0..3 {
+            counter_a.update(cx, |counter, cx| counter.increment(cx));
+        }
+        // 换了一个计数器，开始新的一步
+        counter_b.update(cx, |counter, cx| counter.increment(cx));
+        assert_eq!(counts(&app, cx), (3, 11, 14));
+
+        app.update_in(cx, |app, window, cx| app.undo(&Undo, window, cx));
+        assert_eq!(counts(&app, cx), (3, 10, 13));
+        app.update_in(cx, |app, window, cx| app.undo(&Undo, window, cx));
+        assert_eq!(counts(&app, cx), (0, 10, 10));
+        assert!(!app.read_with(cx, |app, _| app.history.can_undo()));
+    }
+
+    #[gpui::test]
+    fn undo_reset_all_with_keyboard(cx: &mut TestAppContext) {
+        cx.update(init);
+        let (app, cx) = cx.add_window_view(|_, cx| StateManagementApp::new(cx));
+        cx.update(|window, cx| app.focus_handle(cx).focus(window));
+        let (counter_a, _) = counters_a_b(&app, cx);
+
+        counter_a.update(cx, |counter, cx| counter.increment(cx));
+        app.update(cx, |app, cx| app.reset_all(cx));
+        assert_eq!(counts(&app, cx), (0, 0, 0));
+
+        // 一次撤销同时恢复两个计数器，总计随之重新计算
+        cx.simulate_keystrokes("secondary-z");
+        assert_eq!(counts(&app, cx), (1, 10, 11));
+        // 撤销逆序恢复（先 B 后 A），第一次重新计算时两个计数器都已恢复，
+        // 整个变化归到 B 上，A 的通知不再改变总计
+        assert_eq!(
+            app.read_with(cx, |app, cx| app.total_display.read(cx).last_change.clone()),
+            "B: +11"
+        );
+
+        cx.simulate_keystrokes("secondary-shift-z");
+        assert_eq!(counts(&app, cx), (0, 0, 0));
+    }
+
+    #[gpui::test]
+    fn undo_shortcut_is_ignored_in_text_input(cx: &mut TestAppContext) {
+        cx.update(init);
+        let (app, cx) = cx.add_window_view(|_, cx| StateManagementApp::new(cx));
+        let (counter_a, _) = counters_a_b(&app, cx);
+        counter_a.update(cx, |counter, cx| counter.increment(cx));
+        assert_eq!(counts(&app, cx), (1, 10, 11));
+
+        // 焦点在重命名输入框中：Ctrl+Z 不会撤销计数器的修改
+        let input = app.read_with(cx, |app, _| app.counters[0].name_input.clone());
+        cx.update(|window, cx| input.focus_handle(cx).focus(window));
+        cx.simulate_input("收入");
+        cx.simulate_keystrokes("secondary-z");
+        assert_eq!(counts(&app, cx), (1, 10, 11));
+        assert!(app.read_with(cx, |app, _| app.history.can_undo()));
+
+        // 焦点回到应用上，快捷键照常撤销
+        cx.update(|window, cx| app.focus_handle(cx).focus(window));
+        cx.simulate_keystrokes("secondary-z");
+        assert_eq!(counts(&app, cx), (0, 10, 10));
+    }
+
+    #[gpui::test]
+    fn undo_button_reverts_sync(cx: &mut TestAppContext) {
+        let (app, cx) = cx.add_window_view(|_, cx| StateManagementApp::new(cx));
+        app.update(cx, |app, cx| app.sync_counters(cx));
+        assert_eq!(counts(&app, cx), (0, 0, 0));
+
+        let undo = cx.debug_bounds("button-undo").expect("找不到撤销按钮");
+        cx.simulate_click(undo.center(), Modifiers::none());
+        assert_eq!(counts(&app, cx), (0, 10, 10));
+    }
+
+    #[gpui::test]
+    fn counter_names_follow_creation_order(_cx: &mut TestAppContext) {
+        assert_eq!(counter_name(0), "A");
+        assert_eq!(counter_name(25), "Z");
+        assert_eq!(counter_name(26), "A2");
+        assert_eq!(counter_name(53), "B3");
+    }
+
+    #[gpui::test]
+    fn add_rename_and_remove_counters(cx: &mut TestAppContext) {
+        cx.update(init);
+        let (app, cx) = cx.add_window_view(|_, cx| StateManagementApp::new(cx));
+
+        // 添加的计数器同样会被订阅，计入总计
+        let add = cx.debug_bounds("button-add-counter").expect("找不到添加按钮");
+        cx.simulate_click(add.center(), Modifiers::none());
+        let counter_c = app.read_with(cx, |app, _| {
+            assert_eq!(app.counters.len(), 3);
+            app.counters[2].counter.clone()
+        });
+        counter_c.update(cx, |counter, cx| counter.increment(cx));
+        counter_c.update(cx, |counter, cx| counter.increment(cx));
+        app.read_with(cx, |app, cx| {
+            assert_eq!(app.total_display.read(cx).total, 12);
+            assert_eq!(event_log(app, cx), vec!["添加: C", "C: 1 (+1)", "C: 2 (+1)"]);
+        });
+
+        // 在重命名输入框中输入新名称并按 Enter
+        let input = app.read_with(cx, |app, _| app.counters[2].name_input.clone());
+        cx.update(|window, cx| input.focus_handle(cx).focus(window));
+        cx.simulate_input("收入");
+        cx.simulate_keystrokes("enter");
+        assert_eq!(counter_c.read_with(cx, |counter, _| counter.name.clone()), "收入");
+        assert!(input.read_with(cx, |input, _| input.text().is_empty()));
+        counter_c.update(cx, |counter, cx| counter.decrement(cx));
+        app.read_with(cx, |app, cx| {
+            assert_eq!(event_log(app, cx).last().map(String::as_str), Some("收入: 1 (-1)"));
+        });
+
+        // 删除后总计减去它的值，它的订阅也随之丢弃
+        let remove = cx.debug_bounds("button-remove-counter-2").expect("找不到删除按钮");
+        cx.simulate_click(remove.center(), Modifiers::none());
+        let changes = app.read_with(cx, |app, cx| {
+            assert_eq!(app.counters.len(), 2);
+            assert_eq!(app.total_display.read(cx).total, 10);
+            assert_eq!(event_log(app, cx).last().map(String::as_str), Some("删除: 收入 (1)"));
+            app.total_changes
+        });
+        counter_c.update(cx, |counter, cx| counter.increment(cx));
+        app.read_with(cx, |app, cx| {
+            assert_eq!(app.total_changes, changes);
+            assert_eq!(app.total_display.read(cx).total, 10);
+        });
+    }
+
+    #[gpui::test]
+    fn sync_and_reset_cover_every_counter(cx: &mut TestAppContext) {
+        let (app, cx) = cx.add_window_view(|_, cx| StateManagementApp::new(cx));
+        let counter_c = app.update(cx, |app, cx| app.add_counter(5, cx));
+        let (counter_a, _) = counters_a_b(&app, cx);
+        counter_a.update(cx, |counter, cx| counter.increment(cx));
+
+        app.update(cx, |app, cx| app.sync_counters(cx));
+        assert_eq!(counter_c.read_with(cx, |counter, _| counter.count), 1);
+        app.read_with(cx, |app, cx| {
+            let log = event_log(app, cx);
+            assert_eq!(log[log.len() - 2..], ["同步: B <- A (1)", "同步: C <- A (1)"]);
+        });
+
+        app.update(cx, |app, cx| app.reset_all(cx));
+        assert_eq!(counter_c.read_with(cx, |counter, _| counter.count), 0);
+
+        // 一次撤销恢复所有计数器
+        app.update_in(cx, |app, window, cx| app.undo(&Undo, window, cx));
+        assert_eq!(counts(&app, cx), (1, 1, 3));
+        assert_eq!(counter_c.read_with(cx, |counter, _| counter.count), 1);
+    }
+
+    #[gpui::test]
+    fn total_is_derived_from_current_counter_states(cx: &mut TestAppContext) {
+        let (app, cx) = cx.add_window_view(|_, cx| StateManagementApp::new(cx));
+        // 初始值也计入总计
+        assert_eq!(counts(&app, cx), (0, 10, 10));
+
+        // 同步不发出 CounterChangedEvent，总计仍然正确
+        let (counter_a, counter_b) = counters_a_b(&app, cx);
+        counter_a.update(cx, |counter, cx| counter.increment(cx));
+        app.update(cx, |app, cx| app.sync_counters(cx));
+        assert_eq!(counts(&app, cx), (1, 1, 2));
+
+        // 直接向计数器的 Store 发送消息，计数变化后总计同样会重新计算
+        let store_b = counter_b.read_with(cx, |counter, _| counter.store.clone());
+        store_b.update(cx, |store, cx| store.dispatch(CounterMsg::Set(7), cx));
+        assert_eq!(counts(&app, cx), (1, 7, 8));
+        assert_eq!(
+            app.read_with(cx, |app, cx| app.total_display.read(cx).last_change.clone()),
+            "B: +6"
+        );
+
+        // 添加、删除计数器时父组件发布 CounterAdded / CounterRemoved
+        let counter_c = app.update(cx, |app, cx| app.add_counter(5, cx));
+        assert_eq!(counts(&app, cx), (1, 7, 13));
+        app.update(cx, |app, cx| app.remove_counter(&counter_b, cx));
+        app.read_with(cx, |app, cx| {
+            let display = app.total_display.read(cx);
+            assert_eq!(display.total, 6);
+            let inputs: Vec<_> = display.inputs.iter().map(|(counter, _)| counter.clone()).collect();
+            assert_eq!(inputs, vec![counter_a.clone(), counter_c.clone()]);
+            assert_eq!(display.last_change, "计数器列表变化: -7");
+        });
+    }
+
+    #[gpui::test]
+    fn siblings_sync_through_the_event_bus(cx: &mut TestAppContext) {
+        let color = rgba(0xBFDBFEFF);
+        let a = cx.new(|cx| ChildCounter::new("A", 3, color, cx));
+        let b = cx.new(|cx| ChildCounter::new("B", 0, color, cx));
+        let c = cx.new(|cx| ChildCounter::new("C", 8, color, cx));
+        let count = |counter: &Entity<ChildCounter>, cx: &mut TestAppContext| {
+            counter.read_with(cx, |counter, _| counter.count)
+        };
+
+        // 没有父组件：A 发布的消息由 B、C 自己处理
+        a.update(cx, |counter, cx| counter.broadcast(cx));
+        assert_eq!((count(&a, cx), count(&b, cx), count(&c, cx)), (3, 3, 3));
+
+        // 计数器释放后它的订阅随之取消
+        assert_eq!(cx.read(EventBus::subscriber_count::<SyncCounters>), 3);
+        drop(c);
+        cx.update(|_| {});
+        assert_eq!(cx.read(EventBus::subscriber_count::<SyncCounters>), 2);
+    }
+
+    #[gpui::test]
+    fn total_display_follows_bus_messages(cx: &mut TestAppContext) {
+        let display = cx.new(TotalDisplay::new);
+        let counter = cx.new(|cx| ChildCounter::new("A", 2, rgba(0xBFDBFEFF), cx));
+
+        // 加入列表之前的变化不计入总计
+        counter.update(cx, |counter, cx| counter.increment(cx));
+        assert_eq!(display.read_with(cx, |display, _| display.total), 0);
+
+        // 加入时从计数器的当前值计算，之后观察它的每次 notify()
+        cx.update(|cx| EventBus::publish(CounterAdded { counter: counter.clone() }, cx));
+        assert_eq!(display.read_with(cx, |display, _| display.total), 3);
+        counter.update(cx, |counter, cx| counter.set_count(10, cx));
+        display.read_with(cx, |display, _| {
+            assert_eq!(display.total, 10);
+            assert_eq!(display.last_change, "A: +7");
+        });
+
+        // 移除后不再观察它
+        cx.update(|cx| EventBus::publish(CounterRemoved { counter: counter.entity_id() }, cx));
+        counter.update(cx, |counter, cx| counter.increment(cx));
+        assert_eq!(display.read_with(cx, |display, _| display.total), 0);
+
+        // TotalDisplay 释放后，总线上不再有它的订阅
+        assert_eq!(cx.read(EventBus::subscriber_count::<CounterAdded>), 1);
+        drop(display);
+        cx.update(|_| {});
+        assert_eq!(cx.read(EventBus::subscriber_count::<CounterAdded>), 0);
+    }
+
+    #[test]
+    fn counter_reducer_is_pure() {
+        let state = CounterState { count: 1 };
+        assert_eq!(reduce_counter(&state, &CounterMsg::Increment), CounterState { count: 2 });
+        assert_eq!(reduce_counter(&state, &CounterMsg::Decrement), CounterState { count: 0 });
+        assert_eq!(reduce_counter(&state, &CounterMsg::Set(7)), CounterState { count: 7 });
+        assert_eq!(state.count, 1);
+    }
+
+    #[gpui::test]
+    fn counter_changes_go_through_the_store(cx: &mut TestAppContext) {
+        let counter = cx.new(|cx| ChildCounter::new("A", 5, rgba(0xBFDBFEFF), cx));
+        let store = counter.read_with(cx, |counter, _| counter.store.clone());
+
+        counter.update(cx, |counter, cx| counter.increment(cx));
+        assert_eq!(store.read_with(cx, |store, _| store.state().count), 6);
+        assert_eq!(counter.read_with(cx, |counter, _| counter.count), 6);
+
+        // 计数器显示的值跟随 Store，无论消息从哪里发出
+        store.update(cx, |store, cx| store.dispatch(CounterMsg::Set(-2), cx));
+        assert_eq!(counter.read_with(cx, |counter, _| counter.count), -2);
+    }
+}
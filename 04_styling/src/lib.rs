@@ -0,0 +1,881 @@
+// 第四章：GPUI 样式系统
+// ======================
+// 本章学习 GPUI 中类似 Tailwind CSS 的样式系统：
+// 1. Flexbox 布局
+// 2. 尺寸与间距
+// 3. 颜色与背景
+// 4. 边框与圆角
+// 5. 阴影与透明度
+// 6. 文字样式
+// 7. 伪状态（hover、active）
+// 8. 条件样式（when）
+
+use gpui::prelude::FluentBuilder;
+use gpui::*;
+use gpui_components::{
+    ActiveTheme, ButtonSize, ButtonVariant, Card, CardVariant, StyledButton, Theme,
+    ThemeFileStatus,
+};
+
+// ============================================================================
+// 第一部分：按钮与卡片组件 - 来自共享组件库 gpui_components
+// ============================================================================
+//
+// StyledButton（ButtonVariant / ButtonSize）和 Card（CardVariant）
+// 定义在 components/ 中，所有章节共用同一份实现。
+// 它们实现了 RenderOnce，可以像 div() 一样直接作为 child 使用。
+
+// ============================================================================
+// 第二部分：主应用 - 展示完整的样式系统
+// ============================================================================
+
+pub struct StylingApp {
+    /// 当前选中的布局模式
+    layout_mode: LayoutMode,
+    /// 是否显示调试边框
+    show_debug: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum LayoutMode {
+    Row,
+    Column,
+    Wrap,
+    Grid,
+}
+
+impl StylingApp {
+    pub fn new(cx: &mut Context<Self>) -> Self {
+        // 主题文件热重载后替换全局 Theme，这里订阅变化以重新渲染
+        cx.observe_global::<Theme>(|_view, cx| cx.notify()).detach();
+
+        Self {
+            layout_mode: LayoutMode::Row,
+            show_debug: false,
+        }
+    }
+
+    /// 渲染 Flexbox 布局示例区域
+    fn render_flexbox_section(&self, theme: &Theme, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .flex()
+            .flex_col()
+            .gap_4()
+            .p_4()
+            .rounded_lg()
+            .bg(theme.surface)
+            .text_color(theme.text)
+            // 标题
+            .child(
+                div()
+                    .text_lg()
+                    .font_weight(FontWeight::BOLD)
+                    .text_color(theme.text)
+                    .child("📐 Flexbox 布局"),
+            )
+            // 布局模式切换按钮
+            .child(
+                div()
+                    .flex()
+                    .gap_2()
+                    .child(self.layout_button("Row", LayoutMode::Row, cx))
+                    .child(self.layout_button("Column", LayoutMode::Column, cx))
+                    .child(self.layout_button("Wrap", LayoutMode::Wrap, cx))
+                    .child(self.layout_button("Grid", LayoutMode::Grid, cx)),
+            )
+            // 布局展示区
+            .child(self.render_layout_demo(theme))
+    }
+
+    fn layout_button(
+        &self,
+        label: &'static str,
+        mode: LayoutMode,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let is_active = self.layout_mode == mode;
+        let id = format!("layout-{:?}", mode);
+
+        div()
+            // 和 StyledButton 一样，测试中用 cx.debug_bounds("layout-<Mode>") 找到按钮
+            .debug_selector({
+                let id = id.clone();
+                move || id
+            })
+            .id(SharedString::from(id))
+            .px_3()
+            .py_1()
+            .rounded_md()
+            .cursor_pointer()
+            .text_sm()
+            // 使用 when 进行条件样式
+            .when(is_active, |s| {
+                s.bg(rgb(0x3B82F6)).text_color(rgb(0xFFFFFF))
+            })
+            .when(!is_active, |s| {
+                s.bg(rgb(0xE2E8F0))
+                    .text_color(rgb(0x475569))
+                    .hover(|s| s.bg(rgb(0xCBD5E1)))
+            })
+            .child(label)
+            .on_click(cx.listener(move |view, _event, _window, cx| {
+                view.layout_mode = mode;
+                cx.notify();
+            }))
+    }
+
+    fn render_layout_demo(&self, theme: &Theme) -> impl IntoElement {
+        let items = vec![
+            ("A", rgb(0xFCA5A5)),
+            ("B", rgb(0xFCD34D)),
+            ("C", rgb(0x86EFAC)),
+            ("D", rgb(0x93C5FD)),
+            ("E", rgb(0xC4B5FD)),
+        ];
+
+        let mut container = div()
+            .min_h(px(150.0))
+            .p_4()
+            .rounded_lg()
+            .bg(theme.background)
+            .border_1()
+            .border_color(theme.border);
+
+        container = match self.layout_mode {
+            LayoutMode::Row => container
+                .flex()
+                .flex_row()
+                .gap_4()
+                .items_center(),
+
+            LayoutMode::Column => container
+                .flex()
+                .flex_col()
+                .gap_2()
+                .items_start(),
+
+            LayoutMode::Wrap => container
+                .flex()
+                .flex_row()
+                .flex_wrap()
+                .gap_2(),
+
+            LayoutMode::Grid => container
+                .grid()
+                .grid_cols(3)
+                .gap_2(),
+        };
+
+        container.children(items.into_iter().map(|(label, color)| {
+            div()
+                .flex()
+                .items_center()
+                .justify_center()
+                .w(px(60.0))
+                .h(px(60.0))
+                .rounded_md()
+                .bg(color)
+                .text_color(rgb(0x1F2937))
+                .font_weight(FontWeight::BOLD)
+                .child(label)
+        }))
+    }
+
+    /// 渲染按钮展示区
+    fn render_buttons_section(&self, theme: &Theme) -> impl IntoElement {
+        div()
+            .flex()
+            .flex_col()
+            .gap_4()
+            .p_4()
+            .rounded_lg()
+            .bg(theme.surface)
+            .text_color(theme.text)
+            .child(
+                div()
+                    .text_lg()
+                    .font_weight(FontWeight::BOLD)
+                    .text_color(theme.text)
+                    .child("🎨 按钮样式"),
+            )
+            // 按钮变体
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_3()
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(theme.subtext)
+                            .child("变体 (Variants)"),
+                    )
+                    .child(
+                        div()
+                            .flex()
+                            .gap_2()
+                            .flex_wrap()
+                            .child(StyledButton::new("btn-primary", "Primary").variant(ButtonVariant::Primary))
+                            .child(StyledButton::new("btn-secondary", "Secondary").variant(ButtonVariant::Secondary))
+                            .child(StyledButton::new("btn-outline", "Outline").variant(ButtonVariant::Outline))
+                            .child(StyledButton::new("btn-ghost", "Ghost").variant(ButtonVariant::Ghost))
+                            .child(StyledButton::new("btn-danger", "Danger").variant(ButtonVariant::Danger))
+                            .child(StyledButton::new("btn-success", "Success").variant(ButtonVariant::Success)),
+                    ),
+            )
+            // 按钮尺寸
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_3()
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(theme.subtext)
+                            .child("尺寸 (Sizes)"),
+                    )
+                    .child(
+                        div()
+                            .flex()
+                            .gap_2()
+                            .items_center()
+                            .child(StyledButton::new("btn-small", "Small").size(ButtonSize::Small))
+                            .child(StyledButton::new("btn-medium", "Medium").size(ButtonSize::Medium))
+                            .child(StyledButton::new("btn-large", "Large").size(ButtonSize::Large)),
+                    ),
+            )
+            // 禁用状态
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_3()
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(theme.subtext)
+                            .child("状态 (States)"),
+                    )
+                    .child(
+                        div()
+                            .flex()
+                            .gap_2()
+                            .child(StyledButton::new("btn-normal", "Normal"))
+                            .child(StyledButton::new("btn-disabled", "Disabled").disabled(true)),
+                    ),
+            )
+    }
+
+    /// 渲染文字样式区
+    fn render_text_section(&self, theme: &Theme) -> impl IntoElement {
+        div()
+            .flex()
+            .flex_col()
+            .gap_4()
+            .p_4()
+            .rounded_lg()
+            .bg(theme.surface)
+            .text_color(theme.text)
+            .child(
+                div()
+                    .text_lg()
+                    .font_weight(FontWeight::BOLD)
+                    .text_color(theme.text)
+                    .child("✍️ 文字样式"),
+            )
+            // 字体大小
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_2()
+                    .child(div().text_xs().child("text_xs - 超小号文字"))
+                    .child(div().text_sm().child("text_sm - 小号文字"))
+                    .child(div().text_base().child("text_base - 基准文字"))
+                    .child(div().text_lg().child("text_lg - 大号文字"))
+                    .child(div().text_xl().child("text_xl - 超大号文字"))
+                    .child(div().text_2xl().child("text_2xl - 特大号文字"))
+                    .child(div().text_3xl().child("text_3xl - 巨大号文字")),
+            )
+            // 字体粗细
+            .child(
+                div()
+                    .flex()
+                    .flex_wrap()
+                    .gap_4()
+                    .mt_2()
+                    .child(div().font_weight(FontWeight::THIN).child("Thin"))
+                    .child(div().font_weight(FontWeight::LIGHT).child("Light"))
+                    .child(div().font_weight(FontWeight::NORMAL).child("Normal"))
+                    .child(div().font_weight(FontWeight::MEDIUM).child("Medium"))
+                    .child(div().font_weight(FontWeight::SEMIBOLD).child("Semibold"))
+                    .child(div().font_weight(FontWeight::BOLD).child("Bold"))
+                    .child(div().font_weight(FontWeight::EXTRA_BOLD).child("Extra Bold")),
+            )
+            // 文字装饰
+            .child(
+                div()
+                    .flex()
+                    .gap_4()
+                    .mt_2()
+                    .child(div().italic().child("Italic 斜体"))
+                    .child(div().underline().child("Underline 下划线"))
+                    .child(div().line_through().child("Strikethrough 删除线")),
+            )
+            // 文字颜色
+            .child(
+                div()
+                    .flex()
+                    .gap_4()
+                    .mt_2()
+                    .child(div().text_color(rgb(0xEF4444)).child("Red"))
+                    .child(div().text_color(rgb(0xF59E0B)).child("Orange"))
+                    .child(div().text_color(rgb(0x22C55E)).child("Green"))
+                    .child(div().text_color(rgb(0x3B82F6)).child("Blue"))
+                    .child(div().text_color(rgb(0x8B5CF6)).child("Purple")),
+            )
+    }
+
+    /// 渲染卡片展示区
+    fn render_cards_section(&self, theme: &Theme) -> impl IntoElement {
+        div()
+            .flex()
+            .flex_col()
+            .gap_4()
+            .p_4()
+            .rounded_lg()
+            .bg(theme.surface)
+            .text_color(theme.text)
+            .child(
+                div()
+                    .text_lg()
+                    .font_weight(FontWeight::BOLD)
+                    .text_color(theme.text)
+                    .child("🃏 卡片与阴影"),
+            )
+            .child(
+                div()
+                    .flex()
+                    .gap_4()
+                    .flex_wrap()
+                    // 默认卡片
+                    .child(
+                        Card::new()
+                            .variant(CardVariant::Default)
+                            .flex()
+                            .flex_col()
+                            .gap_2()
+                            .child(div().font_weight(FontWeight::SEMIBOLD).child("Default Card"))
+                            .child(div().text_sm().text_color(theme.subtext).child("shadow_sm")),
+                    )
+                    // 悬浮卡片
+                    .child(
+                        Card::new()
+                            .variant(CardVariant::Elevated)
+                            .flex()
+                            .flex_col()
+                            .gap_2()
+                            .child(div().font_weight(FontWeight::SEMIBOLD).child("Elevated Card"))
+                            .child(div().text_sm().text_color(theme.subtext).child("shadow_lg")),
+                    )
+                    // 边框卡片
+                    .child(
+                        Card::new()
+                            .variant(CardVariant::Bordered)
+                            .flex()
+                            .flex_col()
+                            .gap_2()
+                            .child(div().font_weight(FontWeight::SEMIBOLD).child("Bordered Card"))
+                            .child(div().text_sm().text_color(theme.subtext).child("border_1")),
+                    ),
+            )
+    }
+
+    /// 渲染间距与尺寸示例
+    fn render_spacing_section(&self, theme: &Theme) -> impl IntoElement {
+        div()
+            .flex()
+            .flex_col()
+            .gap_4()
+            .p_4()
+            .rounded_lg()
+            .bg(theme.surface)
+            .text_color(theme.text)
+            .child(
+                div()
+                    .text_lg()
+                    .font_weight(FontWeight::BOLD)
+                    .text_color(theme.text)
+                    .child("📏 间距与尺寸"),
+            )
+            // Padding 示例
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_2()
+                    .child(div().text_sm().text_color(theme.subtext).child("Padding (内边距)"))
+                    .child(
+                        div()
+                            .flex()
+                            .gap_4()
+                            .child(
+                                div()
+                                    .p_1()
+                                    .bg(rgb(0xDBEAFE))
+                                    .rounded_md()
+                                    .child(div().bg(rgb(0x3B82F6)).text_color(rgb(0xFFFFFF)).child("p_1")),
+                            )
+                            .child(
+                                div()
+                                    .p_2()
+                                    .bg(rgb(0xDBEAFE))
+                                    .rounded_md()
+                                    .child(div().bg(rgb(0x3B82F6)).text_color(rgb(0xFFFFFF)).child("p_2")),
+                            )
+                            .child(
+                                div()
+                                    .p_4()
+                                    .bg(rgb(0xDBEAFE))
+                                    .rounded_md()
+                                    .child(div().bg(rgb(0x3B82F6)).text_color(rgb(0xFFFFFF)).child("p_4")),
+                            )
+                            .child(
+                                div()
+                                    .px_4()
+                                    .py_2()
+                                    .bg(rgb(0xDBEAFE))
+                                    .rounded_md()
+                                    .child(div().bg(rgb(0x3B82F6)).text_color(rgb(0xFFFFFF)).child("px_4 py_2")),
+                            ),
+                    ),
+            )
+            // Gap 示例
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_2()
+                    .child(div().text_sm().text_color(theme.subtext).child("Gap (间隙)"))
+                    .child(
+                        div()
+                            .flex()
+                            .gap_1()
+                            .children((0..5).map(|_| {
+                                div().w(px(30.0)).h(px(30.0)).bg(rgb(0x10B981)).rounded_md()
+                            }))
+                            .child(div().ml_2().text_sm().child("gap_1")),
+                    )
+                    .child(
+                        div()
+                            .flex()
+                            .gap_4()
+                            .children((0..5).map(|_| {
+                                div().w(px(30.0)).h(px(30.0)).bg(rgb(0x10B981)).rounded_md()
+                            }))
+                            .child(div().ml_2().text_sm().child("gap_4")),
+                    ),
+            )
+            // 固定尺寸 vs 弹性尺寸
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_2()
+                    .child(div().text_sm().text_color(theme.subtext).child("尺寸"))
+                    .child(
+                        div()
+                            .flex()
+                            .gap_4()
+                            .child(
+                                div()
+                                    .w(px(100.0))
+                                    .h(px(40.0))
+                                    .bg(rgb(0x8B5CF6))
+                                    .rounded_md()
+                                    .flex()
+                                    .items_center()
+                                    .justify_center()
+                                    .text_color(rgb(0xFFFFFF))
+                                    .text_xs()
+                                    .child("w(px(100))"),
+                            )
+                            .child(
+                                div()
+                                    .flex_1()
+                                    .h(px(40.0))
+                                    .bg(rgb(0xEC4899))
+                                    .rounded_md()
+                                    .flex()
+                                    .items_center()
+                                    .justify_center()
+                                    .text_color(rgb(0xFFFFFF))
+                                    .text_xs()
+                                    .child("flex_1 (填充剩余)"),
+                            ),
+                    ),
+            )
+    }
+
+    /// 渲染边框和圆角示例
+    fn render_borders_section(&self, theme: &Theme) -> impl IntoElement {
+        div()
+            .flex()
+            .flex_col()
+            .gap_4()
+            .p_4()
+            .rounded_lg()
+            .bg(theme.surface)
+            .text_color(theme.text)
+            .child(
+                div()
+                    .text_lg()
+                    .font_weight(FontWeight::BOLD)
+                    .text_color(theme.text)
+                    .child("🔲 边框与圆角"),
+            )
+            // 边框宽度
+            .child(
+                div()
+                    .flex()
+                    .gap_4()
+                    .child(
+                        div()
+                            .w(px(80.0))
+                            .h(px(50.0))
+                            .bg(theme.background)
+                            .border_1()
+                            .border_color(rgb(0x3B82F6))
+                            .flex()
+                            .items_center()
+                            .justify_center()
+                            .text_xs()
+                            .child("border_1"),
+                    )
+                    .child(
+                        div()
+                            .w(px(80.0))
+                            .h(px(50.0))
+                            .bg(theme.background)
+                            .border_2()
+                            .border_color(rgb(0x3B82F6))
+                            .flex()
+                            .items_center()
+                            .justify_center()
+                            .text_xs()
+                            .child("border_2"),
+                    )
+                    .child(
+                        div()
+                            .w(px(80.0))
+                            .h(px(50.0))
+                            .bg(theme.background)
+                            .border_4()
+                            .border_color(rgb(0x3B82F6))
+                            .flex()
+                            .items_center()
+                            .justify_center()
+                            .text_xs()
+                            .child("border_4"),
+                    ),
+            )
+            // 圆角
+            .child(
+                div()
+                    .flex()
+                    .gap_4()
+                    .child(
+                        div()
+                            .w(px(60.0))
+                            .h(px(60.0))
+                            .bg(rgb(0x10B981))
+                            .flex()
+                            .items_center()
+                            .justify_center()
+                            .text_color(rgb(0xFFFFFF))
+                            .text_xs()
+                            .child("none"),
+                    )
+                    .child(
+                        div()
+                            .w(px(60.0))
+                            .h(px(60.0))
+                            .bg(rgb(0x10B981))
+                            .rounded(px(4.0))
+                            .flex()
+                            .items_center()
+                            .justify_center()
+                            .text_color(rgb(0xFFFFFF))
+                            .text_xs()
+                            .child("rounded"),
+                    )
+                    .child(
+                        div()
+                            .w(px(60.0))
+                            .h(px(60.0))
+                            .bg(rgb(0x10B981))
+                            .rounded_md()
+                            .flex()
+                            .items_center()
+                            .justify_center()
+                            .text_color(rgb(0xFFFFFF))
+                            .text_xs()
+                            .child("md"),
+                    )
+                    .child(
+                        div()
+                            .w(px(60.0))
+                            .h(px(60.0))
+                            .bg(rgb(0x10B981))
+                            .rounded_lg()
+                            .flex()
+                            .items_center()
+                            .justify_center()
+                            .text_color(rgb(0xFFFFFF))
+                            .text_xs()
+                            .child("lg"),
+                    )
+                    .child(
+                        div()
+                            .w(px(60.0))
+                            .h(px(60.0))
+                            .bg(rgb(0x10B981))
+                            .rounded_xl()
+                            .flex()
+                            .items_center()
+                            .justify_center()
+                            .text_color(rgb(0xFFFFFF))
+                            .text_xs()
+                            .child("xl"),
+                    )
+                    .child(
+                        div()
+                            .w(px(60.0))
+                            .h(px(60.0))
+                            .bg(rgb(0x10B981))
+                            .rounded_full()
+                            .flex()
+                            .items_center()
+                            .justify_center()
+                            .text_color(rgb(0xFFFFFF))
+                            .text_xs()
+                            .child("full"),
+                    ),
+            )
+    }
+
+    /// 渲染调试工具栏
+    fn render_debug_toolbar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .flex()
+            .items_center()
+            .gap_4()
+            .p_2()
+            .bg(rgb(0x1F2937))
+            .rounded_lg()
+            .child(
+                div()
+                    .debug_selector(|| "toggle-debug".into())
+                    .id("toggle-debug")
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .px_3()
+                    .py_1()
+                    .rounded_md()
+                    .cursor_pointer()
+                    .when(self.show_debug, |s| s.bg(rgb(0x10B981)))
+                    .when(!self.show_debug, |s| s.bg(rgb(0x374151)))
+                    .text_color(rgb(0xFFFFFF))
+                    .text_sm()
+                    .child(if self.show_debug { "🔍 Debug ON" } else { "🔍 Debug OFF" })
+                    .on_click(cx.listener(|view, _event, _window, cx| {
+                        view.show_debug = !view.show_debug;
+                        cx.notify();
+                    })),
+            )
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(rgb(0x9CA3AF))
+                    .child("提示：开启后可看到元素边框（仅 debug 构建）"),
+            )
+    }
+}
+
+impl Render for StylingApp {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        // 读取全局主题（clone 一份，避免与下面的 cx 可变借用冲突）
+        let theme = cx.theme().clone();
+
+        let mut content = div()
+            .id("main-content")  // 添加 id 以启用滚动
+            .size_full()
+            .flex()
+            .flex_col()
+            .overflow_y_scroll()  // 启用垂直滚动
+            .bg(theme.background)
+            .p_6()
+            .gap_6();
+
+        // 标题
+        content = content.child(
+            div()
+                .flex()
+                .flex_col()
+                .gap_2()
+                .child(
+                    div()
+                        .text_2xl()
+                        .font_weight(FontWeight::EXTRA_BOLD)
+                        .text_color(theme.text)
+                        .child("第四章：样式系统 🎨"),
+                )
+                .child(
+                    div()
+                        .text_sm()
+                        .text_color(theme.subtext)
+                        .child("学习 GPUI 类似 Tailwind CSS 的样式 API"),
+                ),
+        );
+
+        // 主题文件加载失败时显示错误（保留上一次成功加载的主题）
+        if let Some(error) = cx
+            .try_global::<ThemeFileStatus>()
+            .and_then(|status| status.error.clone())
+        {
+            content = content.child(
+                div()
+                    .p_3()
+                    .rounded_lg()
+                    .bg(theme.danger)
+                    .text_color(theme.accent_foreground)
+                    .text_sm()
+                    .child(format!("⚠️ {}", error)),
+            );
+        }
+
+        // 调试工具栏
+        content = content.child(self.render_debug_toolbar(cx));
+
+        // 主内容区域 - 两列布局
+        content = content.child(
+            div()
+                .flex()
+                .gap_6()
+                // 左列
+                .child(
+                    div()
+                        .flex_1()
+                        .flex()
+                        .flex_col()
+                        .gap_6()
+                        .child(self.render_flexbox_section(&theme, cx))
+                        .child(self.render_buttons_section(&theme))
+                        .child(self.render_spacing_section(&theme)),
+                )
+                // 右列
+                .child(
+                    div()
+                        .flex_1()
+                        .flex()
+                        .flex_col()
+                        .gap_6()
+                        .child(self.render_text_section(&theme))
+                        .child(self.render_cards_section(&theme))
+                        .child(self.render_borders_section(&theme)),
+                ),
+        );
+
+        // 知识点总结
+        content = content.child(
+            div()
+                .p_4()
+                .rounded_lg()
+                .bg(theme.callout_background)
+                .border_1()
+                .border_color(theme.callout_border)
+                .child(
+                    div()
+                        .text_sm()
+                        .text_color(theme.callout_text)
+                        .child("💡 本章涵盖：Flexbox布局 | 尺寸间距 | 颜色背景 | 边框圆角 | 阴影透明度 | 文字样式 | hover/active伪状态 | when条件样式"),
+                ),
+        );
+
+        // 调试模式
+        #[cfg(debug_assertions)]
+        if self.show_debug {
+            content = content.debug_below();
+        }
+
+        content
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // 不能 use super::*：gpui::* 中的 test 宏会遮蔽内置的 #[test]
+    use super::{LayoutMode, StylingApp};
+    use gpui::{Entity, Modifiers, TestAppContext, VisualTestContext};
+    use gpui_components::Theme;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    fn styling_app(cx: &mut TestAppContext) -> (Entity<StylingApp>, &mut VisualTestContext) {
+        cx.update(Theme::init);
+        cx.add_window_view(|_, cx| StylingApp::new(cx))
+    }
+
+    fn click(cx: &mut VisualTestContext, selector: &'static str) {
+        let bounds = cx
+            .debug_bounds(selector)
+            .unwrap_or_else(|| panic!("找不到 {selector}"));
+        cx.simulate_click(bounds.center(), Modifiers::none());
+    }
+
+    #[gpui::test]
+    fn layout_buttons_switch_the_layout_mode(cx: &mut TestAppContext) {
+        let (app, cx) = styling_app(cx);
+        assert_eq!(app.read_with(cx, |app, _| app.layout_mode), LayoutMode::Row);
+
+        for (selector, mode) in [
+            ("layout-Column", LayoutMode::Column),
+            ("layout-Wrap", LayoutMode::Wrap),
+            ("layout-Grid", LayoutMode::Grid),
+            ("layout-Row", LayoutMode::Row),
+        ] {
+            click(cx, selector);
+            assert_eq!(app.read_with(cx, |app, _| app.layout_mode), mode);
+        }
+    }
+
+    #[gpui::test]
+    fn debug_toolbar_toggles_debug_borders(cx: &mut TestAppContext) {
+        let (app, cx) = styling_app(cx);
+        assert!(!app.read_with(cx, |app, _| app.show_debug));
+
+        click(cx, "toggle-debug");
+        assert!(app.read_with(cx, |app, _| app.show_debug));
+
+        // 开启后整棵树会用 debug_below 渲染，按钮仍然可以点击
+        click(cx, "toggle-debug");
+        assert!(!app.read_with(cx, |app, _| app.show_debug));
+    }
+
+    #[gpui::test]
+    fn switching_the_theme_rerenders_the_view(cx: &mut TestAppContext) {
+        let (app, cx) = styling_app(cx);
+        let renders = Rc::new(Cell::new(0));
+        cx.update(|_, cx| {
+            let renders = renders.clone();
+            cx.observe(&app, move |_, _| renders.set(renders.get() + 1))
+                .detach();
+        });
+
+        // 全局 Theme 被替换（切换或热重载）时，视图通过 observe_global 收到 notify
+        cx.update(|_, cx| Theme::toggle(cx));
+        assert_eq!(renders.get(), 1);
+    }
+}
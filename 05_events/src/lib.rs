@@ -0,0 +1,1607 @@
+// 第五章：GPUI 事件处理
+// ======================
+// 本章学习 GPUI 的事件系统：
+// 1. 点击事件 (on_click)
+// 2. 鼠标事件 (mouse down/up/move)
+// 3. 键盘事件 (key down/up)
+// 4. 滚轮事件 (scroll wheel)
+// 5. 悬停事件 (hover)
+// 6. cx.listener() 的使用
+// 7. 焦点与键盘输入
+// 8. 文本输入（TextInput 组件 + 输入法）
+// 9. 组合键序列（"g g"、"ctrl-k ctrl-c"）
+// 10. 右键菜单（deferred + anchored 浮层）
+// 11. 用 Signal / Memo 保存状态（写入后自动通知读者，不再手动 cx.notify()）
+
+use gpui::prelude::FluentBuilder;
+use gpui::*;
+use gpui_components::{
+    context_menu, pending_chord_bindings, text_input, ButtonSize, ButtonVariant, ContextMenu, Memo,
+    Signal, StyledButton, TextInput, TextInputEvent,
+};
+
+// 按钮演示的 Action：右键菜单中的每一项都分发其中一个，菜单上显示它们的快捷键
+actions!(
+    button_demo,
+    [
+        ClickOnce,      // 点击次数 +1
+        ResetClicks,    // 点击次数清零
+        CopyClickCount, // 复制点击次数
+        CopyLastEvent,  // 复制最后事件
+    ]
+);
+
+/// ButtonDemo 的键盘上下文
+const BUTTON_DEMO_CONTEXT: &str = "ButtonDemo";
+
+// 组合键演示用的 Action，绑定在 KeyboardDemo 上下文中
+actions!(
+    keyboard,
+    [
+        GoToTop,       // 跳到第一行
+        GoToBottom,    // 跳到最后一行
+        CommentLine,   // 注释当前行
+        UncommentLine, // 取消注释
+    ]
+);
+
+/// KeyboardDemo 的键盘上下文
+const KEYBOARD_DEMO_CONTEXT: &str = "KeyboardDemo";
+
+/// 演示"文档"的行数
+const LINE_COUNT: usize = 100;
+
+/// 注册本章的快捷键：TextInput 的编辑快捷键 + 键盘演示的组合键
+///
+/// 空格分隔的多个按键是一个序列：按下 "ctrl-k" 后 GPUI 进入等待状态，
+/// 1 秒内按下 "ctrl-c" 才会触发 CommentLine，否则放弃前缀，按普通按键处理。
+pub fn bind_keys(cx: &mut App) {
+    text_input::init(cx);
+    context_menu::init(cx);
+    cx.bind_keys([
+        KeyBinding::new("enter", ClickOnce, Some(BUTTON_DEMO_CONTEXT)),
+        KeyBinding::new("r", ResetClicks, Some(BUTTON_DEMO_CONTEXT)),
+        KeyBinding::new("c", CopyClickCount, Some(BUTTON_DEMO_CONTEXT)),
+        KeyBinding::new("shift-c", CopyLastEvent, Some(BUTTON_DEMO_CONTEXT)),
+        KeyBinding::new("g g", GoToTop, Some(KEYBOARD_DEMO_CONTEXT)),
+        KeyBinding::new("shift-g", GoToBottom, Some(KEYBOARD_DEMO_CONTEXT)),
+        KeyBinding::new("ctrl-k ctrl-c", CommentLine, Some(KEYBOARD_DEMO_CONTEXT)),
+        KeyBinding::new("ctrl-k ctrl-u", UncommentLine, Some(KEYBOARD_DEMO_CONTEXT)),
+    ]);
+}
+
+/// 把按键序列显示为 "ctrl-k ctrl-c"
+fn keystrokes_text<'a>(keystrokes: impl IntoIterator<Item = &'a Keystroke>) -> String {
+    keystrokes
+        .into_iter()
+        .map(Keystroke::unparse)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// ============================================================================
+// 第一部分：简化版鼠标追踪器 - 展示鼠标事件
+// ============================================================================
+//
+// 状态保存在 Signal 中：监听器只写入 Signal，不调用 cx.notify()，
+// 读过这个 Signal 的视图会自动重新渲染。
+// 鼠标位置每次移动都会变化，只有 MousePositionLabel 读取它；
+// 网格坐标是位置的 Memo，在同一个格子里移动时不会让 GridCellLabel 重新渲染。
+
+/// 网格坐标的格子边长
+const GRID_SIZE: f32 = 50.0;
+
+/// 鼠标追踪演示
+struct MouseTracker {
+    /// 鼠标位置（相对于窗口）
+    mouse_position: Signal<Option<Point<Pixels>>>,
+    /// 是否正在按下
+    is_pressing: Signal<bool>,
+    /// 点击次数
+    click_count: Signal<i32>,
+    /// 最后一次事件
+    last_event: Signal<String>,
+    position_label: Entity<MousePositionLabel>,
+    grid_label: Entity<GridCellLabel>,
+}
+
+impl MouseTracker {
+    fn new(cx: &mut Context<Self>) -> Self {
+        let mouse_position: Signal<Option<Point<Pixels>>> = Signal::new(None, cx);
+        let grid_cell = Memo::new(cx, {
+            let mouse_position = mouse_position.clone();
+            move |cx| {
+                mouse_position.get(cx).map(|pos| {
+                    ((pos.x / px(GRID_SIZE)).floor() as i32, (pos.y / px(GRID_SIZE)).floor() as i32)
+                })
+            }
+        });
+
+        Self {
+            position_label: cx.new(|_| MousePositionLabel {
+                position: mouse_position.clone(),
+                renders: 0,
+            }),
+            grid_label: cx.new(|_| GridCellLabel {
+                cell: grid_cell,
+                renders: 0,
+            }),
+            mouse_position,
+            is_pressing: Signal::new(false, cx),
+            click_count: Signal::new(0, cx),
+            last_event: Signal::new("等待鼠标事件...".to_string(), cx),
+        }
+    }
+}
+
+impl Render for MouseTracker {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let is_pressing = self.is_pressing.get(cx);
+        // 标签用 cached 渲染：追踪器重新渲染时，没有被通知的标签直接复用上一帧
+        let label_style = || StyleRefinement::default().w_full().h(px(20.0));
+
+        div()
+            .id("mouse-tracker")
+            .w_full()
+            .h(px(200.0))
+            .bg(if is_pressing { rgb(0x1E3A8A) } else { rgb(0x1F2937) })
+            .rounded_lg()
+            .cursor_crosshair()
+            .relative()
+            .overflow_hidden()
+            .flex()
+            .flex_col()
+            .items_center()
+            .justify_center()
+            .gap_2()
+            // 鼠标按下
+            .on_mouse_down(MouseButton::Left, cx.listener(|view, event: &MouseDownEvent, _window, cx| {
+                view.is_pressing.set(true, cx);
+                view.mouse_position.set(Some(event.position), cx);
+                view.last_event.set(format!("按下 @ ({:.0}, {:.0})", event.position.x, event.position.y), cx);
+            }))
+            // 鼠标释放
+            .on_mouse_up(MouseButton::Left, cx.listener(|view, event: &MouseUpEvent, _window, cx| {
+                view.is_pressing.set(false, cx);
+                view.last_event.set(format!("释放 @ ({:.0}, {:.0})", event.position.x, event.position.y), cx);
+            }))
+            // 鼠标移动
+            .on_mouse_move(cx.listener(|view, event: &MouseMoveEvent, _window, cx| {
+                view.mouse_position.set(Some(event.position), cx);
+            }))
+            // 点击事件
+            .on_click(cx.listener(|view, event: &ClickEvent, _window, cx| {
+                view.click_count.update(cx, |count| *count += 1);
+                let click_count = *view.click_count.peek(cx);
+                view.last_event.set(format!("点击 #{} @ ({:.0}, {:.0})", 
+                    click_count, event.position().x, event.position().y), cx);
+            }))
+            // 显示内容
+            .child(
+                div()
+                    .text_2xl()
+                    .font_weight(FontWeight::BOLD)
+                    .text_color(rgb(0xFFFFFF))
+                    .child(format!("点击次数: {}", self.click_count.get(cx))),
+            )
+            .child(AnyView::from(self.position_label.clone()).cached(label_style()))
+            .child(AnyView::from(self.grid_label.clone()).cached(label_style()))
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(if is_pressing { rgb(0x60A5FA) } else { rgb(0x6B7280) })
+                    .child(self.last_event.get(cx)),
+            )
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(rgb(0x4B5563))
+                    .mt_4()
+                    .child("💡 在此区域点击、拖动试试"),
+            )
+    }
+}
+
+/// 显示鼠标位置，只读取 mouse_position
+struct MousePositionLabel {
+    position: Signal<Option<Point<Pixels>>>,
+    /// 渲染次数，用来验证只有它在鼠标移动时重新渲染
+    renders: usize,
+}
+
+impl Render for MousePositionLabel {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        self.renders += 1;
+        div()
+            .flex()
+            .justify_center()
+            .text_color(rgb(0x9CA3AF))
+            .child(match self.position.get(cx) {
+                Some(pos) => format!("鼠标位置: ({:.0}, {:.0})", pos.x, pos.y),
+                None => "鼠标未进入".to_string(),
+            })
+    }
+}
+
+/// 显示鼠标所在的网格坐标，只读取 grid_cell
+struct GridCellLabel {
+    cell: Memo<Option<(i32, i32)>>,
+    renders: usize,
+}
+
+impl Render for GridCellLabel {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        self.renders += 1;
+        div()
+            .flex()
+            .justify_center()
+            .text_xs()
+            .text_color(rgb(0x6B7280))
+            .child(match self.cell.get(cx) {
+                Some((col, row)) => format!("网格: 第 {} 列 第 {} 行（每格 {:.0}px）", col, row, GRID_SIZE),
+                None => "网格: -".to_string(),
+            })
+    }
+}
+
+// ============================================================================
+// 第二部分：按钮演示 - 展示各种点击事件
+// ============================================================================
+
+/// 可交互按钮状态
+struct ButtonDemo {
+    /// 点击次数
+    click_count: i32,
+    /// 最后一次事件信息
+    last_event: String,
+    /// 修饰键状态
+    modifiers_info: String,
+    /// 菜单中的 Action 分发到这里
+    focus_handle: FocusHandle,
+    /// 打开的右键菜单和鼠标位置
+    context_menu: Option<(Entity<ContextMenu>, Point<Pixels>)>,
+}
+
+impl ButtonDemo {
+    fn new(cx: &mut Context<Self>) -> Self {
+        Self {
+            click_count: 0,
+            last_event: "等待交互...".to_string(),
+            modifiers_info: String::new(),
+            focus_handle: cx.focus_handle(),
+            context_menu: None,
+        }
+    }
+
+    /// 在鼠标位置打开右键菜单
+    fn deploy_context_menu(&mut self, event: &MouseDownEvent, window: &mut Window, cx: &mut Context<Self>) {
+        // 先聚焦自己：菜单记录的是创建时的焦点，快捷键提示和 Action 都以这里为准
+        self.focus_handle.focus(window);
+        let can_reset = self.click_count > 0;
+        let menu = ContextMenu::build(window, cx, |menu| {
+            menu.entry("点击一次", ClickOnce)
+                .when(can_reset, |menu| menu.entry("重置计数", ResetClicks))
+                .when(!can_reset, |menu| menu.disabled_entry("重置计数", ResetClicks))
+                .separator()
+                .submenu("复制信息", |menu| {
+                    menu.entry("复制点击次数", CopyClickCount)
+                        .entry("复制最后事件", CopyLastEvent)
+                })
+        });
+        cx.subscribe(&menu, |view, menu, _: &DismissEvent, cx| {
+            // 在菜单外右键时，旧菜单的关闭事件晚于新菜单的创建，只清除自己
+            if view.context_menu.as_ref().is_some_and(|(current, _)| *current == menu) {
+                view.context_menu = None;
+                cx.notify();
+            }
+        })
+        .detach();
+        self.context_menu = Some((menu, event.position));
+        cx.notify();
+    }
+
+    fn click_once(&mut self, _: &ClickOnce, _window: &mut Window, cx: &mut Context<Self>) {
+        self.click_count += 1;
+        self.last_event = "菜单: 点击一次".to_string();
+        cx.notify();
+    }
+
+    fn reset_clicks(&mut self, _: &ResetClicks, _window: &mut Window, cx: &mut Context<Self>) {
+        self.click_count = 0;
+        self.last_event = "菜单: 重置计数".to_string();
+        cx.notify();
+    }
+
+    fn copy_click_count(&mut self, _: &CopyClickCount, _window: &mut Window, cx: &mut Context<Self>) {
+        cx.write_to_clipboard(ClipboardItem::new_string(self.click_count.to_string()));
+        self.last_event = "菜单: 已复制点击次数".to_string();
+        cx.notify();
+    }
+
+    fn copy_last_event(&mut self, _: &CopyLastEvent, _window: &mut Window, cx: &mut Context<Self>) {
+        cx.write_to_clipboard(ClipboardItem::new_string(self.last_event.clone()));
+        self.last_event = "菜单: 已复制最后事件".to_string();
+        cx.notify();
+    }
+}
+
+impl Render for ButtonDemo {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .key_context(BUTTON_DEMO_CONTEXT)
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::click_once))
+            .on_action(cx.listener(Self::reset_clicks))
+            .on_action(cx.listener(Self::copy_click_count))
+            .on_action(cx.listener(Self::copy_last_event))
+            .flex()
+            .flex_col()
+            .gap_4()
+            // 主按钮
+            .child(
+                StyledButton::new("main-button", format!("点击我！({}次)", self.click_count))
+                    .variant(ButtonVariant::Primary)
+                    .size(ButtonSize::Large)
+                    .rounded_lg()
+                    .on_click(cx.listener(|view, event: &ClickEvent, _window, cx| {
+                        view.click_count += 1;
+                        view.last_event = format!("点击位置: ({:.0}, {:.0})", 
+                            event.position().x, event.position().y);
+                        view.modifiers_info = format!(
+                            "修饰键: Ctrl={}, Shift={}, Alt={}",
+                            event.modifiers().control,
+                            event.modifiers().shift,
+                            event.modifiers().alt,
+                        );
+                        cx.notify();
+                    })),
+            )
+            // 鼠标按钮演示
+            .child(
+                div()
+                    .flex()
+                    .gap_2()
+                    .child(
+                        div()
+                            .id("left-btn")
+                            .px_4()
+                            .py_2()
+                            .bg(rgb(0x10B981))
+                            .text_color(rgb(0xFFFFFF))
+                            .rounded_md()
+                            .cursor_pointer()
+                            .hover(|s| s.bg(rgb(0x059669)))
+                            .child("左键按下")
+                            .on_mouse_down(MouseButton::Left, cx.listener(|view, _event, _window, cx| {
+                                view.last_event = "左键按下".to_string();
+                                cx.notify();
+                            })),
+                    )
+                    .child(
+                        div()
+                            .id("right-btn")
+                            .px_4()
+                            .py_2()
+                            .bg(rgb(0xF59E0B))
+                            .text_color(rgb(0xFFFFFF))
+                            .rounded_md()
+                            .cursor_pointer()
+                            .hover(|s| s.bg(rgb(0xD97706)))
+                            .debug_selector(|| "right-btn".into())
+                            .child("右键菜单")
+                            .on_mouse_down(MouseButton::Right, cx.listener(|view, event, window, cx| {
+                                view.last_event = "右键按下".to_string();
+                                view.deploy_context_menu(event, window, cx);
+                                // 阻止外层 track_focus 在冒泡阶段把焦点从菜单抢回来
+                                window.prevent_default();
+                            })),
+                    )
+                    .child(
+                        div()
+                            .id("middle-btn")
+                            .px_4()
+                            .py_2()
+                            .bg(rgb(0x8B5CF6))
+                            .text_color(rgb(0xFFFFFF))
+                            .rounded_md()
+                            .cursor_pointer()
+                            .hover(|s| s.bg(rgb(0x7C3AED)))
+                            .child("中键按下")
+                            .on_mouse_down(MouseButton::Middle, cx.listener(|view, _event, _window, cx| {
+                                view.last_event = "中键按下".to_string();
+                                cx.notify();
+                            })),
+                    ),
+            )
+            // 事件信息显示
+            .child(
+                div()
+                    .p_3()
+                    .bg(rgb(0xF3F4F6))
+                    .rounded_md()
+                    .text_sm()
+                    .child(
+                        div()
+                            .flex()
+                            .flex_col()
+                            .gap_1()
+                            .child(format!("最后事件: {}", self.last_event))
+                            .when(!self.modifiers_info.is_empty(), |el| {
+                                el.child(self.modifiers_info.clone())
+                            }),
+                    ),
+            )
+            // 右键菜单：deferred 让它最后绘制、盖在其它元素上，
+            // anchored 把它放在鼠标位置，靠近窗口边缘时自动移回窗口内
+            .when_some(self.context_menu.clone(), |el, (menu, position)| {
+                el.child(
+                    deferred(
+                        anchored()
+                            .position(position)
+                            .snap_to_window_with_margin(px(8.))
+                            .child(menu),
+                    )
+                    .with_priority(1),
+                )
+            })
+    }
+}
+
+// ============================================================================
+// 第三部分：键盘事件演示
+// ============================================================================
+
+/// 键盘事件演示组件
+struct KeyboardDemo {
+    /// 按键历史
+    key_history: Vec<String>,
+    /// 当前按住的键
+    held_keys: Vec<String>,
+    /// 焦点句柄 - 必须存储以保持稳定
+    focus_handle: FocusHandle,
+    /// 已经按下、等待后续按键的组合键前缀（与 window.pending_input_keystrokes() 同步）
+    pending_chord: Vec<Keystroke>,
+    /// 前缀等待期间收到了不能继续序列的按键：用来区分"被打断"和"超时"
+    chord_interrupted: bool,
+    /// 当前序列已经完成，匹配到了绑定
+    chord_completed: bool,
+    /// 最近一次匹配到的绑定，例如 "ctrl-k ctrl-c → keyboard::CommentLine"
+    last_match: Option<String>,
+    /// 最近一次被放弃的前缀，例如 "⏱ ctrl-k 超时"
+    abandoned_chord: Option<String>,
+    /// 组合键操作的"文档"状态
+    cursor_line: usize,
+    line_commented: bool,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl KeyboardDemo {
+    fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let _subscriptions = vec![
+            // 等待中的前缀发生变化：按下前缀、匹配完成、被打断或超时
+            cx.observe_pending_input(window, Self::pending_input_changed),
+            // 每个按键处理完之后调用，event.action 是最终匹配到的 Action
+            cx.observe_keystrokes(Self::keystroke_observed),
+        ];
+
+        Self {
+            key_history: Vec::new(),
+            held_keys: Vec::new(),
+            focus_handle: cx.focus_handle(),
+            pending_chord: Vec::new(),
+            chord_interrupted: false,
+            chord_completed: false,
+            last_match: None,
+            abandoned_chord: None,
+            cursor_line: 1,
+            line_commented: false,
+            _subscriptions,
+        }
+    }
+
+    fn pending_input_changed(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let pending = window
+            .pending_input_keystrokes()
+            .map(<[Keystroke]>::to_vec)
+            .unwrap_or_default();
+
+        if pending.is_empty() && !self.pending_chord.is_empty() && !self.chord_completed {
+            // 前缀没有完成任何绑定就结束了：GPUI 会把这些按键当作普通按键重新分发
+            let prefix = keystrokes_text(&self.pending_chord);
+            self.abandoned_chord = Some(if self.chord_interrupted {
+                format!("✋ {} 被打断，按普通按键处理", prefix)
+            } else {
+                format!("⏱ {} 超时，按普通按键处理", prefix)
+            });
+        }
+        if self.pending_chord.is_empty() && !pending.is_empty() {
+            // 新的序列开始
+            self.chord_interrupted = false;
+            self.chord_completed = false;
+            self.abandoned_chord = None;
+        }
+
+        self.pending_chord = pending;
+        cx.notify();
+    }
+
+    fn keystroke_observed(&mut self, event: &KeystrokeEvent, window: &mut Window, cx: &mut Context<Self>) {
+        // 观察者对整个应用生效，只关心焦点在本组件时的按键
+        if !self.focus_handle.contains_focused(window, cx) {
+            return;
+        }
+        match &event.action {
+            Some(action) => {
+                // 前缀 + 当前按键是这个 Action 的某个绑定时，序列完成；
+                // 否则前缀已被放弃（GPUI 重新分发前缀时不通知观察者），当前按键单独匹配了绑定
+                let mut sequence = self.pending_chord.clone();
+                sequence.push(event.keystroke.clone());
+                let continues_chord = sequence.len() > 1
+                    && !self.chord_interrupted
+                    && cx
+                        .key_bindings()
+                        .borrow()
+                        .bindings_for_action(action.as_ref())
+                        .any(|binding| binding.match_keystrokes(&sequence) == Some(false));
+                let keystrokes = if continues_chord {
+                    keystrokes_text(&sequence)
+                } else {
+                    event.keystroke.unparse()
+                };
+                self.chord_completed = continues_chord;
+                if !continues_chord && !self.pending_chord.is_empty() {
+                    self.chord_interrupted = true;
+                }
+                self.last_match = Some(format!("{} → {}", keystrokes, action.name()));
+            }
+            None if !self.pending_chord.is_empty() => self.chord_interrupted = true,
+            None => {}
+        }
+        cx.notify();
+    }
+
+    fn go_to_top(&mut self, _: &GoToTop, _window: &mut Window, cx: &mut Context<Self>) {
+        self.cursor_line = 1;
+        cx.notify();
+    }
+
+    fn go_to_bottom(&mut self, _: &GoToBottom, _window: &mut Window, cx: &mut Context<Self>) {
+        self.cursor_line = LINE_COUNT;
+        cx.notify();
+    }
+
+    fn comment_line(&mut self, _: &CommentLine, _window: &mut Window, cx: &mut Context<Self>) {
+        self.line_commented = true;
+        cx.notify();
+    }
+
+    fn uncomment_line(&mut self, _: &UncommentLine, _window: &mut Window, cx: &mut Context<Self>) {
+        self.line_commented = false;
+        cx.notify();
+    }
+
+    fn add_key(&mut self, key: String) {
+        self.key_history.push(key);
+        if self.key_history.len() > 10 {
+            self.key_history.remove(0);
+        }
+    }
+}
+
+impl Render for KeyboardDemo {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        // 检查是否有焦点
+        let has_focus = self.focus_handle.is_focused(window);
+        // 等待中的前缀可以继续完成的绑定
+        let continuations: Vec<String> = pending_chord_bindings(window, cx)
+            .iter()
+            .rev()
+            .map(|binding| {
+                let keystrokes = binding.keystrokes().iter().map(|keystroke| keystroke.inner());
+                format!("{} → {}", keystrokes_text(keystrokes), binding.action().name())
+            })
+            .collect();
+
+        div()
+            .id("keyboard-area")
+            .flex()
+            .flex_col()
+            .gap_3()
+            .p_4()
+            .rounded_lg()
+            .border_2()
+            .cursor_pointer()
+            .when(has_focus, |s| s.border_color(rgb(0x3B82F6)).bg(rgb(0xEFF6FF)))
+            .when(!has_focus, |s| s.border_color(rgb(0xE5E7EB)).bg(rgb(0xFFFFFF)))
+            // 键盘事件需要元素可聚焦 - 使用存储的 focus_handle
+            .track_focus(&self.focus_handle)
+            // 组合键由 bind_keys() 中的 keymap 匹配，完成后分发 Action
+            .key_context(KEYBOARD_DEMO_CONTEXT)
+            .on_action(cx.listener(Self::go_to_top))
+            .on_action(cx.listener(Self::go_to_bottom))
+            .on_action(cx.listener(Self::comment_line))
+            .on_action(cx.listener(Self::uncomment_line))
+            // 点击时获取焦点
+            .on_click(cx.listener(|view, _event, window, cx| {
+                view.focus_handle.focus(window);
+                cx.notify();
+            }))
+            // 键盘按下事件（被快捷键消耗的按键不会到达这里）
+            .on_key_down(cx.listener(|view, event: &KeyDownEvent, _window, cx| {
+                let key_str = format!("{}", event.keystroke);
+                if !event.is_held {
+                    view.add_key(key_str.clone());
+                    if !view.held_keys.contains(&key_str) {
+                        view.held_keys.push(key_str);
+                    }
+                }
+                cx.notify();
+            }))
+            // 键盘释放事件
+            .on_key_up(cx.listener(|view, event: &KeyUpEvent, _window, cx| {
+                let key_str = format!("{}", event.keystroke);
+                view.held_keys.retain(|k| k != &key_str);
+                cx.notify();
+            }))
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(if has_focus { rgb(0x1D4ED8) } else { rgb(0x6B7280) })
+                    .font_weight(if has_focus { FontWeight::BOLD } else { FontWeight::NORMAL })
+                    .child(if has_focus {
+                        "🎹 键盘区域已聚焦 - 按任意键！"
+                    } else {
+                        "👆 点击此区域以获取键盘焦点"
+                    }),
+            )
+            // 组合键
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_1()
+                    .p_2()
+                    .bg(rgb(0xF3F4F6))
+                    .rounded_md()
+                    .text_xs()
+                    .child(
+                        div()
+                            .text_color(rgb(0x374151))
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .child("组合键: g g 顶部 · shift-g 底部 · ctrl-k ctrl-c 注释 · ctrl-k ctrl-u 取消注释"),
+                    )
+                    .child(div().text_color(rgb(0x374151)).child(format!(
+                        "📄 第 {} / {} 行{}",
+                        self.cursor_line,
+                        LINE_COUNT,
+                        if self.line_commented { "（已注释）" } else { "" }
+                    )))
+                    .when(!self.pending_chord.is_empty(), |el| {
+                        el.child(
+                            div()
+                                .text_color(rgb(0xD97706))
+                                .font_weight(FontWeight::BOLD)
+                                .child(format!("⌨️ 等待后续按键: {} …", keystrokes_text(&self.pending_chord))),
+                        )
+                        .children(continuations.into_iter().map(|continuation| {
+                            div().pl_4().text_color(rgb(0x6B7280)).child(continuation)
+                        }))
+                    })
+                    .when_some(self.abandoned_chord.clone(), |el, abandoned| {
+                        el.child(div().text_color(rgb(0xDC2626)).child(abandoned))
+                    })
+                    .when_some(self.last_match.clone(), |el, matched| {
+                        el.child(div().text_color(rgb(0x059669)).child(format!("✅ 匹配: {}", matched)))
+                    }),
+            )
+            // 当前按住的键
+            .child(
+                div()
+                    .flex()
+                    .flex_wrap()
+                    .gap_2()
+                    .min_h(px(40.0))
+                    .items_center()
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(rgb(0x374151))
+                            .child("按住的键:"),
+                    )
+                    .when(self.held_keys.is_empty() && has_focus, |el| {
+                        el.child(
+                            div()
+                                .text_sm()
+                                .text_color(rgb(0x9CA3AF))
+                                .italic()
+                                .child("(按下键盘)")
+                        )
+                    })
+                    .children(self.held_keys.iter().map(|key| {
+                        div()
+                            .px_3()
+                            .py_1()
+                            .bg(rgb(0x3B82F6))
+                            .text_color(rgb(0xFFFFFF))
+                            .rounded_md()
+                            .text_sm()
+                            .font_weight(FontWeight::BOLD)
+                            .child(key.clone())
+                    })),
+            )
+            // 按键历史
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_1()
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(rgb(0x374151))
+                            .child(format!("按键历史 ({}):", self.key_history.len())),
+                    )
+                    .child(
+                        div()
+                            .flex()
+                            .flex_wrap()
+                            .gap_1()
+                            .min_h(px(30.0))
+                            .when(self.key_history.is_empty(), |el| {
+                                el.child(
+                                    div()
+                                        .text_xs()
+                                        .text_color(rgb(0x9CA3AF))
+                                        .italic()
+                                        .child("(还没有按键记录)")
+                                )
+                            })
+                            .children(self.key_history.iter().map(|key| {
+                                div()
+                                    .px_2()
+                                    .py_1()
+                                    .bg(rgb(0xE5E7EB))
+                                    .text_color(rgb(0x374151))
+                                    .rounded_md()
+                                    .text_xs()
+                                    .child(key.clone())
+                            })),
+                    ),
+            )
+    }
+}
+
+// ============================================================================
+// 第四部分：滚轮事件演示
+// ============================================================================
+
+/// 滚轮事件演示
+struct ScrollWheelDemo {
+    /// 累计滚动量
+    scroll_delta: Signal<Point<f32>>,
+    /// 滚动事件计数
+    scroll_count: Signal<i32>,
+}
+
+impl ScrollWheelDemo {
+    fn new(cx: &mut App) -> Self {
+        Self {
+            scroll_delta: Signal::new(Point { x: 0.0, y: 0.0 }, cx),
+            scroll_count: Signal::new(0, cx),
+        }
+    }
+
+    fn reset(&mut self, cx: &mut App) {
+        self.scroll_delta.set(Point { x: 0.0, y: 0.0 }, cx);
+        self.scroll_count.set(0, cx);
+    }
+}
+
+impl Render for ScrollWheelDemo {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let scroll_delta = self.scroll_delta.get(cx);
+        let offset_x = scroll_delta.x.clamp(-100.0, 100.0);
+        let offset_y = scroll_delta.y.clamp(-100.0, 100.0);
+
+        div()
+            .id("scroll-area")
+            .flex()
+            .flex_col()
+            .gap_3()
+            .p_4()
+            .bg(rgb(0xFEF3C7))
+            .rounded_lg()
+            // 滚轮事件
+            .on_scroll_wheel(cx.listener(|view, event: &ScrollWheelEvent, _window, cx| {
+                view.scroll_count.update(cx, |count| *count += 1);
+                let delta = event.delta.pixel_delta(px(20.0));
+                view.scroll_delta.update(cx, |scroll_delta| {
+                    scroll_delta.x += f32::from(delta.x);
+                    scroll_delta.y += f32::from(delta.y);
+                });
+            }))
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(rgb(0x92400E))
+                    .child("🖱️ 在此区域滚动鼠标滚轮"),
+            )
+            // 可视化滚动方向
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .justify_center()
+                    .w_full()
+                    .h(px(120.0))
+                    .bg(rgb(0xFFFFFF))
+                    .rounded_md()
+                    .relative()
+                    .overflow_hidden()
+                    .child(
+                        // 指示器
+                        div()
+                            .absolute()
+                            .left(px(60.0) + px(offset_x))
+                            .top(px(50.0) - px(offset_y))
+                            .w(px(20.0))
+                            .h(px(20.0))
+                            .bg(rgb(0xF59E0B))
+                            .rounded_full()
+                    )
+                    .child(
+                        // 中心十字
+                        div()
+                            .absolute()
+                            .left(px(68.0))
+                            .top(px(10.0))
+                            .w(px(4.0))
+                            .h(px(100.0))
+                            .bg(rgb(0xE5E7EB))
+                    )
+                    .child(
+                        div()
+                            .absolute()
+                            .left(px(20.0))
+                            .top(px(58.0))
+                            .w(px(100.0))
+                            .h(px(4.0))
+                            .bg(rgb(0xE5E7EB))
+                    ),
+            )
+            // 滚动信息
+            .child(
+                div()
+                    .flex()
+                    .justify_between()
+                    .text_sm()
+                    .text_color(rgb(0x78350F))
+                    .child(format!("X: {:.1}, Y: {:.1}", scroll_delta.x, scroll_delta.y))
+                    .child(format!("滚动次数: {}", self.scroll_count.get(cx))),
+            )
+            // 重置按钮
+            .child(
+                StyledButton::new("reset-scroll", "重置")
+                    .variant(ButtonVariant::Secondary)
+                    .size(ButtonSize::Small)
+                    .on_click(cx.listener(|view, _event, _window, cx| {
+                        view.reset(cx);
+                    })),
+            )
+    }
+}
+
+// ============================================================================
+// 第五部分：悬停状态演示
+// ============================================================================
+
+/// 悬停演示
+struct HoverDemo {
+    /// 当前悬停的项目索引
+    hovered_index: Signal<Option<usize>>,
+    /// 悬停计数
+    hover_count: Signal<i32>,
+}
+
+impl HoverDemo {
+    fn new(cx: &mut App) -> Self {
+        Self { 
+            hovered_index: Signal::new(None, cx),
+            hover_count: Signal::new(0, cx),
+        }
+    }
+}
+
+impl Render for HoverDemo {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let colors = [
+            ("Red", rgb(0xEF4444)),
+            ("Orange", rgb(0xF59E0B)),
+            ("Green", rgb(0x10B981)),
+            ("Blue", rgb(0x3B82F6)),
+            ("Purple", rgb(0x8B5CF6)),
+        ];
+        let hovered_index = self.hovered_index.get(cx);
+        let hover_count = self.hover_count.get(cx);
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_3()
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(rgb(0x6B7280))
+                    .child(match hovered_index {
+                        Some(i) => format!("悬停在: {} (共{}次悬停)", colors[i].0, hover_count),
+                        None => format!("将鼠标移到色块上 (共{}次悬停)", hover_count),
+                    }),
+            )
+            .child(
+                div()
+                    .flex()
+                    .gap_3()
+                    .children(colors.iter().enumerate().map(|(i, (name, color))| {
+                        let is_hovered = hovered_index == Some(i);
+                        let color = *color;
+                        
+                        div()
+                            .id(SharedString::from(format!("hover-{}", i)))
+                            .w(px(70.0))
+                            .h(px(70.0))
+                            .rounded_lg()
+                            .bg(color)
+                            .cursor_pointer()
+                            .flex()
+                            .items_center()
+                            .justify_center()
+                            .text_color(rgb(0xFFFFFF))
+                            .text_sm()
+                            .font_weight(FontWeight::BOLD)
+                            // hover 伪状态样式
+                            .hover(|s| s.opacity(0.8).shadow_lg())
+                            // 使用 when 根据状态变化
+                            .when(is_hovered, |s| {
+                                s.border_4().border_color(rgb(0xFFFFFF)).shadow_xl()
+                            })
+                            .child(*name)
+                            // 鼠标进入 - 通过 mouse_move 检测
+                            .on_mouse_move(cx.listener(move |view, _event, _window, cx| {
+                                // set 在值不变时不通知，只有进入新的色块才计数
+                                if *view.hovered_index.peek(cx) != Some(i) {
+                                    view.hovered_index.set(Some(i), cx);
+                                    view.hover_count.update(cx, |count| *count += 1);
+                                }
+                            }))
+                    })),
+            )
+            // 清除悬停状态的区域
+            .child(
+                div()
+                    .id("clear-hover")
+                    .h(px(30.0))
+                    .flex()
+                    .items_center()
+                    .text_xs()
+                    .text_color(rgb(0x9CA3AF))
+                    .child("(移到这里清除悬停状态)")
+                    .on_mouse_move(cx.listener(|view, _event, _window, cx| {
+                        view.hovered_index.set(None, cx);
+                    }))
+            )
+    }
+}
+
+// ============================================================================
+// 第六部分：文本输入演示
+// ============================================================================
+//
+// on_key_down 只能拿到单个按键，无法处理中文输入法的"组合输入"。
+// 真正的文本输入需要实现 EntityInputHandler，让平台把最终文字交给我们。
+// 共享组件库的 TextInput 已经实现好了，这里演示如何使用它的事件。
+
+/// 文本输入演示
+struct TextInputDemo {
+    input: Entity<TextInput>,
+    /// 当前内容的字符数（来自 Changed 事件）
+    char_count: usize,
+    /// 按 Enter 提交过的内容（来自 Submitted 事件）
+    submitted: Vec<SharedString>,
+    _subscription: Subscription,
+}
+
+impl TextInputDemo {
+    fn new(cx: &mut Context<Self>) -> Self {
+        let input = cx.new(|cx| TextInput::new(cx).placeholder("输入文字后按 Enter，支持中文输入法"));
+
+        // 订阅输入框事件 - 与第三章的 EventEmitter + subscribe 完全一样
+        let subscription = cx.subscribe(&input, |view, input, event: &TextInputEvent, cx| {
+            match event {
+                TextInputEvent::Changed(text) => {
+                    view.char_count = text.chars().count();
+                }
+                TextInputEvent::Submitted(text) => {
+                    if !text.is_empty() {
+                        view.submitted.push(text.clone());
+                        if view.submitted.len() > 5 {
+                            view.submitted.remove(0);
+                        }
+                        input.update(cx, |input, cx| input.clear(cx));
+                    }
+                }
+            }
+            cx.notify();
+        });
+
+        Self {
+            input,
+            char_count: 0,
+            submitted: Vec::new(),
+            _subscription: subscription,
+        }
+    }
+}
+
+impl Render for TextInputDemo {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .flex()
+            .flex_col()
+            .gap_3()
+            .child(self.input.clone())
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(rgb(0x6B7280))
+                    .child(format!(
+                        "{} 个字符 | ←/→ 移动 | Shift 选择 | Ctrl+←/→ 按词移动 | Ctrl+C/X/V 剪贴板",
+                        self.char_count
+                    )),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_1()
+                    .when(self.submitted.is_empty(), |el| {
+                        el.child(
+                            div()
+                                .text_xs()
+                                .text_color(rgb(0x9CA3AF))
+                                .italic()
+                                .child("(还没有提交记录)"),
+                        )
+                    })
+                    .children(self.submitted.iter().map(|text| {
+                        div()
+                            .px_2()
+                            .py_1()
+                            .bg(rgb(0xE5E7EB))
+                            .text_color(rgb(0x374151))
+                            .rounded_md()
+                            .text_sm()
+                            .child(format!("✉️ {}", text))
+                    })),
+            )
+    }
+}
+
+// ============================================================================
+// 第七部分：主应用
+// ============================================================================
+
+pub struct EventsApp {
+    /// 鼠标追踪器
+    mouse_tracker: Entity<MouseTracker>,
+    /// 按钮演示
+    button_demo: Entity<ButtonDemo>,
+    /// 键盘演示
+    keyboard_demo: Entity<KeyboardDemo>,
+    /// 文本输入演示
+    text_input_demo: Entity<TextInputDemo>,
+    /// 滚轮演示
+    scroll_demo: Entity<ScrollWheelDemo>,
+    /// 悬停演示
+    hover_demo: Entity<HoverDemo>,
+}
+
+impl EventsApp {
+    pub fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let mouse_tracker = cx.new(MouseTracker::new);
+        let button_demo = cx.new(ButtonDemo::new);
+        let keyboard_demo = cx.new(|cx| KeyboardDemo::new(window, cx));
+        let text_input_demo = cx.new(TextInputDemo::new);
+        let scroll_demo = cx.new(|cx| ScrollWheelDemo::new(cx));
+        let hover_demo = cx.new(|cx| HoverDemo::new(cx));
+
+        Self {
+            mouse_tracker,
+            button_demo,
+            keyboard_demo,
+            text_input_demo,
+            scroll_demo,
+            hover_demo,
+        }
+    }
+}
+
+impl Render for EventsApp {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .id("main-container")
+            .size_full()
+            .flex()
+            .flex_col()
+            .overflow_y_scroll()
+            .bg(rgb(0xF1F5F9))
+            .p_6()
+            .gap_6()
+            // 标题
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_2()
+                    .child(
+                        div()
+                            .text_2xl()
+                            .font_weight(FontWeight::EXTRA_BOLD)
+                            .text_color(rgb(0x1E293B))
+                            .child("第五章：事件处理 🎯"),
+                    )
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(rgb(0x64748B))
+                            .child("学习鼠标、键盘、滚轮等事件的处理方式"),
+                    ),
+            )
+            // 主内容 - 两列布局
+            .child(
+                div()
+                    .flex()
+                    .gap_6()
+                    // 左列
+                    .child(
+                        div()
+                            .flex_1()
+                            .flex()
+                            .flex_col()
+                            .gap_6()
+                            // 鼠标追踪
+                            .child(
+                                div()
+                                    .flex()
+                                    .flex_col()
+                                    .gap_3()
+                                    .p_4()
+                                    .bg(rgb(0xFFFFFF))
+                                    .rounded_lg()
+                                    .shadow_sm()
+                                    .child(
+                                        div()
+                                            .text_lg()
+                                            .font_weight(FontWeight::BOLD)
+                                            .text_color(rgb(0x1E293B))
+                                            .child("🎨 鼠标追踪"),
+                                    )
+                                    .child(self.mouse_tracker.clone()),
+                            )
+                            // 按钮演示
+                            .child(
+                                div()
+                                    .flex()
+                                    .flex_col()
+                                    .gap_3()
+                                    .p_4()
+                                    .bg(rgb(0xFFFFFF))
+                                    .rounded_lg()
+                                    .shadow_sm()
+                                    .child(
+                                        div()
+                                            .text_lg()
+                                            .font_weight(FontWeight::BOLD)
+                                            .text_color(rgb(0x1E293B))
+                                            .child("🖱️ 点击事件"),
+                                    )
+                                    .child(self.button_demo.clone()),
+                            ),
+                    )
+                    // 右列
+                    .child(
+                        div()
+                            .flex_1()
+                            .flex()
+                            .flex_col()
+                            .gap_6()
+                            // 键盘演示
+                            .child(
+                                div()
+                                    .flex()
+                                    .flex_col()
+                                    .gap_3()
+                                    .p_4()
+                                    .bg(rgb(0xFFFFFF))
+                                    .rounded_lg()
+                                    .shadow_sm()
+                                    .child(
+                                        div()
+                                            .text_lg()
+                                            .font_weight(FontWeight::BOLD)
+                                            .text_color(rgb(0x1E293B))
+                                            .child("⌨️ 键盘事件（点击获取焦点）"),
+                                    )
+                                    .child(self.keyboard_demo.clone()),
+                            )
+                            // 文本输入演示
+                            .child(
+                                div()
+                                    .flex()
+                                    .flex_col()
+                                    .gap_3()
+                                    .p_4()
+                                    .bg(rgb(0xFFFFFF))
+                                    .rounded_lg()
+                                    .shadow_sm()
+                                    .child(
+                                        div()
+                                            .text_lg()
+                                            .font_weight(FontWeight::BOLD)
+                                            .text_color(rgb(0x1E293B))
+                                            .child("✏️ 文本输入"),
+                                    )
+                                    .child(self.text_input_demo.clone()),
+                            )
+                            // 滚轮演示
+                            .child(
+                                div()
+                                    .flex()
+                                    .flex_col()
+                                    .gap_3()
+                                    .p_4()
+                                    .bg(rgb(0xFFFFFF))
+                                    .rounded_lg()
+                                    .shadow_sm()
+                                    .child(
+                                        div()
+                                            .text_lg()
+                                            .font_weight(FontWeight::BOLD)
+                                            .text_color(rgb(0x1E293B))
+                                            .child("🖱️ 滚轮事件"),
+                                    )
+                                    .child(self.scroll_demo.clone()),
+                            )
+                            // 悬停演示
+                            .child(
+                                div()
+                                    .flex()
+                                    .flex_col()
+                                    .gap_3()
+                                    .p_4()
+                                    .bg(rgb(0xFFFFFF))
+                                    .rounded_lg()
+                                    .shadow_sm()
+                                    .child(
+                                        div()
+                                            .text_lg()
+                                            .font_weight(FontWeight::BOLD)
+                                            .text_color(rgb(0x1E293B))
+                                            .child("👆 悬停状态"),
+                                    )
+                                    .child(self.hover_demo.clone()),
+                            ),
+                    ),
+            )
+            // 知识点总结
+            .child(
+                div()
+                    .p_4()
+                    .rounded_lg()
+                    .bg(rgb(0xFEF3C7))
+                    .border_1()
+                    .border_color(rgb(0xFCD34D))
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(rgb(0x92400E))
+                            .child("💡 关键API：on_click | on_mouse_down/up/move | on_key_down/up | 组合键 observe_pending_input | on_scroll_wheel | hover/active | cx.listener() | track_focus() | EntityInputHandler"),
+                    ),
+            )
+    }
+}
+
+// ============================================================================
+// 测试：组合键序列的匹配、等待提示、超时与打断；右键菜单
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    // 不能 use super::*：gpui::* 中的 test 宏会遮蔽内置的 #[test]
+    use super::{bind_keys, ButtonDemo, KeyboardDemo, MouseTracker, LINE_COUNT};
+    use gpui::{
+        point, px, Entity, Modifiers, MouseButton, TestAppContext, VisualTestContext,
+    };
+    use gpui_components::{
+        load_keymap, pending_chord_bindings, Effect, KeymapDiagnostic, Memo, Signal,
+    };
+    use std::cell::RefCell;
+    use std::path::Path;
+    use std::rc::Rc;
+    use std::time::Duration;
+
+    fn keyboard_demo(cx: &mut TestAppContext) -> (Entity<KeyboardDemo>, &mut VisualTestContext) {
+        cx.update(bind_keys);
+        let (demo, cx) = cx.add_window_view(KeyboardDemo::new);
+        cx.update(|window, cx| demo.read(cx).focus_handle.focus(window));
+        (demo, cx)
+    }
+
+    #[gpui::test]
+    fn chords_dispatch_actions_and_show_the_match(cx: &mut TestAppContext) {
+        let (demo, cx) = keyboard_demo(cx);
+
+        cx.simulate_keystrokes("shift-g");
+        demo.read_with(cx, |demo, _| {
+            assert_eq!(demo.cursor_line, LINE_COUNT);
+            assert_eq!(demo.last_match.as_deref(), Some("shift-g → keyboard::GoToBottom"));
+        });
+
+        cx.simulate_keystrokes("g g");
+        demo.read_with(cx, |demo, _| {
+            assert_eq!(demo.cursor_line, 1);
+            assert_eq!(demo.last_match.as_deref(), Some("g g → keyboard::GoToTop"));
+            assert!(demo.pending_chord.is_empty());
+            assert!(demo.abandoned_chord.is_none());
+        });
+
+        cx.simulate_keystrokes("ctrl-k ctrl-c");
+        demo.read_with(cx, |demo, _| {
+            assert!(demo.line_commented);
+            assert_eq!(demo.last_match.as_deref(), Some("ctrl-k ctrl-c → keyboard::CommentLine"));
+            // 被快捷键消耗的按键不会进入按键历史
+            assert!(demo.key_history.is_empty());
+        });
+    }
+
+    #[gpui::test]
+    fn pending_prefix_is_shown_and_times_out(cx: &mut TestAppContext) {
+        let (demo, cx) = keyboard_demo(cx);
+
+        cx.simulate_keystrokes("ctrl-k");
+        assert_eq!(
+            demo.read_with(cx, |demo, _| super::keystrokes_text(&demo.pending_chord)),
+            "ctrl-k"
+        );
+        let continuations = cx.update(|window, cx| pending_chord_bindings(window, cx).len());
+        assert_eq!(continuations, 2);
+
+        cx.executor().advance_clock(Duration::from_secs(1));
+        demo.read_with(cx, |demo, _| {
+            assert!(demo.pending_chord.is_empty());
+            assert_eq!(demo.abandoned_chord.as_deref(), Some("⏱ ctrl-k 超时，按普通按键处理"));
+            // 超时后前缀按普通按键重新分发
+            assert_eq!(demo.key_history.len(), 1);
+            assert!(!demo.line_commented);
+        });
+    }
+
+    #[gpui::test]
+    fn unrelated_key_interrupts_the_prefix(cx: &mut TestAppContext) {
+        let (demo, cx) = keyboard_demo(cx);
+
+        cx.simulate_keystrokes("shift-g g x");
+        demo.read_with(cx, |demo, _| {
+            assert_eq!(demo.cursor_line, LINE_COUNT);
+            assert_eq!(demo.abandoned_chord.as_deref(), Some("✋ g 被打断，按普通按键处理"));
+            assert_eq!(demo.key_history.len(), 2);
+        });
+
+        // 打断前缀的按键自己匹配了单键绑定时，显示的是这个单键绑定
+        cx.simulate_keystrokes("g g g shift-g");
+        demo.read_with(cx, |demo, _| {
+            assert_eq!(demo.cursor_line, LINE_COUNT);
+            assert_eq!(demo.last_match.as_deref(), Some("shift-g → keyboard::GoToBottom"));
+            assert_eq!(demo.abandoned_chord.as_deref(), Some("✋ g 被打断，按普通按键处理"));
+        });
+    }
+
+    #[gpui::test]
+    fn keymap_file_binds_chords(cx: &mut TestAppContext) {
+        let source = r#"{
+            "KeyboardDemo": {
+                "g t": "keyboard::GoToTop",
+                "g  t": "keyboard::GoToBottom",
+                "ctrl-k ctrl-k": "keyboard::CommentLine"
+            }
+        }"#;
+        cx.update(|cx| {
+            bind_keys(cx);
+            let loaded = load_keymap(Path::new("events.json"), source, cx).unwrap();
+            // 多余的空格不影响比较，"g  t" 与 "g t" 重复
+            assert_eq!(
+                loaded.diagnostics,
+                [KeymapDiagnostic::Duplicate {
+                    context: "KeyboardDemo".into(),
+                    keystroke: "g  t".into(),
+                    first: "g t".into(),
+                }]
+            );
+            cx.bind_keys(loaded.bindings);
+        });
+        let (demo, cx) = cx.add_window_view(KeyboardDemo::new);
+        cx.update(|window, cx| demo.read(cx).focus_handle.focus(window));
+
+        cx.simulate_keystrokes("shift-g g t ctrl-k ctrl-k");
+        demo.read_with(cx, |demo, _| {
+            assert_eq!(demo.cursor_line, 1);
+            assert!(demo.line_commented);
+            assert_eq!(demo.last_match.as_deref(), Some("ctrl-k ctrl-k → keyboard::CommentLine"));
+        });
+    }
+
+    fn button_demo(cx: &mut TestAppContext) -> (Entity<ButtonDemo>, &mut VisualTestContext) {
+        cx.update(bind_keys);
+        let (demo, cx) = cx.add_window_view(|_window, cx| ButtonDemo::new(cx));
+        cx.run_until_parked();
+        (demo, cx)
+    }
+
+    /// 在"右键菜单"按钮上按下右键
+    fn open_context_menu(cx: &mut VisualTestContext) {
+        let bounds = cx.debug_bounds("right-btn").unwrap();
+        cx.simulate_mouse_down(bounds.center(), MouseButton::Right, Modifiers::none());
+        cx.run_until_parked();
+    }
+
+    fn menu_state(demo: &Entity<ButtonDemo>, cx: &mut VisualTestContext) -> Option<(String, usize)> {
+        demo.read_with(cx, |demo, cx| {
+            demo.context_menu.as_ref().map(|(menu, _)| {
+                let menu = menu.read(cx);
+                let selected = menu.selected_label().map(|label| label.to_string());
+                (selected.unwrap_or_default(), menu.depth())
+            })
+        })
+    }
+
+    #[gpui::test]
+    fn right_click_opens_menu_and_dispatches_selection(cx: &mut TestAppContext) {
+        let (demo, cx) = button_demo(cx);
+
+        open_context_menu(cx);
+        assert_eq!(menu_state(&demo, cx), Some((String::new(), 1)));
+        // 菜单画在 deferred 浮层中，同样参与布局和点击测试
+        assert!(cx.debug_bounds("context-menu-item-点击一次").is_some());
+
+        cx.simulate_keystrokes("down enter");
+        assert_eq!(menu_state(&demo, cx), None);
+        demo.read_with(cx, |demo, _| assert_eq!(demo.click_count, 1));
+        // 焦点回到 ButtonDemo，它自己的快捷键继续生效
+        cx.simulate_keystrokes("enter");
+        demo.read_with(cx, |demo, _| assert_eq!(demo.click_count, 2));
+
+        // 鼠标点击菜单项
+        open_context_menu(cx);
+        let item = cx.debug_bounds("context-menu-item-重置计数").unwrap();
+        cx.simulate_click(item.center(), Modifiers::none());
+        cx.run_until_parked();
+        assert_eq!(menu_state(&demo, cx), None);
+        demo.read_with(cx, |demo, _| assert_eq!(demo.click_count, 0));
+    }
+
+    #[gpui::test]
+    fn keyboard_navigation_skips_disabled_items_and_opens_submenus(cx: &mut TestAppContext) {
+        let (demo, cx) = button_demo(cx);
+
+        // 点击次数为 0 时"重置计数"被禁用，分隔线也会被跳过
+        open_context_menu(cx);
+        cx.simulate_keystrokes("down down");
+        assert_eq!(menu_state(&demo, cx), Some(("复制信息".into(), 1)));
+        cx.simulate_keystrokes("down");
+        assert_eq!(menu_state(&demo, cx), Some(("点击一次".into(), 1)));
+        cx.simulate_keystrokes("up");
+        assert_eq!(menu_state(&demo, cx), Some(("复制信息".into(), 1)));
+
+        cx.simulate_keystrokes("right");
+        assert_eq!(menu_state(&demo, cx), Some(("复制点击次数".into(), 2)));
+        cx.simulate_keystrokes("left");
+        assert_eq!(menu_state(&demo, cx), Some(("复制信息".into(), 1)));
+
+        cx.simulate_keystrokes("enter down enter");
+        assert_eq!(menu_state(&demo, cx), None);
+        assert_eq!(
+            cx.read_from_clipboard().and_then(|item| item.text()).as_deref(),
+            Some("右键按下")
+        );
+        demo.read_with(cx, |demo, _| assert_eq!(demo.last_event, "菜单: 已复制最后事件"));
+    }
+
+    #[gpui::test]
+    fn escape_and_outside_click_close_the_menu(cx: &mut TestAppContext) {
+        let (demo, cx) = button_demo(cx);
+
+        open_context_menu(cx);
+        cx.simulate_keystrokes("escape");
+        assert_eq!(menu_state(&demo, cx), None);
+        let focused = cx.update(|window, cx| demo.read(cx).focus_handle.is_focused(window));
+        assert!(focused);
+
+        open_context_menu(cx);
+        cx.simulate_mouse_down(point(px(1.), px(1.)), MouseButton::Left, Modifiers::none());
+        cx.run_until_parked();
+        assert_eq!(menu_state(&demo, cx), None);
+
+        // 菜单打开时再次右键：旧菜单关闭，新菜单打开
+        open_context_menu(cx);
+        let first = demo.read_with(cx, |demo, _| demo.context_menu.clone().unwrap().0);
+        open_context_menu(cx);
+        let second = demo.read_with(cx, |demo, _| demo.context_menu.clone().unwrap().0);
+        assert_ne!(first, second);
+    }
+
+    #[gpui::test]
+    fn mouse_move_rerenders_only_the_labels_that_read_the_position(cx: &mut TestAppContext) {
+        let (tracker, cx) = cx.add_window_view(|_, cx| MouseTracker::new(cx));
+        let (position_label, grid_label) =
+            tracker.read_with(cx, |tracker, _| (tracker.position_label.clone(), tracker.grid_label.clone()));
+        let renders = |cx: &mut VisualTestContext| {
+            let position = position_label.read_with(cx, |label, _| label.renders);
+            let grid = grid_label.read_with(cx, |label, _| label.renders);
+            (position, grid)
+        };
+
+        cx.simulate_mouse_move(point(px(10.), px(10.)), None, Modifiers::none());
+        cx.run_until_parked();
+        let (position, grid) = renders(cx);
+
+        // 同一个格子里移动：位置标签重新渲染，网格 Memo 的值没变，网格标签被复用
+        cx.simulate_mouse_move(point(px(20.), px(30.)), None, Modifiers::none());
+        cx.run_until_parked();
+        assert_eq!(renders(cx), (position + 1, grid));
+
+        // 进入另一个格子：两个标签都重新渲染
+        cx.simulate_mouse_move(point(px(60.), px(30.)), None, Modifiers::none());
+        cx.run_until_parked();
+        assert_eq!(renders(cx), (position + 2, grid + 1));
+        grid_label.read_with(cx, |label, cx| assert_eq!(*label.cell.peek(cx), Some((1, 0))));
+    }
+
+    #[gpui::test]
+    fn memo_and_effect_rerun_only_when_their_inputs_change(cx: &mut TestAppContext) {
+        let count = cx.update(|cx| Signal::new(1, cx));
+        let parity = cx.update(|cx| {
+            let count = count.clone();
+            Memo::new(cx, move |cx| count.get(cx) % 2)
+        });
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let effect = cx.update(|cx| {
+            let parity = parity.clone();
+            let seen = seen.clone();
+            Effect::new(cx, move |cx| seen.borrow_mut().push(parity.get(cx)))
+        });
+        assert_eq!(*seen.borrow(), [1]);
+
+        // Memo 重新计算，但奇偶性没变，Effect 不运行
+        cx.update(|cx| count.set(3, cx));
+        cx.run_until_parked();
+        cx.update(|cx| {
+            assert_eq!(parity.computations(cx), 2);
+            assert_eq!(effect.runs(cx), 1);
+        });
+
+        cx.update(|cx| count.set(4, cx));
+        cx.run_until_parked();
+        assert_eq!(*seen.borrow(), [1, 0]);
+
+        // 写入相同的值不通知任何读者
+        cx.update(|cx| count.set(4, cx));
+        cx.run_until_parked();
+        cx.update(|cx| assert_eq!(parity.computations(cx), 3));
+
+        // 丢弃 Effect 后不再运行
+        drop(effect);
+        cx.update(|cx| count.set(5, cx));
+        cx.run_until_parked();
+        assert_eq!(*seen.borrow(), [1, 0]);
+        cx.update(|cx| assert_eq!(*parity.peek(cx), 1));
+    }
+}
@@ -0,0 +1,2337 @@
+// 第六章：GPUI 高级主题
+// ======================
+// 本章学习 GPUI 的高级特性：
+// 1. Action 系统 - 命令模式与键盘快捷键
+// 2. 异步操作 - cx.spawn() 和 Task
+// 3. 焦点管理 - FocusHandle 详解
+// 4. Global 状态 - 全局数据管理
+// 5. 用户快捷键文件 - 按名字加载 Action 并报告无效条目
+// 6. Action 宏 - 录制分发的 Action 并回放
+// 7. 共享日志面板 - 多个组件写入同一个 LogPanel 实体
+// 8. Store - 计数集中在单一状态中，由纯函数 reduce 修改，中间件负责日志和持久化
+// 9. Signal / Effect - 异步任务写入 Signal，只通知读取它的视图；日志由 Effect 写入
+
+use gpui::prelude::FluentBuilder;
+use gpui::*;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use gpui_components::{
+    command_palette, file_from_args, text_input, ActionRecorder, ActionScript, ActiveTheme, ButtonVariant,
+    CommandPalette, Effect, KeymapFileStatus, LogMiddleware, LogPanel, PersistMiddleware, PlaybackSpeed,
+    RecordActions, Signal, Store, StyledButton, Theme, ThemeFileStatus,
+};
+use std::path::PathBuf;
+use std::time::Duration;
+
+// ============================================================================
+// 第一部分：Action 系统
+// ============================================================================
+
+// 使用 actions! 宏定义简单的 Action
+// 格式: actions!(namespace, [ActionName1, ActionName2, ...])
+actions!(
+    app,
+    [
+        Increment,      // 增加计数
+        Decrement,      // 减少计数
+        Reset,          // 重置
+        ToggleTheme,    // 切换主题
+        FocusNext,      // 焦点移到下一个区域
+        FocusPrevious,  // 焦点移到上一个区域
+    ]
+);
+
+/// ActionDemo 的键盘上下文：快捷键只在它或它的子元素获得焦点时生效
+const ACTION_DEMO_CONTEXT: &str = "ActionDemo";
+
+/// FocusDemo 的键盘上下文
+const FOCUS_DEMO_CONTEXT: &str = "FocusDemo";
+
+/// 声明式 keymap：按键 -> Action（+ 上下文谓词）
+///
+/// 按键先经过 keymap 匹配，命中后沿焦点路径分发 Action，视图只需要注册 on_action，
+/// 不必在 on_key_down 里手写 match。同一个 Action 可以绑定多个按键，
+/// 修改绑定也不需要改动视图代码。
+pub fn bind_keys(cx: &mut App) {
+    cx.bind_keys([
+        KeyBinding::new("up", Increment, Some(ACTION_DEMO_CONTEXT)),
+        KeyBinding::new("k", Increment, Some(ACTION_DEMO_CONTEXT)),
+        KeyBinding::new("down", Decrement, Some(ACTION_DEMO_CONTEXT)),
+        KeyBinding::new("j", Decrement, Some(ACTION_DEMO_CONTEXT)),
+        KeyBinding::new("r", Reset, Some(ACTION_DEMO_CONTEXT)),
+        KeyBinding::new("shift-up", AddDelta { delta: 10 }, Some(ACTION_DEMO_CONTEXT)),
+        KeyBinding::new("shift-down", AddDelta { delta: -10 }, Some(ACTION_DEMO_CONTEXT)),
+        KeyBinding::new("tab", FocusNext, Some(FOCUS_DEMO_CONTEXT)),
+        KeyBinding::new("right", FocusNext, Some(FOCUS_DEMO_CONTEXT)),
+        KeyBinding::new("down", FocusNext, Some(FOCUS_DEMO_CONTEXT)),
+        KeyBinding::new("shift-tab", FocusPrevious, Some(FOCUS_DEMO_CONTEXT)),
+        KeyBinding::new("left", FocusPrevious, Some(FOCUS_DEMO_CONTEXT)),
+        KeyBinding::new("up", FocusPrevious, Some(FOCUS_DEMO_CONTEXT)),
+        // 没有上下文：在窗口的任何位置都生效
+        KeyBinding::new("secondary-t", ToggleTheme, None),
+    ]);
+    // 数字键 0-9：同一个 Action 类型，参数不同
+    cx.bind_keys((0..10).map(|value| {
+        KeyBinding::new(&value.to_string(), SetValue { value }, Some(ACTION_DEMO_CONTEXT))
+    }));
+    // 命令面板（包括其中的输入框）的快捷键
+    text_input::init(cx);
+    command_palette::init(cx);
+}
+
+/// 初始化本章：主题、快捷键和全局 Action 处理器，main 和测试都调用它
+pub fn init(cx: &mut App) {
+    Theme::init(cx);
+    bind_keys(cx);
+    // ToggleTheme 不属于某个视图，注册为全局 Action：无论焦点在哪里都能处理
+    cx.on_action(|_: &ToggleTheme, cx| Theme::toggle(cx));
+}
+
+/// 从 keymap 中查出 Action 的所有快捷键，用于界面提示，例如 "up / K"
+fn shortcut_text(action: &dyn Action, cx: &App) -> String {
+    let keymap = cx.key_bindings();
+    let keymap = keymap.borrow();
+    keymap
+        .bindings_for_action(action)
+        .map(|binding| {
+            binding
+                .keystrokes()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect::<Vec<_>>()
+        .join(" / ")
+}
+
+// 带参数的 Action：用 #[derive(Action)] 生成 Action trait 的实现
+// - Deserialize：keymap 文件中的 JSON 参数通过 serde 反序列化成结构体
+// - JsonSchema：描述参数结构，供编辑器补全和校验 keymap 使用
+// - Serialize：录制 Action 宏时把参数保存为 JSON
+// 与 actions! 宏一样，派生的 Action 会自动注册，可以用 "app::SetValue" 按名字构造
+
+/// 把计数设置为指定值
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize, JsonSchema, Action)]
+#[action(namespace = app)]
+pub struct SetValue {
+    pub value: i32,
+}
+
+/// 在计数上加一个增量（可以为负）
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize, JsonSchema, Action)]
+#[action(namespace = app)]
+pub struct AddDelta {
+    pub delta: i32,
+}
+
+/// 聚焦 FocusDemo 中指定的区域（从 0 开始）
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize, JsonSchema, Action)]
+#[action(namespace = app)]
+pub struct FocusArea {
+    pub index: usize,
+}
+
+// ============================================================================
+// 第二部分：Action 演示组件
+// ============================================================================
+
+/// ActionDemo 的全部状态：视图只保存从 Store 中选出的值
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct CounterState {
+    pub count: i32,
+}
+
+/// 修改计数的消息，每个 Action 处理器对应一条
+#[derive(Clone, Debug, PartialEq)]
+pub enum CounterMsg {
+    Increment,
+    Decrement,
+    Reset,
+    SetValue(i32),
+    AddDelta(i32),
+}
+
+/// 日志中显示的消息名，例如 "SetValue(7)"、"AddDelta(+10)"
+impl std::fmt::Display for CounterMsg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CounterMsg::Increment => write!(f, "Increment"),
+            CounterMsg::Decrement => write!(f, "Decrement"),
+            CounterMsg::Reset => write!(f, "Reset"),
+            CounterMsg::SetValue(value) => write!(f, "SetValue({})", value),
+            CounterMsg::AddDelta(delta) => write!(f, "AddDelta({:+})", delta),
+        }
+    }
+}
+
+/// 纯函数：新状态只由旧状态和消息决定，可以脱离 GPUI 单独测试
+pub fn reduce_counter(state: &CounterState, msg: &CounterMsg) -> CounterState {
+    let count = match *msg {
+        CounterMsg::Increment => state.count + 1,
+        CounterMsg::Decrement => state.count - 1,
+        CounterMsg::Reset => 0,
+        CounterMsg::SetValue(value) => value,
+        CounterMsg::AddDelta(delta) => state.count + delta,
+    };
+    CounterState { count }
+}
+
+pub type CounterStore = Store<CounterState, CounterMsg>;
+
+struct ActionDemo {
+    /// 计数的唯一来源，修改都通过 dispatch
+    store: Entity<CounterStore>,
+    /// 从 Store 中选出的计数，只在它变化时更新
+    count: i32,
+    focus_handle: FocusHandle,
+    /// 共享的日志面板，持久化的读写错误也写在这里
+    logs: Entity<LogPanel>,
+    /// 录制器：分发到这里的 Action 会被录制
+    recorder: Option<Entity<ActionRecorder>>,
+    _store_subscription: Subscription,
+}
+
+impl ActionDemo {
+    fn new(logs: Entity<LogPanel>, cx: &mut Context<Self>) -> Self {
+        // 共享的日志面板交给日志中间件，以 "ActionDemo" 为来源写入，处理器中不再逐个调用 log
+        let store = cx.new(|_| {
+            Store::new(CounterState::default(), reduce_counter).with_middleware(LogMiddleware::new(
+                logs.clone(),
+                ACTION_DEMO_CONTEXT,
+                |msg: &CounterMsg, state: &CounterState| format!("{} → {}", msg, state.count),
+            ))
+        });
+        let (count, store_subscription) =
+            Store::select(&store, cx, |state| state.count, |demo: &mut Self, count, cx| {
+                demo.count = *count;
+                cx.notify();
+            });
+        Self {
+            store,
+            count,
+            focus_handle: cx.focus_handle(),
+            logs,
+            recorder: None,
+            _store_subscription: store_subscription,
+        }
+    }
+
+    fn with_recorder(mut self, recorder: Entity<ActionRecorder>) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    /// 计数保存到 path，并先读回上次保存的状态（main 中根据 --state 参数设置）
+    fn persist_to(&mut self, path: PathBuf, cx: &mut Context<Self>) {
+        let persist = PersistMiddleware::new(path.clone(), self.logs.clone());
+        let loaded = persist.load();
+        if let Err(err) = &loaded
+            && err.kind() != std::io::ErrorKind::NotFound
+        {
+            // 第一次运行时文件还不存在，从默认状态开始；其他错误写入日志面板
+            let message = format!("读取 {} 失败: {}", path.display(), err);
+            self.logs.update(cx, |logs, cx| logs.error(ACTION_DEMO_CONTEXT, message, cx));
+        }
+        self.store.update(cx, |store, cx| {
+            if let Ok(state) = loaded {
+                store.replace_state(state, cx);
+            }
+            store.add_middleware(persist);
+        });
+    }
+
+    fn dispatch(&mut self, msg: CounterMsg, cx: &mut Context<Self>) {
+        self.store.update(cx, |store, cx| store.dispatch(msg, cx));
+    }
+
+    // Action 处理方法 - 签名: &mut self, action: &ActionType, window: &mut Window, cx: &mut Context<Self>
+    // 处理器只把 Action 翻译成消息，状态如何变化由 reduce_counter 决定
+    fn handle_increment(&mut self, _: &Increment, _window: &mut Window, cx: &mut Context<Self>) {
+        self.dispatch(CounterMsg::Increment, cx);
+    }
+
+    fn handle_decrement(&mut self, _: &Decrement, _window: &mut Window, cx: &mut Context<Self>) {
+        self.dispatch(CounterMsg::Decrement, cx);
+    }
+
+    fn handle_reset(&mut self, _: &Reset, _window: &mut Window, cx: &mut Context<Self>) {
+        self.dispatch(CounterMsg::Reset, cx);
+    }
+
+    // 带参数的 Action：处理器直接读取 Action 中的字段
+    fn handle_set_value(&mut self, action: &SetValue, _window: &mut Window, cx: &mut Context<Self>) {
+        self.dispatch(CounterMsg::SetValue(action.value), cx);
+    }
+
+    fn handle_add_delta(&mut self, action: &AddDelta, _window: &mut Window, cx: &mut Context<Self>) {
+        self.dispatch(CounterMsg::AddDelta(action.delta), cx);
+    }
+}
+
+impl Render for ActionDemo {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        // contains_focused：焦点在子元素上时快捷键同样生效
+        let has_focus = self.focus_handle.contains_focused(window, cx);
+        let increment_keys = shortcut_text(&Increment, cx);
+        let decrement_keys = shortcut_text(&Decrement, cx);
+        let reset_keys = shortcut_text(&Reset, cx);
+        let add_ten_keys = shortcut_text(&AddDelta { delta: 10 }, cx);
+        let subtract_ten_keys = shortcut_text(&AddDelta { delta: -10 }, cx);
+        let theme = cx.theme().clone();
+
+        div()
+            .id("action-demo")
+            .flex()
+            .flex_col()
+            .gap_4()
+            .p_4()
+            .rounded_lg()
+            .border_2()
+            .when(has_focus, |s| s.border_color(theme.accent).bg(theme.accent.opacity(0.08)))
+            .when(!has_focus, |s| s.border_color(theme.border).bg(theme.surface))
+            .track_focus(&self.focus_handle)
+            // 注册 Action 处理器
+            .on_action(cx.listener(Self::handle_increment))
+            .on_action(cx.listener(Self::handle_decrement))
+            .on_action(cx.listener(Self::handle_reset))
+            .on_action(cx.listener(Self::handle_set_value))
+            .on_action(cx.listener(Self::handle_add_delta))
+            // 捕获阶段录制：只录制本组件处理的 Action，不影响命令面板列出的命令
+            .when_some(self.recorder.clone(), |el, recorder| {
+                el.record_action::<Increment>(&recorder)
+                    .record_action::<Decrement>(&recorder)
+                    .record_action::<Reset>(&recorder)
+                    .record_action_with_args::<SetValue>(&recorder)
+                    .record_action_with_args::<AddDelta>(&recorder)
+            })
+            // 快捷键（包括数字键）由 bind_keys() 中的 keymap 决定，这里只声明上下文
+            .key_context(ACTION_DEMO_CONTEXT)
+            .on_click(cx.listener(|view, _, window, cx| {
+                view.focus_handle.focus(window);
+                cx.notify();
+            }))
+            // 提示
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(if has_focus { theme.accent } else { theme.subtext })
+                    .font_weight(if has_focus { FontWeight::BOLD } else { FontWeight::NORMAL })
+                    .child(if has_focus {
+                        format!(
+                            "🎮 Action 区域已聚焦 - 增加 {} · 减少 {} · 重置 {} · 数字键直接设置",
+                            increment_keys, decrement_keys, reset_keys
+                        )
+                    } else {
+                        "👆 点击此区域以启用快捷键".to_string()
+                    }),
+            )
+            // 计数显示
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .justify_center()
+                    .h(px(80.0))
+                    .bg(theme.surface_muted)
+                    .rounded_lg()
+                    .text_3xl()
+                    .font_weight(FontWeight::BOLD)
+                    .text_color(theme.success)
+                    .child(format!("{}", self.count)),
+            )
+            // 按钮组
+            .child(
+                div()
+                    .flex()
+                    .gap_2()
+                    .justify_center()
+                    .child(
+                        StyledButton::new("btn-dec", format!("- 减少 ({})", decrement_keys))
+                            .variant(ButtonVariant::Danger)
+                            .on_click(cx.listener(|_view, _, window, cx| {
+                                window.dispatch_action(Decrement.boxed_clone(), cx);
+                            })),
+                    )
+                    .child(
+                        StyledButton::new("btn-reset", format!("重置 ({})", reset_keys))
+                            .variant(ButtonVariant::Secondary)
+                            .on_click(cx.listener(|_view, _, window, cx| {
+                                window.dispatch_action(Reset.boxed_clone(), cx);
+                            })),
+                    )
+                    .child(
+                        StyledButton::new("btn-inc", format!("+ 增加 ({})", increment_keys))
+                            .variant(ButtonVariant::Success)
+                            .on_click(cx.listener(|_view, _, window, cx| {
+                                window.dispatch_action(Increment.boxed_clone(), cx);
+                            })),
+                    ),
+            )
+            // 带参数的 Action：按钮分发时构造带字段的 Action
+            .child(
+                div()
+                    .flex()
+                    .gap_2()
+                    .justify_center()
+                    .child(
+                        StyledButton::new("btn-sub-ten", format!("-10 ({})", subtract_ten_keys))
+                            .variant(ButtonVariant::Secondary)
+                            .on_click(cx.listener(|_view, _, window, cx| {
+                                window.dispatch_action(AddDelta { delta: -10 }.boxed_clone(), cx);
+                            })),
+                    )
+                    .child(
+                        StyledButton::new("btn-set-hundred", "设为 100")
+                            .variant(ButtonVariant::Secondary)
+                            .on_click(cx.listener(|_view, _, window, cx| {
+                                window.dispatch_action(SetValue { value: 100 }.boxed_clone(), cx);
+                            })),
+                    )
+                    .child(
+                        StyledButton::new("btn-add-ten", format!("+10 ({})", add_ten_keys))
+                            .variant(ButtonVariant::Secondary)
+                            .on_click(cx.listener(|_view, _, window, cx| {
+                                window.dispatch_action(AddDelta { delta: 10 }.boxed_clone(), cx);
+                            })),
+                    ),
+            )
+    }
+}
+
+// ============================================================================
+// 第三部分：异步操作演示
+// ============================================================================
+//
+// 状态保存在 Signal 中，异步任务只写入 Signal，不需要 cx.notify()。
+// 进度每 200ms 变化一次，只有读取它的 ProgressView 被通知；
+// 加载开始/结束写日志是副作用，交给 Effect：它读取 loading，loading 变化时运行。
+
+struct AsyncDemo {
+    /// 加载状态
+    loading: Signal<bool>,
+    /// 加载结果
+    result: Signal<Option<String>>,
+    /// 进度 (0-100)
+    progress: Signal<i32>,
+    progress_view: Entity<ProgressView>,
+    /// loading 变化时写日志，保存以防止被 drop
+    _log_effect: Effect,
+    /// 任务句柄（保存以防止被 drop）
+    _task: Option<Task<()>>,
+}
+
+/// AsyncDemo 写入共享日志面板时使用的来源
+const ASYNC_DEMO_SOURCE: &str = "AsyncDemo";
+
+impl AsyncDemo {
+    fn new(logs: Entity<LogPanel>, cx: &mut Context<Self>) -> Self {
+        let loading = Signal::new(false, cx);
+        let result: Signal<Option<String>> = Signal::new(None, cx);
+        let progress = Signal::new(0, cx);
+
+        // Effect 创建时立即运行一次，这时只记录初始值，不写日志
+        let mut was_loading = None;
+        let log_effect = Effect::new(cx, {
+            let loading = loading.clone();
+            let result = result.clone();
+            move |cx| {
+                let is_loading = loading.get(cx);
+                if was_loading.replace(is_loading).is_none() {
+                    return;
+                }
+                // 用 peek 读取结果：result 不是依赖，它单独变化时不写日志
+                let message = if is_loading {
+                    "开始加载".to_string()
+                } else {
+                    result.peek(cx).clone().unwrap_or_else(|| "加载结束".to_string())
+                };
+                logs.update(cx, |logs, cx| logs.info(ASYNC_DEMO_SOURCE, message, cx));
+            }
+        });
+
+        Self {
+            progress_view: cx.new(|cx| {
+                // 进度条用 cached 渲染，父视图重绘时不会跟着渲染，需要自己观察主题变化
+                cx.observe_global::<Theme>(|_, cx| cx.notify()).detach();
+                ProgressView {
+                    progress: progress.clone(),
+                    renders: 0,
+                }
+            }),
+            loading,
+            result,
+            progress,
+            _log_effect: log_effect,
+            _task: None,
+        }
+    }
+
+    /// 模拟异步加载
+    fn start_loading(&mut self, cx: &mut Context<Self>) {
+        self.loading.set(true, cx);
+        self.progress.set(0, cx);
+        self.result.set(None, cx);
+
+        // cx.spawn 用于在视图上下文中启动异步任务
+        let task = cx.spawn(async |weak_view: WeakEntity<Self>, cx: &mut AsyncApp| {
+            // 模拟分步加载
+            for i in 1..=10 {
+                // 模拟网络延迟
+                cx.background_executor().timer(Duration::from_millis(200)).await;
+                
+                // 更新进度
+                let _ = weak_view.update(cx, |view, cx| {
+                    view.progress.set(i * 10, cx);
+                });
+            }
+
+            // 加载完成：先写结果，日志 Effect 在 loading 变化后运行时读到的就是它
+            let _ = weak_view.update(cx, |view, cx| {
+                view.result.set(Some(format!("加载完成！时间: {:?}", std::time::SystemTime::now())), cx);
+                view.loading.set(false, cx);
+            });
+        });
+
+        // 保存任务句柄，防止被 drop
+        self._task = Some(task);
+    }
+
+    /// 取消加载
+    fn cancel_loading(&mut self, cx: &mut Context<Self>) {
+        self._task = None; // drop task 会取消它
+        self.result.set(Some("已取消".to_string()), cx);
+        self.loading.set(false, cx);
+        self.progress.set(0, cx);
+    }
+}
+
+impl Render for AsyncDemo {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let loading = self.loading.get(cx);
+        let theme = cx.theme().clone();
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_4()
+            // 状态显示
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .child(
+                        div()
+                            .w(px(12.0))
+                            .h(px(12.0))
+                            .rounded_full()
+                            .bg(if loading { theme.warning } else { theme.success }),
+                    )
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(theme.text)
+                            .child(if loading { "加载中..." } else { "就绪" }),
+                    ),
+            )
+            // 进度条：cached 渲染，AsyncDemo 重新渲染时复用没有变化的进度条
+            .when(loading, |el| {
+                el.child(
+                    AnyView::from(self.progress_view.clone())
+                        .cached(StyleRefinement::default().w_full().h(px(32.0))),
+                )
+            })
+            // 结果显示
+            .when_some(self.result.get(cx), |el, result| {
+                el.child(
+                    div()
+                        .p_3()
+                        .bg(theme.success.opacity(0.15))
+                        .rounded_md()
+                        .text_sm()
+                        .text_color(theme.success)
+                        .child(result),
+                )
+            })
+            // 按钮
+            .child(
+                div()
+                    .flex()
+                    .gap_2()
+                    .child(
+                        // disabled 会屏蔽 hover/active 样式并吞掉点击，无需再用 when 包裹 on_click
+                        StyledButton::new("btn-start", "开始加载")
+                            .variant(ButtonVariant::Primary)
+                            .disabled(loading)
+                            .on_click(cx.listener(|view, _, _window, cx| {
+                                view.start_loading(cx);
+                            })),
+                    )
+                    .child(
+                        StyledButton::new("btn-cancel", "取消")
+                            .variant(ButtonVariant::Danger)
+                            .disabled(!loading)
+                            .on_click(cx.listener(|view, _, _window, cx| {
+                                view.cancel_loading(cx);
+                            })),
+                    ),
+            )
+    }
+}
+
+/// 进度条，只读取 progress
+struct ProgressView {
+    progress: Signal<i32>,
+    /// 渲染次数，用来验证进度变化只让它重新渲染
+    renders: usize,
+}
+
+impl Render for ProgressView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        self.renders += 1;
+        let progress = self.progress.get(cx);
+        let theme = cx.theme();
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_1()
+            .child(
+                div()
+                    .w_full()
+                    .h(px(8.0))
+                    .bg(theme.border)
+                    .rounded_full()
+                    .overflow_hidden()
+                    .child(
+                        div()
+                            .h_full()
+                            .w(relative(progress as f32 / 100.0))
+                            .bg(theme.accent)
+                            .rounded_full(),
+                    ),
+            )
+            .child(
+                div()
+                    .text_center()
+                    .text_sm()
+                    .text_color(theme.subtext)
+                    .child(format!("{}%", progress)),
+            )
+    }
+}
+
+// ============================================================================
+// 第四部分：焦点管理演示
+// ============================================================================
+
+struct FocusDemo {
+    /// 整个组件的焦点句柄，回放 Action 宏时先聚焦它
+    focus_handle: FocusHandle,
+    /// 多个可聚焦区域的焦点句柄
+    focus_handles: Vec<FocusHandle>,
+    /// 共享的日志面板，以 "FocusDemo" 为来源写入
+    logs: Entity<LogPanel>,
+    recorder: Option<Entity<ActionRecorder>>,
+}
+
+impl FocusDemo {
+    fn new(logs: Entity<LogPanel>, cx: &mut Context<Self>) -> Self {
+        // 创建多个焦点句柄
+        let focus_handles: Vec<FocusHandle> = (0..4)
+            .map(|_| cx.focus_handle())
+            .collect();
+
+        Self {
+            focus_handle: cx.focus_handle(),
+            focus_handles,
+            logs,
+            recorder: None,
+        }
+    }
+
+    fn with_recorder(mut self, recorder: Entity<ActionRecorder>) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    /// 焦点所在区域：直接查询焦点句柄，不另外保存一份可能过期的索引
+    fn current_index(&self, window: &Window) -> Option<usize> {
+        self.focus_handles
+            .iter()
+            .position(|handle| handle.is_focused(window))
+    }
+
+    fn log_focus(&mut self, msg: &str, cx: &mut Context<Self>) {
+        self.logs
+            .update(cx, |logs, cx| logs.info(FOCUS_DEMO_CONTEXT, msg.to_string(), cx));
+    }
+
+    /// 聚焦第 index 个区域，焦点发生变化时写日志
+    ///
+    /// 所有焦点切换都从 Action 处理器走到这里，render 只读取焦点状态
+    fn focus_area(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(handle) = self.focus_handles.get(index) else {
+            return;
+        };
+        if !handle.is_focused(window) {
+            handle.focus(window);
+            self.log_focus(&format!("聚焦到区域 {}", index + 1), cx);
+        }
+        cx.notify();
+    }
+
+    fn focus_next(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let next = match self.current_index(window) {
+            Some(i) => (i + 1) % self.focus_handles.len(),
+            None => 0,
+        };
+        self.focus_area(next, window, cx);
+    }
+
+    fn focus_prev(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let prev = match self.current_index(window) {
+            Some(i) => {
+                if i == 0 {
+                    self.focus_handles.len() - 1
+                } else {
+                    i - 1
+                }
+            }
+            None => self.focus_handles.len() - 1,
+        };
+        self.focus_area(prev, window, cx);
+    }
+
+    fn handle_focus_next(&mut self, _: &FocusNext, window: &mut Window, cx: &mut Context<Self>) {
+        self.focus_next(window, cx);
+    }
+
+    fn handle_focus_previous(&mut self, _: &FocusPrevious, window: &mut Window, cx: &mut Context<Self>) {
+        self.focus_prev(window, cx);
+    }
+
+    fn handle_focus_area(&mut self, action: &FocusArea, window: &mut Window, cx: &mut Context<Self>) {
+        self.focus_area(action.index, window, cx);
+    }
+}
+
+impl Render for FocusDemo {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let focused_index = self.current_index(window);
+        let theme = cx.theme().clone();
+        // 每个区域聚焦时的强调色，背景是同一颜色的浅色版本
+        let colors = [
+            ("区域 1", theme.danger),
+            ("区域 2", theme.warning),
+            ("区域 3", theme.success),
+            ("区域 4", theme.accent),
+        ];
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_4()
+            .track_focus(&self.focus_handle)
+            .key_context(FOCUS_DEMO_CONTEXT)
+            // 键盘导航：Tab/方向键由 bind_keys() 绑定到 FocusNext / FocusPrevious
+            .on_action(cx.listener(Self::handle_focus_next))
+            .on_action(cx.listener(Self::handle_focus_previous))
+            .on_action(cx.listener(Self::handle_focus_area))
+            .when_some(self.recorder.clone(), |el, recorder| {
+                el.record_action::<FocusNext>(&recorder)
+                    .record_action::<FocusPrevious>(&recorder)
+                    .record_action_with_args::<FocusArea>(&recorder)
+            })
+            // 说明
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(theme.subtext)
+                    .child("点击区域聚焦，按 Tab/Shift+Tab 切换焦点，按方向键导航"),
+            )
+            // 焦点区域网格
+            .child(
+                div()
+                    .grid()
+                    .grid_cols(2)
+                    .gap_3()
+                    .children(self.focus_handles.iter().enumerate().map(|(i, handle)| {
+                        let is_focused = handle.is_focused(window);
+                        let (label, border_color) = colors[i];
+                        let handle_clone = handle.clone();
+
+                        div()
+                            .id(SharedString::from(format!("focus-area-{}", i)))
+                            .flex()
+                            .items_center()
+                            .justify_center()
+                            .h(px(80.0))
+                            .rounded_lg()
+                            .cursor_pointer()
+                            .border_2()
+                            .when(is_focused, |s| {
+                                s.bg(border_color.opacity(0.15))
+                                    .border_color(border_color)
+                                    .shadow_lg()
+                            })
+                            .when(!is_focused, |s| {
+                                s.bg(theme.surface_muted)
+                                    .border_color(theme.border)
+                            })
+                            .track_focus(&handle_clone)
+                            // 点击也通过 Action 聚焦，这样录制的宏里包含点击了哪个区域
+                            .on_click(cx.listener(move |view, _, window, cx| {
+                                view.focus_handle.focus(window);
+                                window.dispatch_action(FocusArea { index: i }.boxed_clone(), cx);
+                            }))
+                            .child(
+                                div()
+                                    .text_lg()
+                                    .font_weight(if is_focused { FontWeight::BOLD } else { FontWeight::NORMAL })
+                                    .text_color(if is_focused { border_color } else { theme.subtext })
+                                    .child(label),
+                            )
+                    })),
+            )
+            // 焦点日志
+            .child(
+                div()
+                    .p_2()
+                    .bg(theme.surface_muted)
+                    .rounded_md()
+                    .text_xs()
+                    .child(
+                        div()
+                            .flex()
+                            .flex_col()
+                            .gap_1()
+                            .child(
+                                div()
+                                    .font_weight(FontWeight::SEMIBOLD)
+                                    .text_color(theme.text)
+                                    .child(format!("当前焦点: {:?}", focused_index.map(|i| i + 1))),
+                            ),
+                    ),
+            )
+    }
+}
+
+// ============================================================================
+// 第五部分：Global 状态演示
+// ============================================================================
+
+// 全局主题 Theme 定义在 gpui_components 中，保存语义化的设计令牌：
+// background / surface / text / subtext / accent / danger / success / border
+// 以及 radius、spacing 尺寸表。视图通过 cx.theme() 读取，不再自己判断 is_dark。
+
+struct GlobalDemo {
+    local_count: i32,
+}
+
+impl GlobalDemo {
+    fn new() -> Self {
+        Self { local_count: 0 }
+    }
+}
+
+impl Render for GlobalDemo {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        // 读取全局主题
+        let theme = cx.theme().clone();
+
+        div()
+            .flex()
+            .flex_col()
+            .gap(theme.spacing.lg)
+            .p(theme.spacing.lg)
+            .rounded(theme.radius.lg)
+            .bg(theme.surface)
+            // 主题显示
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .child(
+                        div()
+                            .text_lg()
+                            .text_color(theme.text)
+                            .child(if theme.is_dark() { "🌙 深色主题" } else { "☀️ 浅色主题" }),
+                    ),
+            )
+            // 本地状态
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .child(
+                        div()
+                            .text_color(theme.text)
+                            .child(format!("本地计数: {}", self.local_count)),
+                    )
+                    .child(
+                        div()
+                            .id("local-inc")
+                            .px_3()
+                            .py_1()
+                            .bg(theme.accent)
+                            .text_color(theme.accent_foreground)
+                            .rounded(theme.radius.md)
+                            .cursor_pointer()
+                            .child("+1")
+                            .on_click(cx.listener(|view, _, _window, cx| {
+                                view.local_count += 1;
+                                cx.notify();
+                            })),
+                    ),
+            )
+            // 切换主题按钮
+            .child(
+                div()
+                    .id("toggle-theme")
+                    .px_4()
+                    .py_2()
+                    .bg(theme.accent)
+                    .text_color(theme.accent_foreground)
+                    .rounded(theme.radius.md)
+                    .cursor_pointer()
+                    .text_center()
+                    .hover(|s| s.opacity(0.9))
+                    .child(format!(
+                        "切换主题 (全局状态, {})",
+                        shortcut_text(&ToggleTheme, cx)
+                    ))
+                    .on_click(|_, window, cx| {
+                        // 分发全局 Action，由 init() 中注册的 cx.on_action 更新全局状态
+                        window.dispatch_action(ToggleTheme.boxed_clone(), cx);
+                    }),
+            )
+            // 说明
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(theme.subtext)
+                    .child("💡 Global 状态在所有组件间共享，切换主题会影响其他使用该状态的组件"),
+            )
+    }
+}
+
+// ============================================================================
+// 第六部分：Keymap 诊断面板
+// ============================================================================
+
+// 用户快捷键文件由 gpui_components::watch_keymap_file 加载，
+// 每次加载的结果保存在全局 KeymapFileStatus 中，面板观察它并列出被跳过的条目。
+
+struct KeymapDiagnosticsPanel;
+
+impl KeymapDiagnosticsPanel {
+    fn new(cx: &mut Context<Self>) -> Self {
+        // 文件重新加载后刷新诊断信息
+        cx.observe_global::<KeymapFileStatus>(|_view, cx| cx.notify())
+            .detach();
+        Self
+    }
+}
+
+impl Render for KeymapDiagnosticsPanel {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = cx.theme().clone();
+        let status = cx.try_global::<KeymapFileStatus>().cloned();
+
+        let Some(status) = status else {
+            return div()
+                .text_sm()
+                .text_color(theme.subtext)
+                .child("未加载快捷键文件，使用默认快捷键（启动参数 --keymap <path> 可加载用户快捷键）");
+        };
+
+        let summary = match &status.error {
+            Some(_) => "加载失败，只使用默认快捷键".to_string(),
+            None if status.diagnostics.is_empty() => {
+                format!("✅ 已应用 {} 条用户快捷键", status.binding_count)
+            }
+            None => format!(
+                "已应用 {} 条用户快捷键，跳过 {} 条",
+                status.binding_count,
+                status.diagnostics.len()
+            ),
+        };
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(theme.subtext)
+                    .child(format!("文件: {}", status.path.display())),
+            )
+            .child(div().text_sm().text_color(theme.text).child(summary))
+            .when_some(status.error, |el, error| {
+                el.child(
+                    div()
+                        .text_sm()
+                        .text_color(theme.danger)
+                        .child(format!("⚠️ {}", error)),
+                )
+            })
+            .children(status.diagnostics.into_iter().map(|diagnostic| {
+                div()
+                    .text_xs()
+                    .text_color(theme.danger)
+                    .child(format!("• {}", diagnostic))
+            }))
+    }
+}
+
+// ============================================================================
+// 第七部分：Action 宏录制面板
+// ============================================================================
+
+// ActionRecorder 在 ActionDemo / FocusDemo 的根元素上用 capture_action 录制 Action，
+// 面板负责开始/停止录制、按原速或 4 倍速回放，以及把脚本保存到 JSON 文件。
+
+/// 默认的宏脚本文件，启动参数 --macro <path> 可以指定其他文件
+const DEFAULT_MACRO_FILE: &str = "action_macro.json";
+
+/// 加速回放的倍数
+const FAST_PLAYBACK: f32 = 4.0;
+
+/// 从命令行参数中读取 --state <path>
+pub fn state_file_from_args() -> Option<PathBuf> {
+    file_from_args("--state")
+}
+
+/// 从命令行参数中读取 --macro <path>
+pub fn macro_file_from_args() -> Option<PathBuf> {
+    file_from_args("--macro")
+}
+
+struct MacroPanel {
+    recorder: Entity<ActionRecorder>,
+    path: PathBuf,
+    /// 最近一次保存/加载的结果
+    status: Option<String>,
+}
+
+impl MacroPanel {
+    fn new(recorder: Entity<ActionRecorder>, cx: &mut Context<Self>) -> Self {
+        // 录制、回放进度变化时刷新
+        cx.observe(&recorder, |_panel, _recorder, cx| cx.notify())
+            .detach();
+        Self {
+            recorder,
+            path: PathBuf::from(DEFAULT_MACRO_FILE),
+            status: None,
+        }
+    }
+
+    fn toggle_recording(&mut self, cx: &mut Context<Self>) {
+        self.recorder.update(cx, |recorder, cx| {
+            if recorder.is_recording() {
+                recorder.stop(cx);
+            } else {
+                recorder.start_recording(cx);
+            }
+        });
+    }
+
+    fn play(&mut self, speed: PlaybackSpeed, window: &mut Window, cx: &mut Context<Self>) {
+        self.recorder
+            .update(cx, |recorder, cx| recorder.play(speed, window, cx));
+    }
+
+    fn save(&mut self, cx: &mut Context<Self>) {
+        let script = self.recorder.read(cx).script();
+        self.status = Some(match script.save(&self.path) {
+            Ok(()) => format!("已保存 {} 条到 {}", script.actions.len(), self.path.display()),
+            Err(error) => format!("⚠️ 保存失败: {}", error),
+        });
+        cx.notify();
+    }
+
+    fn load(&mut self, cx: &mut Context<Self>) {
+        self.status = Some(match ActionScript::load(&self.path) {
+            Ok(script) => {
+                let status = format!("已从 {} 加载 {} 条", self.path.display(), script.actions.len());
+                self.recorder
+                    .update(cx, |recorder, cx| recorder.set_script(script, cx));
+                status
+            }
+            Err(error) => format!("⚠️ 加载失败: {}", error),
+        });
+        cx.notify();
+    }
+}
+
+impl Render for MacroPanel {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = cx.theme().clone();
+        let recorder = self.recorder.read(cx);
+        let recording = recorder.is_recording();
+        let playing = recorder.is_playing();
+        let (played, total) = recorder.progress();
+        let errors = recorder.errors().to_vec();
+        // 只显示最近的几条
+        let recent: Vec<String> = recorder
+            .script()
+            .actions
+            .iter()
+            .enumerate()
+            .skip(total.saturating_sub(5))
+            .map(|(i, action)| {
+                let args = action.args.as_ref().map(|args| format!(" {}", args)).unwrap_or_default();
+                let marker = if playing && i < played { "✓" } else { "•" };
+                format!("{} {:>6}ms  {}{}", marker, action.timestamp_ms, action.name, args)
+            })
+            .collect();
+
+        let state = if recording {
+            format!("🔴 录制中… 已录制 {} 条", total)
+        } else if playing {
+            format!("▶️ 回放中 {}/{}", played, total)
+        } else {
+            format!("共 {} 条，时长 {:.1}s", total, recorder.script().duration().as_secs_f32())
+        };
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .child(div().text_sm().text_color(theme.text).child(state))
+            .child(
+                div()
+                    .flex()
+                    .flex_wrap()
+                    .gap_2()
+                    .child(
+                        StyledButton::new("macro-record", if recording { "⏹ 停止" } else { "⏺ 录制" })
+                            .variant(if recording { ButtonVariant::Danger } else { ButtonVariant::Primary })
+                            .disabled(playing)
+                            .on_click(cx.listener(|panel, _, _window, cx| panel.toggle_recording(cx))),
+                    )
+                    .child(
+                        StyledButton::new("macro-play", "▶ 回放")
+                            .variant(ButtonVariant::Success)
+                            .disabled(recording || playing || total == 0)
+                            .on_click(cx.listener(|panel, _, window, cx| {
+                                panel.play(PlaybackSpeed::Original, window, cx)
+                            })),
+                    )
+                    .child(
+                        StyledButton::new("macro-play-fast", format!("⏩ {}x", FAST_PLAYBACK))
+                            .variant(ButtonVariant::Secondary)
+                            .disabled(recording || playing || total == 0)
+                            .on_click(cx.listener(|panel, _, window, cx| {
+                                panel.play(PlaybackSpeed::Accelerated(FAST_PLAYBACK), window, cx)
+                            })),
+                    )
+                    .when(playing, |el| {
+                        el.child(
+                            StyledButton::new("macro-stop", "⏹ 停止回放")
+                                .variant(ButtonVariant::Danger)
+                                .on_click(cx.listener(|panel, _, _window, cx| {
+                                    panel.recorder.update(cx, |recorder, cx| recorder.stop(cx));
+                                })),
+                        )
+                    })
+                    .child(
+                        StyledButton::new("macro-save", "💾 保存")
+                            .variant(ButtonVariant::Secondary)
+                            .disabled(recording || total == 0)
+                            .on_click(cx.listener(|panel, _, _window, cx| panel.save(cx))),
+                    )
+                    .child(
+                        StyledButton::new("macro-load", "📂 加载")
+                            .variant(ButtonVariant::Secondary)
+                            .disabled(recording || playing)
+                            .on_click(cx.listener(|panel, _, _window, cx| panel.load(cx))),
+                    ),
+            )
+            .children(recent.into_iter().map(|line| {
+                div().text_xs().text_color(theme.subtext).child(line)
+            }))
+            .when_some(self.status.clone(), |el, status| {
+                el.child(div().text_xs().text_color(theme.subtext).child(status))
+            })
+            .children(errors.into_iter().map(|error| {
+                div()
+                    .text_xs()
+                    .text_color(theme.danger)
+                    .child(format!("⚠️ {}", error))
+            }))
+    }
+}
+
+// ============================================================================
+// 第八部分：主应用
+// ============================================================================
+
+/// 日志面板最多保存的条目数
+const LOG_CAPACITY: usize = 500;
+
+/// 日志面板"导出"写入的文件（相对于当前目录）
+const DEFAULT_LOG_FILE: &str = "advanced_log.txt";
+
+pub struct AdvancedApp {
+    action_demo: Entity<ActionDemo>,
+    async_demo: Entity<AsyncDemo>,
+    focus_demo: Entity<FocusDemo>,
+    global_demo: Entity<GlobalDemo>,
+    keymap_panel: Entity<KeymapDiagnosticsPanel>,
+    macro_panel: Entity<MacroPanel>,
+    /// Action 演示、异步演示和焦点演示共用的日志面板
+    logs: Entity<LogPanel>,
+    /// 打开中的命令面板，关闭后置为 None
+    command_palette: Option<Entity<CommandPalette>>,
+    /// 根视图的焦点：没有子元素获得焦点时，快捷键从这里开始分发（例如打开命令面板）
+    focus_handle: FocusHandle,
+}
+
+impl Focusable for AdvancedApp {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl AdvancedApp {
+    pub fn new(cx: &mut Context<Self>) -> Self {
+        // 在主题变化时重新渲染整个界面
+        cx.observe_global::<Theme>(|_view, cx| cx.notify()).detach();
+
+        let recorder = cx.new(|_| ActionRecorder::new());
+        // 同一个 LogPanel 实体传给三个组件，它们写入的日志按来源区分
+        let logs = cx.new(|cx| LogPanel::new(LOG_CAPACITY, cx).export_path(DEFAULT_LOG_FILE));
+        let action_demo = cx.new(|cx| ActionDemo::new(logs.clone(), cx).with_recorder(recorder.clone()));
+        let async_demo = cx.new(|cx| AsyncDemo::new(logs.clone(), cx));
+        let focus_demo = cx.new(|cx| FocusDemo::new(logs.clone(), cx).with_recorder(recorder.clone()));
+        let global_demo = cx.new(|_| GlobalDemo::new());
+        let keymap_panel = cx.new(KeymapDiagnosticsPanel::new);
+        let macro_panel = cx.new(|cx| MacroPanel::new(recorder.clone(), cx));
+
+        // 回放时按录制到的上下文把焦点移回对应组件
+        let action_focus = action_demo.read(cx).focus_handle.clone();
+        let focus_demo_focus = focus_demo.read(cx).focus_handle.clone();
+        recorder.update(cx, |recorder, _| {
+            recorder.register_target(ACTION_DEMO_CONTEXT, action_focus);
+            recorder.register_target(FOCUS_DEMO_CONTEXT, focus_demo_focus);
+        });
+
+        Self {
+            action_demo,
+            async_demo,
+            focus_demo,
+            global_demo,
+            keymap_panel,
+            macro_panel,
+            logs,
+            command_palette: None,
+            focus_handle: cx.focus_handle(),
+        }
+    }
+
+    /// 使用指定的宏脚本文件（main 中根据 --macro 参数设置）
+    pub fn set_macro_file(&mut self, path: PathBuf, cx: &mut Context<Self>) {
+        self.macro_panel.update(cx, |panel, cx| {
+            panel.path = path;
+            cx.notify();
+        });
+    }
+
+    /// 把 Action 演示的计数持久化到指定文件（main 中根据 --state 参数设置）
+    pub fn set_counter_state_file(&mut self, path: PathBuf, cx: &mut Context<Self>) {
+        self.action_demo.update(cx, |demo, cx| demo.persist_to(path, cx));
+    }
+
+    fn toggle_command_palette(
+        &mut self,
+        _: &command_palette::Toggle,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        // 面板打开时焦点在面板内，Toggle 由面板自己处理（关闭），到不了这里
+        let palette = cx.new(|cx| CommandPalette::new(window, cx));
+        cx.subscribe(&palette, |app, _palette, _: &DismissEvent, cx| {
+            app.command_palette = None;
+            cx.notify();
+        })
+        .detach();
+        self.command_palette = Some(palette);
+        cx.notify();
+    }
+}
+
+impl Render for AdvancedApp {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let palette_keys = window.keystroke_text_for(&command_palette::Toggle);
+        // 读取全局主题
+        let theme = cx.theme();
+        let text = theme.text;
+        let subtext = theme.subtext;
+        // 主题文件加载失败时的错误提示
+        let theme_error = cx
+            .try_global::<ThemeFileStatus>()
+            .and_then(|status| status.error.clone());
+
+        div()
+            .id("main-container")
+            .size_full()
+            .flex()
+            .flex_col()
+            .overflow_y_scroll()
+            .bg(theme.background)
+            .p(theme.spacing.xl)
+            .gap(theme.spacing.xl)
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::toggle_command_palette))
+            // 标题
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_2()
+                    .child(
+                        div()
+                            .text_2xl()
+                            .font_weight(FontWeight::EXTRA_BOLD)
+                            .text_color(text)
+                            .child("第六章：高级主题 🚀"),
+                    )
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(subtext)
+                            .child("学习 Action 系统、异步操作、焦点管理和全局状态"),
+                    )
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(subtext)
+                            .child(format!("🔍 按 {} 打开命令面板，执行当前焦点位置可用的任意 Action", palette_keys)),
+                    ),
+            )
+            .when_some(theme_error, |el, error| {
+                el.child(
+                    div()
+                        .p(theme.spacing.md)
+                        .rounded(theme.radius.lg)
+                        .bg(theme.danger)
+                        .text_color(theme.accent_foreground)
+                        .text_sm()
+                        .child(format!("⚠️ {}", error)),
+                )
+            })
+            // 主内容 - 两列布局
+            .child(
+                div()
+                    .flex()
+                    .gap_6()
+                    // 左列
+                    .child(
+                        div()
+                            .flex_1()
+                            .flex()
+                            .flex_col()
+                            .gap_6()
+                            // Action 演示
+                            .child(
+                                div()
+                                    .flex()
+                                    .flex_col()
+                                    .gap_3()
+                                    .p(theme.spacing.lg)
+                                    .bg(theme.surface)
+                                    .rounded(theme.radius.lg)
+                                    .shadow_sm()
+                                    .child(
+                                        div()
+                                            .text_lg()
+                                            .font_weight(FontWeight::BOLD)
+                                            .text_color(text)
+                                            .child("🎮 Action 系统"),
+                                    )
+                                    .child(
+                                        div()
+                                            .text_xs()
+                                            .text_color(subtext)
+                                            .child("使用 actions! 宏定义命令，绑定快捷键"),
+                                    )
+                                    .child(self.action_demo.clone()),
+                            )
+                            // 异步演示
+                            .child(
+                                div()
+                                    .flex()
+                                    .flex_col()
+                                    .gap_3()
+                                    .p(theme.spacing.lg)
+                                    .bg(theme.surface)
+                                    .rounded(theme.radius.lg)
+                                    .shadow_sm()
+                                    .child(
+                                        div()
+                                            .text_lg()
+                                            .font_weight(FontWeight::BOLD)
+                                            .text_color(text)
+                                            .child("⏳ 异步操作"),
+                                    )
+                                    .child(
+                                        div()
+                                            .text_xs()
+                                            .text_color(subtext)
+                                            .child("使用 cx.spawn() 执行异步任务"),
+                                    )
+                                    .child(self.async_demo.clone()),
+                            ),
+                    )
+                    // 右列
+                    .child(
+                        div()
+                            .flex_1()
+                            .flex()
+                            .flex_col()
+                            .gap_6()
+                            // 焦点演示
+                            .child(
+                                div()
+                                    .flex()
+                                    .flex_col()
+                                    .gap_3()
+                                    .p(theme.spacing.lg)
+                                    .bg(theme.surface)
+                                    .rounded(theme.radius.lg)
+                                    .shadow_sm()
+                                    .child(
+                                        div()
+                                            .text_lg()
+                                            .font_weight(FontWeight::BOLD)
+                                            .text_color(text)
+                                            .child("🎯 焦点管理"),
+                                    )
+                                    .child(
+                                        div()
+                                            .text_xs()
+                                            .text_color(subtext)
+                                            .child("使用 FocusHandle 管理多个焦点区域"),
+                                    )
+                                    .child(self.focus_demo.clone()),
+                            )
+                            // Global 演示
+                            .child(
+                                div()
+                                    .flex()
+                                    .flex_col()
+                                    .gap_3()
+                                    .p(theme.spacing.lg)
+                                    .bg(theme.surface)
+                                    .rounded(theme.radius.lg)
+                                    .shadow_sm()
+                                    .child(
+                                        div()
+                                            .text_lg()
+                                            .font_weight(FontWeight::BOLD)
+                                            .text_color(text)
+                                            .child("🌍 Global 状态"),
+                                    )
+                                    .child(
+                                        div()
+                                            .text_xs()
+                                            .text_color(subtext)
+                                            .child("使用 Global trait 管理全局共享状态"),
+                                    )
+                                    .child(self.global_demo.clone()),
+                            )
+                            // Keymap 诊断
+                            .child(
+                                div()
+                                    .flex()
+                                    .flex_col()
+                                    .gap_3()
+                                    .p(theme.spacing.lg)
+                                    .bg(theme.surface)
+                                    .rounded(theme.radius.lg)
+                                    .shadow_sm()
+                                    .child(
+                                        div()
+                                            .text_lg()
+                                            .font_weight(FontWeight::BOLD)
+                                            .text_color(text)
+                                            .child("⌨️ Keymap 诊断"),
+                                    )
+                                    .child(
+                                        div()
+                                            .text_xs()
+                                            .text_color(subtext)
+                                            .child("用户快捷键文件中被跳过的条目"),
+                                    )
+                                    .child(self.keymap_panel.clone()),
+                            )
+                            // Action 宏
+                            .child(
+                                div()
+                                    .flex()
+                                    .flex_col()
+                                    .gap_3()
+                                    .p(theme.spacing.lg)
+                                    .bg(theme.surface)
+                                    .rounded(theme.radius.lg)
+                                    .shadow_sm()
+                                    .child(
+                                        div()
+                                            .text_lg()
+                                            .font_weight(FontWeight::BOLD)
+                                            .text_color(text)
+                                            .child("⏺️ Action 宏"),
+                                    )
+                                    .child(
+                                        div()
+                                            .text_xs()
+                                            .text_color(subtext)
+                                            .child("录制 Action 演示和焦点演示中分发的 Action，保存为 JSON 并回放"),
+                                    )
+                                    .child(self.macro_panel.clone()),
+                            ),
+                    ),
+            )
+            // 日志面板：Action 演示、异步演示和焦点演示共用
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_3()
+                    .p(theme.spacing.lg)
+                    .bg(theme.surface)
+                    .rounded(theme.radius.lg)
+                    .shadow_sm()
+                    .child(
+                        div()
+                            .text_lg()
+                            .font_weight(FontWeight::BOLD)
+                            .text_color(text)
+                            .child("📜 日志面板"),
+                    )
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(subtext)
+                            .child("Action 演示、异步演示和焦点演示写入同一个 LogPanel 实体，可按级别、来源过滤和搜索"),
+                    )
+                    .child(div().h(px(260.)).child(self.logs.clone())),
+            )
+            // 知识点总结
+            .child(
+                div()
+                    .p(theme.spacing.lg)
+                    .rounded(theme.radius.lg)
+                    .bg(theme.callout_background)
+                    .border_1()
+                    .border_color(theme.callout_border)
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(theme.callout_text)
+                            .child("💡 关键API：actions! | on_action | dispatch_action | cx.spawn() | Task | FocusHandle | track_focus | Global | cx.set_global()"),
+                    ),
+            )
+            // 命令面板：deferred 让它最后绘制，anchored 把它定位到窗口左上角并铺满窗口
+            .when_some(self.command_palette.clone(), |el, palette| {
+                el.child(deferred(anchored().position(point(px(0.), px(0.))).child(palette)))
+            })
+    }
+}
+
+// ============================================================================
+// 测试：在无界面的 TestAppContext 中分发 Action / 按键并断言状态
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    // 不能 use super::*：gpui::* 中的 test 宏会遮蔽内置的 #[test]
+    use super::{
+        bind_keys, init, reduce_counter, shortcut_text, ActionDemo, AddDelta, AdvancedApp,
+        AsyncDemo, CounterMsg, CounterState, Decrement, FocusArea, FocusDemo, Increment, Reset,
+        SetValue, ACTION_DEMO_CONTEXT, ASYNC_DEMO_SOURCE, FOCUS_DEMO_CONTEXT,
+    };
+    use gpui::{
+        App, AppContext, Entity, Focusable, KeyBinding, Modifiers, Subscription, TestAppContext,
+        VisualTestContext,
+    };
+    use gpui_components::{
+        load_keymap, ActionRecorder, ActionScript, ActiveTheme, KeymapDiagnostic, LogLevel,
+        LogPanel, Middleware, PersistMiddleware, PlaybackSpeed, StateChanged, Store, Theme,
+    };
+    use serde_json::json;
+    use std::cell::{Cell, RefCell};
+    use std::path::Path;
+    use std::rc::Rc;
+    use std::time::Duration;
+
+    fn test_logs(cx: &mut App) -> Entity<LogPanel> {
+        cx.new(|cx| LogPanel::new(100, cx))
+    }
+
+    /// 最新一条日志，格式为 "来源: 消息"
+    fn last_log(logs: &Entity<LogPanel>, cx: &App) -> Option<String> {
+        let entry = logs.read(cx).last()?;
+        Some(format!("{}: {}", entry.source, entry.message))
+    }
+
+    #[gpui::test]
+    fn action_demo_handles_dispatched_actions(cx: &mut TestAppContext) {
+        cx.update(Theme::init);
+        let logs = cx.update(test_logs);
+        let (demo, cx) = cx.add_window_view(|_, cx| ActionDemo::new(logs.clone(), cx));
+        // Action 沿焦点路径分发，先让 ActionDemo 获得焦点
+        cx.update(|window, cx| demo.read(cx).focus_handle.focus(window));
+
+        cx.dispatch_action(Increment);
+        cx.dispatch_action(Increment);
+        cx.dispatch_action(Decrement);
+        demo.read_with(cx, |demo, cx| {
+            assert_eq!(demo.count, 1);
+            // 日志由 Store 的日志中间件写入
+            assert_eq!(last_log(&logs, cx), Some("ActionDemo: Decrement → 1".into()));
+        });
+
+        cx.dispatch_action(Reset);
+        assert_eq!(demo.read_with(cx, |demo, _| demo.count), 0);
+    }
+
+    #[gpui::test]
+    fn action_demo_keyboard_shortcuts(cx: &mut TestAppContext) {
+        cx.update(init);
+        let (demo, cx) = cx.add_window_view(|_, cx| ActionDemo::new(test_logs(cx), cx));
+        cx.update(|window, cx| demo.read(cx).focus_handle.focus(window));
+
+        cx.simulate_keystrokes("up up k");
+        assert_eq!(demo.read_with(cx, |demo, _| demo.count), 3);
+
+        cx.simulate_keystrokes("down");
+        assert_eq!(demo.read_with(cx, |demo, _| demo.count), 2);
+
+        cx.simulate_keystrokes("7");
+        assert_eq!(demo.read_with(cx, |demo, _| demo.count), 7);
+
+        cx.simulate_keystrokes("r");
+        assert_eq!(demo.read_with(cx, |demo, _| demo.count), 0);
+    }
+
+    #[gpui::test]
+    fn action_demo_ignores_keys_without_focus(cx: &mut TestAppContext) {
+        cx.update(init);
+        let (demo, cx) = cx.add_window_view(|_, cx| ActionDemo::new(test_logs(cx), cx));
+
+        cx.simulate_keystrokes("up up");
+        assert_eq!(demo.read_with(cx, |demo, _| demo.count), 0);
+    }
+
+    #[gpui::test]
+    fn action_demo_can_be_rebound_without_touching_the_view(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            init(cx);
+            cx.bind_keys([KeyBinding::new("i", Increment, Some(ACTION_DEMO_CONTEXT))]);
+        });
+        let (demo, cx) = cx.add_window_view(|_, cx| ActionDemo::new(test_logs(cx), cx));
+        cx.update(|window, cx| demo.read(cx).focus_handle.focus(window));
+
+        cx.simulate_keystrokes("i i");
+        assert_eq!(demo.read_with(cx, |demo, _| demo.count), 2);
+        assert_eq!(cx.update(|_, cx| shortcut_text(&Increment, cx)), "up / K / I");
+    }
+
+    #[gpui::test]
+    fn toggle_theme_binding_works_without_focus(cx: &mut TestAppContext) {
+        cx.update(init);
+        let (_demo, cx) = cx.add_window_view(|_, cx| ActionDemo::new(test_logs(cx), cx));
+        assert!(!cx.update(|_, cx| cx.theme().is_dark()));
+
+        cx.simulate_keystrokes("secondary-t");
+        assert!(cx.update(|_, cx| cx.theme().is_dark()));
+    }
+
+    #[gpui::test]
+    fn focus_demo_tab_and_arrow_navigation(cx: &mut TestAppContext) {
+        cx.update(init);
+        let (demo, cx) = cx.add_window_view(|_, cx| FocusDemo::new(test_logs(cx), cx));
+        let focused = |cx: &mut VisualTestContext| {
+            cx.update(|window, cx| demo.read(cx).current_index(window))
+        };
+        assert_eq!(focused(cx), None);
+
+        cx.update(|window, cx| demo.update(cx, |demo, cx| demo.focus_next(window, cx)));
+        cx.run_until_parked();
+        assert_eq!(focused(cx), Some(0));
+
+        cx.simulate_keystrokes("tab");
+        assert_eq!(focused(cx), Some(1));
+
+        cx.simulate_keystrokes("down right");
+        assert_eq!(focused(cx), Some(3));
+
+        // 最后一个区域之后回到第一个
+        cx.simulate_keystrokes("tab");
+        assert_eq!(focused(cx), Some(0));
+
+        cx.simulate_keystrokes("shift-tab");
+        assert_eq!(focused(cx), Some(3));
+
+        cx.simulate_keystrokes("left");
+        assert_eq!(focused(cx), Some(2));
+
+        demo.read_with(cx, |demo, cx| {
+            assert_eq!(last_log(&demo.logs, cx), Some("FocusDemo: 聚焦到区域 3".into()));
+        });
+    }
+
+    #[gpui::test]
+    fn focus_demo_logs_focus_changes_not_renders(cx: &mut TestAppContext) {
+        cx.update(init);
+        let logs = cx.update(test_logs);
+        let (demo, cx) = cx.add_window_view({
+            let logs = logs.clone();
+            |_, cx| FocusDemo::new(logs, cx)
+        });
+        let messages = |cx: &mut VisualTestContext| {
+            logs.read_with(cx, |logs, _| logs.messages_from(FOCUS_DEMO_CONTEXT))
+        };
+
+        // 与点击区域时一样：先聚焦组件，再派发 FocusArea
+        cx.update(|window, cx| demo.read(cx).focus_handle.focus(window));
+        cx.dispatch_action(FocusArea { index: 1 });
+        // 重新渲染不会写日志
+        cx.update(|window, _| window.refresh());
+        cx.run_until_parked();
+        assert_eq!(messages(cx), ["聚焦到区域 2"]);
+
+        // 已经聚焦的区域再次聚焦不重复记录
+        cx.update(|window, cx| demo.update(cx, |demo, cx| demo.focus_area(1, window, cx)));
+        cx.simulate_keystrokes("tab");
+        assert_eq!(messages(cx), ["聚焦到区域 2", "聚焦到区域 3"]);
+    }
+
+    #[gpui::test]
+    fn keymap_file_rebinds_and_unbinds_keys(cx: &mut TestAppContext) {
+        let source = r#"{
+            "ActionDemo": { "=": "app::Increment", "k": null },
+            "": { "ctrl-shift-t": "app::ToggleTheme" }
+        }"#;
+        cx.update(|cx| {
+            init(cx);
+            let loaded = load_keymap(Path::new("test.json"), source, cx).unwrap();
+            assert!(loaded.diagnostics.is_empty());
+            assert_eq!(loaded.bindings.len(), 3);
+            cx.bind_keys(loaded.bindings);
+        });
+        let (demo, cx) = cx.add_window_view(|_, cx| ActionDemo::new(test_logs(cx), cx));
+        cx.update(|window, cx| demo.read(cx).focus_handle.focus(window));
+
+        cx.simulate_keystrokes("= = k up");
+        assert_eq!(demo.read_with(cx, |demo, _| demo.count), 3);
+
+        cx.simulate_keystrokes("ctrl-shift-t");
+        assert!(cx.update(|_, cx| cx.theme().is_dark()));
+    }
+
+    #[gpui::test]
+    fn keymap_file_reports_invalid_entries(cx: &mut TestAppContext) {
+        let source = r#"{
+            "ActionDemo": {
+                "x": "app::Explode",
+                "ctrl-a-b": "app::Increment",
+                "ctrl-shift-a": "app::Reset",
+                "shift-ctrl-a": "app::Decrement",
+                "y": 42
+            },
+            "ActionDemo &&": { "z": "app::Reset" }
+        }"#;
+        let loaded = cx.update(|cx| {
+            bind_keys(cx);
+            load_keymap(Path::new("test.json"), source, cx).unwrap()
+        });
+
+        // 只有 ctrl-shift-a 被保留，其余条目都被跳过
+        assert_eq!(loaded.bindings.len(), 1);
+        let diagnostics = loaded.diagnostics;
+        assert_eq!(diagnostics.len(), 5);
+        assert_eq!(
+            diagnostics[0],
+            KeymapDiagnostic::UnknownAction {
+                context: "ActionDemo".into(),
+                keystroke: "x".into(),
+                action: "app::Explode".into(),
+            }
+        );
+        assert!(matches!(
+            &diagnostics[1],
+            KeymapDiagnostic::InvalidKeystroke { keystroke, .. } if keystroke == "ctrl-a-b"
+        ));
+        assert_eq!(
+            diagnostics[2],
+            KeymapDiagnostic::Duplicate {
+                context: "ActionDemo".into(),
+                keystroke: "shift-ctrl-a".into(),
+                first: "ctrl-shift-a".into(),
+            }
+        );
+        assert!(matches!(&diagnostics[3], KeymapDiagnostic::InvalidEntry { .. }));
+        assert!(matches!(&diagnostics[4], KeymapDiagnostic::InvalidContext { .. }));
+    }
+
+    #[gpui::test]
+    fn keymap_file_with_bad_json_is_an_error(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            assert!(load_keymap(Path::new("test.json"), "{ \"ActionDemo\": [] }", cx).is_err());
+        });
+    }
+
+    #[gpui::test]
+    fn parameterized_actions_from_keys_and_buttons(cx: &mut TestAppContext) {
+        cx.update(init);
+        let logs = cx.update(test_logs);
+        let (demo, cx) = cx.add_window_view(|_, cx| ActionDemo::new(logs.clone(), cx));
+        cx.update(|window, cx| demo.read(cx).focus_handle.focus(window));
+
+        cx.dispatch_action(SetValue { value: 42 });
+        assert_eq!(demo.read_with(cx, |demo, _| demo.count), 42);
+
+        // 数字键和 shift-↑/↓ 都绑定到带参数的 Action
+        cx.simulate_keystrokes("7 shift-up shift-up shift-down");
+        demo.read_with(cx, |demo, cx| {
+            assert_eq!(demo.count, 17);
+            assert_eq!(last_log(&logs, cx), Some("ActionDemo: AddDelta(-10) → 17".into()));
+        });
+        assert_eq!(
+            cx.update(|_, cx| shortcut_text(&AddDelta { delta: 10 }, cx)),
+            "shift-up"
+        );
+
+        let set_hundred = cx.debug_bounds("button-btn-set-hundred").expect("找不到 设为 100 按钮");
+        cx.simulate_click(set_hundred.center(), Modifiers::none());
+        assert_eq!(demo.read_with(cx, |demo, _| demo.count), 100);
+    }
+
+    #[gpui::test]
+    fn keymap_file_binds_actions_with_json_arguments(cx: &mut TestAppContext) {
+        let source = r#"{
+            "ActionDemo": {
+                "p": ["app::AddDelta", { "delta": 5 }],
+                "z": ["app::SetValue", { "value": 0 }],
+                "x": ["app::SetValue", { "value": "many" }]
+            }
+        }"#;
+        cx.update(|cx| {
+            init(cx);
+            let loaded = load_keymap(Path::new("test.json"), source, cx).unwrap();
+            assert_eq!(loaded.bindings.len(), 2);
+            assert!(matches!(
+                &loaded.diagnostics[..],
+                [KeymapDiagnostic::InvalidArguments { action, .. }] if action == "app::SetValue"
+            ));
+            cx.bind_keys(loaded.bindings);
+        });
+        let (demo, cx) = cx.add_window_view(|_, cx| ActionDemo::new(test_logs(cx), cx));
+        cx.update(|window, cx| demo.read(cx).focus_handle.focus(window));
+
+        cx.simulate_keystrokes("p p p");
+        assert_eq!(demo.read_with(cx, |demo, _| demo.count), 15);
+        cx.simulate_keystrokes("z");
+        assert_eq!(demo.read_with(cx, |demo, _| demo.count), 0);
+    }
+
+    #[gpui::test]
+    fn command_palette_dispatches_to_previously_focused_element(cx: &mut TestAppContext) {
+        cx.update(init);
+        let (app, cx) = cx.add_window_view(|_, cx| AdvancedApp::new(cx));
+        let demo = app.read_with(cx, |app, _| app.action_demo.clone());
+        cx.update(|window, cx| demo.read(cx).focus_handle.focus(window));
+
+        cx.simulate_keystrokes("secondary-shift-p");
+        let palette = app
+            .read_with(cx, |app, _| app.command_palette.clone())
+            .expect("命令面板没有打开");
+        let commands = palette.read_with(cx, |palette, _| palette.matching_commands());
+        // 列出的是 ActionDemo 能处理的 Action，以及全局的 ToggleTheme
+        assert!(commands.iter().any(|name| name == "app: increment"));
+        assert!(commands.iter().any(|name| name == "app: toggle theme"));
+        assert!(!commands.iter().any(|name| name == "command palette: toggle"));
+
+        cx.simulate_input("incr");
+        let commands = palette.read_with(cx, |palette, _| palette.matching_commands());
+        assert_eq!(commands.first().map(|name| name.as_ref()), Some("app: increment"));
+
+        cx.simulate_keystrokes("enter");
+        assert!(app.read_with(cx, |app, _| app.command_palette.is_none()));
+        assert_eq!(demo.read_with(cx, |demo, _| demo.count), 1);
+        // 焦点回到了 ActionDemo，快捷键继续生效
+        cx.simulate_keystrokes("up");
+        assert_eq!(demo.read_with(cx, |demo, _| demo.count), 2);
+    }
+
+    #[gpui::test]
+    fn command_palette_keyboard_navigation_and_dismiss(cx: &mut TestAppContext) {
+        cx.update(init);
+        let (app, cx) = cx.add_window_view(|_, cx| AdvancedApp::new(cx));
+        let demo = app.read_with(cx, |app, _| app.action_demo.clone());
+        cx.update(|window, cx| demo.read(cx).focus_handle.focus(window));
+
+        // Escape 关闭面板，不执行任何命令
+        cx.simulate_keystrokes("secondary-shift-p");
+        cx.simulate_input("reset");
+        cx.simulate_keystrokes("escape");
+        assert!(app.read_with(cx, |app, _| app.command_palette.is_none()));
+        cx.simulate_keystrokes("up");
+        assert_eq!(demo.read_with(cx, |demo, _| demo.count), 1);
+
+        // 再按一次打开快捷键也会关闭面板
+        cx.simulate_keystrokes("secondary-shift-p secondary-shift-p");
+        assert!(app.read_with(cx, |app, _| app.command_palette.is_none()));
+
+        // 得分相同的命令按字母顺序排列，↓ 选中第二项 "app: increment"
+        cx.simulate_keystrokes("secondary-shift-p");
+        cx.simulate_input("app");
+        let palette = app.read_with(cx, |app, _| app.command_palette.clone()).unwrap();
+        let commands = palette.read_with(cx, |palette, _| palette.matching_commands());
+        assert_eq!(commands[..2], ["app: decrement", "app: increment"]);
+        cx.simulate_keystrokes("down enter");
+        assert_eq!(demo.read_with(cx, |demo, _| demo.count), 2);
+    }
+
+    #[gpui::test]
+    fn command_palette_without_focus_runs_global_actions(cx: &mut TestAppContext) {
+        cx.update(init);
+        let (app, cx) = cx.add_window_view(|_, cx| AdvancedApp::new(cx));
+        // 与 main 中一样，启动后让根视图获得焦点
+        cx.update(|window, cx| app.focus_handle(cx).focus(window));
+
+        cx.simulate_keystrokes("secondary-shift-p");
+        cx.simulate_input("toggle theme");
+        cx.simulate_keystrokes("enter");
+        assert!(cx.update(|_, cx| cx.theme().is_dark()));
+    }
+
+    fn recorder_of(app: &Entity<AdvancedApp>, cx: &mut VisualTestContext) -> Entity<ActionRecorder> {
+        app.read_with(cx, |app, cx| app.macro_panel.read(cx).recorder.clone())
+    }
+
+    #[gpui::test]
+    fn macro_records_keys_buttons_and_focus_actions(cx: &mut TestAppContext) {
+        cx.update(init);
+        let (app, cx) = cx.add_window_view(|_, cx| AdvancedApp::new(cx));
+        let recorder = recorder_of(&app, cx);
+        let (demo, focus_demo) = app.read_with(cx, |app, _| {
+            (app.action_demo.clone(), app.focus_demo.clone())
+        });
+        cx.update(|window, cx| demo.read(cx).focus_handle.focus(window));
+
+        recorder.update(cx, |recorder, cx| recorder.start_recording(cx));
+        cx.simulate_keystrokes("up 7 shift-up");
+        let add_ten = cx.debug_bounds("button-btn-add-ten").expect("找不到 +10 按钮");
+        cx.simulate_click(add_ten.center(), Modifiers::none());
+        cx.update(|window, cx| focus_demo.read(cx).focus_handles[0].focus(window));
+        cx.simulate_keystrokes("tab right");
+        recorder.update(cx, |recorder, cx| recorder.stop(cx));
+        // 停止后的操作不再录制
+        cx.simulate_keystrokes("left");
+
+        let script = recorder.read_with(cx, |recorder, _| recorder.script().clone());
+        let steps: Vec<_> = script
+            .actions
+            .iter()
+            .map(|action| (action.name.as_str(), action.args.clone(), action.context.last().cloned()))
+            .collect();
+        let action_demo = Some(ACTION_DEMO_CONTEXT.to_string());
+        let focus_context = Some("FocusDemo".to_string());
+        assert_eq!(
+            steps,
+            [
+                ("app::Increment", None, action_demo.clone()),
+                ("app::SetValue", Some(json!({ "value": 7 })), action_demo.clone()),
+                ("app::AddDelta", Some(json!({ "delta": 10 })), action_demo.clone()),
+                ("app::AddDelta", Some(json!({ "delta": 10 })), action_demo),
+                ("app::FocusNext", None, focus_context.clone()),
+                ("app::FocusNext", None, focus_context),
+            ]
+        );
+        assert!(script.actions.windows(2).all(|pair| pair[0].timestamp_ms <= pair[1].timestamp_ms));
+
+        // JSON 往返不丢失信息，也能经过文件保存/加载
+        assert_eq!(ActionScript::from_json(&script.to_json()).unwrap(), script);
+        let path = std::env::temp_dir().join(format!("gpui_advanced_macro_{}.json", std::process::id()));
+        script.save(&path).unwrap();
+        assert_eq!(ActionScript::load(&path).unwrap(), script);
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[gpui::test]
+    fn macro_playback_restores_count_and_focus(cx: &mut TestAppContext) {
+        cx.update(init);
+        let (app, cx) = cx.add_window_view(|_, cx| AdvancedApp::new(cx));
+        let recorder = recorder_of(&app, cx);
+        let (demo, focus_demo) = app.read_with(cx, |app, _| {
+            (app.action_demo.clone(), app.focus_demo.clone())
+        });
+        cx.update(|window, cx| app.focus_handle(cx).focus(window));
+
+        let script = ActionScript::from_json(
+            r#"{ "actions": [
+                { "name": "app::SetValue", "args": { "value": 5 }, "context": ["ActionDemo"], "timestamp_ms": 0 },
+                { "name": "app::Increment", "context": ["ActionDemo"], "timestamp_ms": 10 },
+                { "name": "app::Explode", "context": ["ActionDemo"], "timestamp_ms": 20 },
+                { "name": "app::AddDelta", "args": { "delta": -3 }, "context": ["ActionDemo"], "timestamp_ms": 30 },
+                { "name": "app::FocusArea", "args": { "index": 2 }, "context": ["FocusDemo"], "timestamp_ms": 40 },
+                { "name": "app::FocusNext", "context": ["FocusDemo"], "timestamp_ms": 50 }
+            ] }"#,
+        )
+        .unwrap();
+        recorder.update(cx, |recorder, cx| recorder.set_script(script, cx));
+        recorder.update_in(cx, |recorder, window, cx| {
+            recorder.play(PlaybackSpeed::Immediate, window, cx)
+        });
+        cx.run_until_parked();
+
+        // 每条 Action 回放前焦点移回录制时的组件
+        assert_eq!(demo.read_with(cx, |demo, _| demo.count), 3);
+        assert_eq!(
+            cx.update(|window, cx| focus_demo.read(cx).current_index(window)),
+            Some(3)
+        );
+        recorder.read_with(cx, |recorder, _| {
+            assert!(!recorder.is_playing());
+            assert_eq!(recorder.progress(), (6, 6));
+            // 未知的 Action 被跳过并记录下来
+            assert_eq!(recorder.errors().len(), 1);
+            assert!(recorder.errors()[0].contains("app::Explode"));
+        });
+    }
+
+    #[gpui::test]
+    fn macro_accelerated_playback_follows_timestamps(cx: &mut TestAppContext) {
+        cx.update(init);
+        let (app, cx) = cx.add_window_view(|_, cx| AdvancedApp::new(cx));
+        let recorder = recorder_of(&app, cx);
+        let demo = app.read_with(cx, |app, _| app.action_demo.clone());
+
+        let script = ActionScript::from_json(
+            r#"{ "actions": [
+                { "name": "app::Increment", "context": ["ActionDemo"], "timestamp_ms": 0 },
+                { "name": "app::Increment", "context": ["ActionDemo"], "timestamp_ms": 400 },
+                { "name": "app::Increment", "context": ["ActionDemo"], "timestamp_ms": 1200 }
+            ] }"#,
+        )
+        .unwrap();
+        recorder.update(cx, |recorder, cx| recorder.set_script(script, cx));
+        recorder.update_in(cx, |recorder, window, cx| {
+            recorder.play(PlaybackSpeed::Accelerated(4.0), window, cx)
+        });
+        cx.run_until_parked();
+        let count = |cx: &mut VisualTestContext| demo.read_with(cx, |demo, _| demo.count);
+        assert_eq!(count(cx), 1);
+
+        // 4 倍速：400ms 的间隔变成 100ms
+        cx.executor().advance_clock(Duration::from_millis(99));
+        assert_eq!(count(cx), 1);
+        cx.executor().advance_clock(Duration::from_millis(1));
+        assert_eq!(count(cx), 2);
+        assert!(recorder.read_with(cx, |recorder, _| recorder.is_playing()));
+
+        cx.executor().advance_clock(Duration::from_millis(200));
+        assert_eq!(count(cx), 3);
+        assert!(!recorder.read_with(cx, |recorder, _| recorder.is_playing()));
+    }
+
+    #[gpui::test]
+    fn macro_playback_can_be_stopped(cx: &mut TestAppContext) {
+        cx.update(init);
+        let (app, cx) = cx.add_window_view(|_, cx| AdvancedApp::new(cx));
+        let recorder = recorder_of(&app, cx);
+        let demo = app.read_with(cx, |app, _| app.action_demo.clone());
+
+        let script = ActionScript::from_json(
+            r#"{ "actions": [
+                { "name": "app::Increment", "context": ["ActionDemo"], "timestamp_ms": 0 },
+                { "name": "app::Increment", "context": ["ActionDemo"], "timestamp_ms": 1000 }
+            ] }"#,
+        )
+        .unwrap();
+        recorder.update(cx, |recorder, cx| recorder.set_script(script, cx));
+        recorder.update_in(cx, |recorder, window, cx| {
+            recorder.play(PlaybackSpeed::Original, window, cx)
+        });
+        cx.run_until_parked();
+        recorder.update(cx, |recorder, cx| recorder.stop(cx));
+
+        cx.executor().advance_clock(Duration::from_secs(2));
+        assert_eq!(demo.read_with(cx, |demo, _| demo.count), 1);
+        assert_eq!(recorder.read_with(cx, |recorder, _| recorder.progress()), (1, 2));
+    }
+
+    #[gpui::test]
+    fn demos_write_to_the_shared_log_panel(cx: &mut TestAppContext) {
+        cx.update(init);
+        let (app, cx) = cx.add_window_view(|_, cx| AdvancedApp::new(cx));
+        let (action_demo, focus_demo, logs) = app.read_with(cx, |app, _| {
+            (app.action_demo.clone(), app.focus_demo.clone(), app.logs.clone())
+        });
+        // 多个组件写入同一个日志面板实体：FocusDemo 直接持有它，ActionDemo 通过 Store 的日志中间件
+        assert!(focus_demo.read_with(cx, |demo, _| demo.logs == logs));
+
+        cx.update(|window, cx| action_demo.read(cx).focus_handle.focus(window));
+        cx.dispatch_action(Increment);
+        cx.update(|window, cx| focus_demo.update(cx, |demo, cx| demo.focus_area(1, window, cx)));
+        cx.run_until_parked();
+
+        logs.update(cx, |logs, cx| {
+            assert_eq!(logs.messages_from(ACTION_DEMO_CONTEXT), ["Increment → 1"]);
+            assert_eq!(logs.messages_from(FOCUS_DEMO_CONTEXT), ["聚焦到区域 2"]);
+
+            logs.set_source_filter(Some(FOCUS_DEMO_CONTEXT.into()), cx);
+            let visible: Vec<_> = logs.visible_entries().map(|entry| entry.message.to_string()).collect();
+            assert_eq!(visible, ["聚焦到区域 2"]);
+        });
+    }
+
+    #[gpui::test]
+    fn log_panel_filters_searches_and_exports(cx: &mut TestAppContext) {
+        cx.update(init);
+        let (logs, cx) = cx.add_window_view(|_, cx| LogPanel::new(100, cx));
+        logs.update(cx, |logs, cx| {
+            for ix in 0..110 {
+                let level = if ix % 10 == 0 { LogLevel::Warn } else { LogLevel::Info };
+                let source = if ix % 2 == 0 { "偶数" } else { "奇数" };
+                logs.log(level, source, format!("第 {} 条", ix), cx);
+            }
+            logs.error("偶数", "磁盘已满", cx);
+        });
+
+        // 容量 100：最早的 11 条被丢弃
+        logs.read_with(cx, |logs, _| {
+            assert_eq!(logs.entries().count(), 100);
+            assert_eq!(logs.dropped(), 11);
+            assert_eq!(logs.entries().next().map(|entry| entry.message.to_string()), Some("第 11 条".into()));
+        });
+
+        // 级别过滤显示不低于所选级别的条目
+        let visible = |logs: &LogPanel| -> Vec<String> {
+            logs.visible_entries().map(|entry| entry.message.to_string()).collect()
+        };
+        logs.update(cx, |logs, cx| logs.set_min_level(LogLevel::Warn, cx));
+        assert_eq!(
+            logs.read_with(cx, |logs, _| visible(logs)),
+            ["第 20 条", "第 30 条", "第 40 条", "第 50 条", "第 60 条", "第 70 条", "第 80 条", "第 90 条", "第 100 条", "磁盘已满"]
+        );
+        logs.update(cx, |logs, cx| logs.set_min_level(LogLevel::Debug, cx));
+
+        // 在搜索框中输入，按消息和来源匹配
+        let search = cx.debug_bounds("log-search").expect("找不到搜索框");
+        cx.simulate_click(search.center(), Modifiers::none());
+        cx.simulate_input("5");
+        let found = logs.read_with(cx, |logs, _| visible(logs));
+        assert_eq!(found.len(), 19);
+        assert_eq!(found.first().map(String::as_str), Some("第 15 条"));
+        assert_eq!(found.last().map(String::as_str), Some("第 105 条"));
+        logs.update(cx, |logs, cx| logs.set_query("奇数", cx));
+        assert_eq!(logs.read_with(cx, |logs, _| visible(logs).len()), 50);
+
+        // 跟随最新：100 行放不下，虚拟列表滚动到底部，最后一行在窗口内
+        logs.update(cx, |logs, cx| logs.set_query("", cx));
+        cx.run_until_parked();
+        let last_row = cx.debug_bounds("log-row-99").expect("最后一行没有渲染");
+        let viewport = cx.update(|window, _| window.viewport_size());
+        assert!(last_row.bottom() <= viewport.height, "{:?}", last_row);
+
+        // 导出缓冲区中的全部条目，不受过滤条件影响
+        let path = std::env::temp_dir().join(format!("gpui-log-panel-{}.txt", std::process::id()));
+        logs.update(cx, |logs, cx| logs.set_source_filter(Some("奇数".into()), cx));
+        logs.read_with(cx, |logs, _| logs.export(&path)).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        let lines: Vec<_> = text.lines().collect();
+        assert_eq!(lines.len(), 101);
+        assert_eq!(lines[0], "# 更早的 11 条日志超出容量已被丢弃");
+        assert!(lines[100].ends_with("ERROR [偶数] 磁盘已满"), "{}", lines[100]);
+    }
+
+    #[gpui::test]
+    fn counter_reducer_is_pure(_cx: &mut TestAppContext) {
+        let state = CounterState { count: 5 };
+        let next = reduce_counter(&state, &CounterMsg::AddDelta(-10));
+        // 旧状态不受影响，同样的输入总是得到同样的输出
+        assert_eq!(state, CounterState { count: 5 });
+        assert_eq!(next, CounterState { count: -5 });
+        assert_eq!(reduce_counter(&state, &CounterMsg::AddDelta(-10)), next);
+        assert_eq!(reduce_counter(&next, &CounterMsg::Reset), CounterState::default());
+        assert_eq!(CounterMsg::SetValue(7).to_string(), "SetValue(7)");
+        assert_eq!(CounterMsg::AddDelta(10).to_string(), "AddDelta(+10)");
+    }
+
+    /// 记录中间件的调用顺序，并拦截负数的 SetValue
+    struct Recording {
+        name: &'static str,
+        calls: Rc<RefCell<Vec<String>>>,
+    }
+
+    impl Middleware<CounterState, CounterMsg> for Recording {
+        fn before_dispatch(&mut self, state: &CounterState, msg: &CounterMsg) -> bool {
+            self.calls.borrow_mut().push(format!("{} before {} @ {}", self.name, msg, state.count));
+            !matches!(msg, CounterMsg::SetValue(value) if *value < 0)
+        }
+
+        fn after_dispatch(&mut self, msg: &CounterMsg, previous: &CounterState, state: &CounterState, _cx: &mut App) {
+            self.calls
+                .borrow_mut()
+                .push(format!("{} after {}: {} -> {}", self.name, msg, previous.count, state.count));
+        }
+    }
+
+    #[gpui::test]
+    fn store_middleware_wraps_reduce_and_can_block_messages(cx: &mut TestAppContext) {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let store = cx.new(|_| {
+            Store::new(CounterState::default(), reduce_counter)
+                .with_middleware(Recording { name: "first", calls: calls.clone() })
+                .with_middleware(Recording { name: "second", calls: calls.clone() })
+        });
+        let changes = Rc::new(RefCell::new(0));
+        let _subscription = cx.update(|cx| {
+            let changes = changes.clone();
+            cx.subscribe(&store, move |_, _: &StateChanged, _| *changes.borrow_mut() += 1)
+        });
+
+        store.update(cx, |store, cx| store.dispatch(CounterMsg::Increment, cx));
+        assert_eq!(
+            *calls.borrow(),
+            [
+                "first before Increment @ 0",
+                "second before Increment @ 0",
+                "first after Increment: 0 -> 1",
+                "second after Increment: 0 -> 1",
+            ]
+        );
+
+        // 被拦截的消息不会交给 reduce，后面的中间件也看不到它
+        calls.borrow_mut().clear();
+        store.update(cx, |store, cx| store.dispatch(CounterMsg::SetValue(-3), cx));
+        assert_eq!(*calls.borrow(), ["first before SetValue(-3) @ 1"]);
+        assert_eq!(store.read_with(cx, |store, _| store.state().count), 1);
+        assert_eq!(*changes.borrow(), 1);
+    }
+
+    /// 只关心计数是否达到 10 的视图
+    struct ThresholdWatcher {
+        reached: bool,
+        notifications: usize,
+        _subscription: Subscription,
+    }
+
+    #[gpui::test]
+    fn select_notifies_only_when_the_selected_slice_changes(cx: &mut TestAppContext) {
+        let store = cx.new(|_| Store::new(CounterState { count: 8 }, reduce_counter));
+        let watcher = cx.new(|cx| {
+            let (reached, subscription) = Store::select(
+                &store,
+                cx,
+                |state: &CounterState| state.count >= 10,
+                |watcher: &mut ThresholdWatcher, reached, _| {
+                    watcher.reached = *reached;
+                    watcher.notifications += 1;
+                },
+            );
+            ThresholdWatcher { reached, notifications: 0, _subscription: subscription }
+        });
+        let dispatch = |msg: CounterMsg, cx: &mut TestAppContext| {
+            store.update(cx, |store, cx| store.dispatch(msg, cx));
+        };
+        let seen = |cx: &mut TestAppContext| {
+            watcher.read_with(cx, |watcher, _| (watcher.reached, watcher.notifications))
+        };
+
+        // 计数变了，但选中的值没变
+        dispatch(CounterMsg::Increment, cx);
+        assert_eq!(seen(cx), (false, 0));
+        dispatch(CounterMsg::Increment, cx);
+        assert_eq!(seen(cx), (true, 1));
+        dispatch(CounterMsg::AddDelta(5), cx);
+        assert_eq!(seen(cx), (true, 1));
+        dispatch(CounterMsg::Reset, cx);
+        assert_eq!(seen(cx), (false, 2));
+    }
+
+    #[gpui::test]
+    fn counter_state_is_persisted_and_restored(cx: &mut TestAppContext) {
+        cx.update(init);
+        let path = std::env::temp_dir().join(format!("gpui_advanced_state_{}.json", std::process::id()));
+        std::fs::remove_file(&path).ok();
+
+        // 文件不存在时从 0 开始，每次变化都写入文件
+        let (demo, cx) = cx.add_window_view(|_, cx| ActionDemo::new(test_logs(cx), cx));
+        demo.update(cx, |demo, cx| demo.persist_to(path.clone(), cx));
+        cx.update(|window, cx| demo.read(cx).focus_handle.focus(window));
+        cx.simulate_keystrokes("4 shift-up");
+        // 写盘经过防抖，在后台线程完成
+        cx.executor().advance_clock(Duration::from_secs(1));
+        let saved: CounterState = PersistMiddleware::new(&path, cx.update(|_, cx| test_logs(cx)))
+            .load()
+            .expect("状态没有保存");
+        assert_eq!(saved, CounterState { count: 14 });
+
+        // 下次启动时读回上次的状态
+        let other = cx.new(|cx| ActionDemo::new(test_logs(cx), cx));
+        other.update(cx, |demo, cx| demo.persist_to(path.clone(), cx));
+        assert_eq!(other.read_with(cx, |demo, _| demo.count), 14);
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[gpui::test]
+    fn async_progress_only_notifies_the_progress_view(cx: &mut TestAppContext) {
+        cx.update(Theme::init);
+        let logs = cx.update(test_logs);
+        let (demo, cx) = cx.add_window_view(|_, cx| AsyncDemo::new(logs.clone(), cx));
+        let progress_view = demo.read_with(cx, |demo, _| demo.progress_view.clone());
+        let demo_notifications = Rc::new(Cell::new(0));
+        let _observe_demo = cx.update(|_, cx| {
+            let demo_notifications = demo_notifications.clone();
+            cx.observe(&demo, move |_, _| demo_notifications.set(demo_notifications.get() + 1))
+        });
+
+        demo.update(cx, |demo, cx| demo.start_loading(cx));
+        cx.run_until_parked();
+        let notifications = demo_notifications.get();
+        let renders = progress_view.read_with(cx, |view, _| view.renders);
+
+        // 进度只被 ProgressView 读取：它重新渲染，AsyncDemo 没有被通知
+        cx.executor().advance_clock(Duration::from_millis(200));
+        cx.run_until_parked();
+        assert_eq!(demo.read_with(cx, |demo, cx| *demo.progress.peek(cx)), 10);
+        assert_eq!(demo_notifications.get(), notifications);
+        assert_eq!(progress_view.read_with(cx, |view, _| view.renders), renders + 1);
+
+        // 切换主题时，cached 渲染的进度条也要用新的颜色重新渲染
+        cx.update(|_, cx| Theme::toggle(cx));
+        cx.run_until_parked();
+        assert_eq!(progress_view.read_with(cx, |view, _| view.renders), renders + 2);
+
+        // 加载结束时 loading 变化，AsyncDemo 被通知，Effect 写入日志
+        cx.executor().advance_clock(Duration::from_secs(2));
+        cx.run_until_parked();
+        assert!(demo_notifications.get() > notifications);
+        logs.read_with(cx, |logs, _| {
+            let messages = logs.messages_from(ASYNC_DEMO_SOURCE);
+            assert_eq!(messages.len(), 2);
+            assert_eq!(messages[0], "开始加载");
+            assert!(messages[1].starts_with("加载完成"));
+        });
+
+        // 取消时 Effect 读到的结果是"已取消"
+        demo.update(cx, |demo, cx| demo.start_loading(cx));
+        demo.update(cx, |demo, cx| demo.cancel_loading(cx));
+        cx.run_until_parked();
+        logs.read_with(cx, |logs, _| {
+            assert_eq!(logs.messages_from(ASYNC_DEMO_SOURCE)[2..], ["开始加载", "已取消"]);
+        });
+    }
+}
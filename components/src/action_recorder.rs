@@ -0,0 +1,294 @@
+// Action 宏录制与回放
+// ==================
+// 复现界面问题时经常要反复按同一串按键，录制器把这串操作保存成 JSON 脚本：
+// 1. 在根元素上用 capture_action 拦截 Action，键盘、按钮、命令面板分发的都能录到
+// 2. 每条记录包含 Action 名字、参数（JSON）、焦点所在的上下文栈和时间戳
+// 3. 回放时按名字重新构造 Action，先把焦点移到记录时的上下文，再分发
+// 4. 可以按原速、加速或不等待回放，测试中用 PlaybackSpeed::Immediate
+//
+// ```ignore
+// let recorder = cx.new(|_| ActionRecorder::new());
+// recorder.update(cx, |recorder, _| recorder.register_target("ActionDemo", demo_focus_handle));
+//
+// div()
+//     .track_focus(&self.focus_handle)
+//     .record_action::<Increment>(&recorder)           // actions! 定义的无参数 Action
+//     .record_action_with_args::<SetValue>(&recorder)  // 带参数的 Action 需要实现 Serialize
+// ```
+
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use gpui::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// 录制下来的一次 Action 分发
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecordedAction {
+    /// Action 名字，例如 "app::SetValue"
+    pub name: String,
+    /// 参数，无参数的 Action 为 None
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub args: Option<Value>,
+    /// 分发时焦点所在的上下文栈，从外到内，例如 ["ActionDemo"]
+    #[serde(default)]
+    pub context: Vec<String>,
+    /// 距离开始录制的毫秒数
+    pub timestamp_ms: u64,
+}
+
+/// 一段录制脚本，序列化为 JSON 保存
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ActionScript {
+    pub actions: Vec<RecordedAction>,
+}
+
+impl ActionScript {
+    pub fn from_json(source: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(source)
+    }
+
+    pub fn to_json(&self) -> String {
+        // 只包含字符串、数字和 JSON 值，序列化不会失败
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    /// JSON 格式错误以 io::ErrorKind::InvalidData 返回
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        let source = std::fs::read_to_string(path)?;
+        Ok(Self::from_json(&source)?)
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        std::fs::write(path, self.to_json())
+    }
+
+    /// 脚本总时长
+    pub fn duration(&self) -> Duration {
+        Duration::from_millis(self.actions.last().map_or(0, |action| action.timestamp_ms))
+    }
+}
+
+/// 回放速度
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PlaybackSpeed {
+    /// 按录制时的间隔
+    Original,
+    /// 间隔除以倍数，例如 4.0 表示 4 倍速
+    Accelerated(f32),
+    /// 不等待，依次分发（用于测试）
+    Immediate,
+}
+
+impl PlaybackSpeed {
+    fn scale(self, interval: Duration) -> Duration {
+        match self {
+            PlaybackSpeed::Original => interval,
+            // 时间戳精确到毫秒，缩放后也取整到毫秒，避免浮点误差让间隔多出几纳秒
+            PlaybackSpeed::Accelerated(factor) if factor > 0.0 => {
+                Duration::from_millis((interval.as_millis() as f64 / factor as f64).round() as u64)
+            }
+            PlaybackSpeed::Accelerated(_) | PlaybackSpeed::Immediate => Duration::ZERO,
+        }
+    }
+}
+
+enum RecorderState {
+    Idle,
+    Recording { started_at: Instant },
+    Playing { _task: Task<()> },
+}
+
+/// 录制器 + 回放器
+///
+/// 同一时间只能录制或回放其中之一；回放分发的 Action 不会被再次录制。
+pub struct ActionRecorder {
+    state: RecorderState,
+    script: ActionScript,
+    /// 上下文名字 -> 回放时获得焦点的元素
+    targets: Vec<(SharedString, FocusHandle)>,
+    /// 回放进度：已经分发的条数
+    played: usize,
+    /// 回放中无法构造的 Action
+    errors: Vec<String>,
+}
+
+impl Default for ActionRecorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ActionRecorder {
+    pub fn new() -> Self {
+        Self {
+            state: RecorderState::Idle,
+            script: ActionScript::default(),
+            targets: Vec::new(),
+            played: 0,
+            errors: Vec::new(),
+        }
+    }
+
+    /// 注册回放目标：记录的上下文栈中包含 context 时，回放前先聚焦 focus_handle
+    pub fn register_target(&mut self, context: impl Into<SharedString>, focus_handle: FocusHandle) {
+        self.targets.push((context.into(), focus_handle));
+    }
+
+    pub fn is_recording(&self) -> bool {
+        matches!(self.state, RecorderState::Recording { .. })
+    }
+
+    pub fn is_playing(&self) -> bool {
+        matches!(self.state, RecorderState::Playing { .. })
+    }
+
+    pub fn script(&self) -> &ActionScript {
+        &self.script
+    }
+
+    /// 替换当前脚本，例如从文件加载之后
+    pub fn set_script(&mut self, script: ActionScript, cx: &mut Context<Self>) {
+        self.script = script;
+        self.played = 0;
+        cx.notify();
+    }
+
+    /// 回放进度 (已分发, 总数)
+    pub fn progress(&self) -> (usize, usize) {
+        (self.played, self.script.actions.len())
+    }
+
+    /// 最近一次回放中无法构造的 Action
+    pub fn errors(&self) -> &[String] {
+        &self.errors
+    }
+
+    /// 开始录制，清空之前的脚本
+    pub fn start_recording(&mut self, cx: &mut Context<Self>) {
+        self.script = ActionScript::default();
+        self.played = 0;
+        self.state = RecorderState::Recording {
+            started_at: Instant::now(),
+        };
+        cx.notify();
+    }
+
+    /// 停止录制或回放
+    pub fn stop(&mut self, cx: &mut Context<Self>) {
+        // 丢弃回放任务即取消回放
+        self.state = RecorderState::Idle;
+        cx.notify();
+    }
+
+    /// 由 record_action / record_action_with_args 在捕获阶段调用
+    fn record(&mut self, name: &str, args: Option<Value>, window: &Window, cx: &mut Context<Self>) {
+        let RecorderState::Recording { started_at } = self.state else {
+            return;
+        };
+        let context = window
+            .context_stack()
+            .iter()
+            .filter_map(|context| context.primary().map(|entry| entry.key.to_string()))
+            .collect();
+        self.script.actions.push(RecordedAction {
+            name: name.to_string(),
+            args,
+            context,
+            timestamp_ms: started_at.elapsed().as_millis() as u64,
+        });
+        cx.notify();
+    }
+
+    /// 回放当前脚本；正在录制时先停止录制
+    pub fn play(&mut self, speed: PlaybackSpeed, window: &mut Window, cx: &mut Context<Self>) {
+        self.played = 0;
+        self.errors.clear();
+        let actions = self.script.actions.clone();
+
+        let task = cx.spawn_in(window, async move |this, cx| {
+            let mut previous_ms = 0;
+            for recorded in actions {
+                let delay = speed.scale(Duration::from_millis(
+                    recorded.timestamp_ms.saturating_sub(previous_ms),
+                ));
+                previous_ms = recorded.timestamp_ms;
+                if !delay.is_zero() {
+                    cx.background_executor().timer(delay).await;
+                }
+
+                let result = this.update_in(cx, |recorder, window, cx| {
+                    recorder.replay(&recorded, window, cx);
+                });
+                if result.is_err() {
+                    // 录制器或窗口已经关闭
+                    return;
+                }
+            }
+            this.update(cx, |recorder, cx| recorder.stop(cx)).ok();
+        });
+
+        self.state = RecorderState::Playing { _task: task };
+        cx.notify();
+    }
+
+    fn replay(&mut self, recorded: &RecordedAction, window: &mut Window, cx: &mut Context<Self>) {
+        self.played += 1;
+        cx.notify();
+
+        let action = match cx.build_action(&recorded.name, recorded.args.clone()) {
+            Ok(action) => action,
+            Err(error) => {
+                self.errors.push(error.to_string());
+                return;
+            }
+        };
+
+        // 从内到外找到第一个注册过的上下文，焦点不在它内部时先聚焦它
+        let target = recorded.context.iter().rev().find_map(|context| {
+            self.targets
+                .iter()
+                .find(|(name, _)| name.as_ref() == context)
+                .map(|(_, handle)| handle)
+        });
+        if let Some(target) = target
+            && !target.contains_focused(window, cx)
+        {
+            target.focus(window);
+        }
+
+        window.dispatch_action(action, cx);
+    }
+}
+
+/// 在元素上捕获指定类型的 Action 并交给录制器
+///
+/// 使用捕获阶段：不影响 Action 的正常处理，只要分发路径经过这个元素就能录到。
+pub trait RecordActions: InteractiveElement + Sized {
+    /// 录制无参数的 Action（actions! 宏定义的）
+    fn record_action<A: Action>(self, recorder: &Entity<ActionRecorder>) -> Self {
+        let recorder = recorder.downgrade();
+        self.capture_action(move |action: &A, window, cx| {
+            recorder
+                .update(cx, |recorder, cx| recorder.record(action.name(), None, window, cx))
+                .ok();
+        })
+    }
+
+    /// 录制带参数的 Action，参数通过 serde 序列化为 JSON
+    fn record_action_with_args<A: Action + Serialize>(
+        self,
+        recorder: &Entity<ActionRecorder>,
+    ) -> Self {
+        let recorder = recorder.downgrade();
+        self.capture_action(move |action: &A, window, cx| {
+            let args = serde_json::to_value(action).ok();
+            recorder
+                .update(cx, |recorder, cx| recorder.record(action.name(), args, window, cx))
+                .ok();
+        })
+    }
+}
+
+impl<E: InteractiveElement> RecordActions for E {}
@@ -0,0 +1,306 @@
+// 按钮组件
+// ========
+// 从第四章的 StyledButton 提取而来，所有章节共用同一套按钮样式。
+
+use gpui::*;
+
+/// 按钮变体枚举
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ButtonVariant {
+    Primary,
+    Secondary,
+    Outline,
+    Ghost,
+    Danger,
+    Success,
+}
+
+/// 按钮尺寸枚举
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ButtonSize {
+    Small,
+    Medium,
+    Large,
+}
+
+/// 点击回调类型 - 与 div().on_click 的回调签名一致
+type ClickHandler = Box<dyn Fn(&ClickEvent, &mut Window, &mut App) + 'static>;
+
+/// 可复用的按钮组件
+///
+/// 实现了 RenderOnce，可以直接作为 child 使用：
+///
+/// ```ignore
+/// StyledButton::new("save", "保存")
+///     .variant(ButtonVariant::Primary)
+///     .on_click(cx.listener(|view, _event, _window, cx| view.save(cx)))
+/// ```
+#[derive(IntoElement)]
+pub struct StyledButton {
+    id: ElementId,
+    label: SharedString,
+    variant: ButtonVariant,
+    size: ButtonSize,
+    disabled: bool,
+    /// Tab 键导航顺序，设置后按钮可以获得焦点并响应 Enter/Space
+    tab_index: Option<isize>,
+    /// 覆盖变体的背景色和悬停/按下时的背景色
+    colors: Option<(Hsla, Hsla)>,
+    on_click: Option<ClickHandler>,
+    /// 调用方追加的样式（例如固定宽高），在变体样式之后应用
+    style: StyleRefinement,
+}
+
+impl StyledButton {
+    pub fn new(id: impl Into<ElementId>, label: impl Into<SharedString>) -> Self {
+        Self {
+            id: id.into(),
+            label: label.into(),
+            variant: ButtonVariant::Primary,
+            size: ButtonSize::Medium,
+            disabled: false,
+            tab_index: None,
+            colors: None,
+            on_click: None,
+            style: StyleRefinement::default(),
+        }
+    }
+
+    pub fn variant(mut self, variant: ButtonVariant) -> Self {
+        self.variant = variant;
+        self
+    }
+
+    pub fn size(mut self, size: ButtonSize) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// 禁用按钮：不显示 hover/active 样式，吞掉点击，不参与焦点切换
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+
+    /// 让按钮成为 Tab 焦点停靠点，聚焦后按 Enter/Space 会触发 on_click
+    pub fn tab_index(mut self, index: isize) -> Self {
+        self.tab_index = Some(index);
+        self
+    }
+
+    /// 使用变体之外的颜色（例如紫色的"同步"按钮），文字为白色，其余样式仍由变体决定
+    ///
+    /// 直接调用 .bg() 只能覆盖静止时的背景，悬停时仍会显示变体的颜色
+    pub fn colors(mut self, background: impl Into<Hsla>, hover: impl Into<Hsla>) -> Self {
+        self.colors = Some((background.into(), hover.into()));
+        self
+    }
+
+    /// 设置点击回调，通常配合 cx.listener() 使用
+    pub fn on_click(
+        mut self,
+        handler: impl Fn(&ClickEvent, &mut Window, &mut App) + 'static,
+    ) -> Self {
+        self.on_click = Some(Box::new(handler));
+        self
+    }
+}
+
+impl Styled for StyledButton {
+    fn style(&mut self) -> &mut StyleRefinement {
+        &mut self.style
+    }
+}
+
+impl RenderOnce for StyledButton {
+    fn render(self, _window: &mut Window, _cx: &mut App) -> impl IntoElement {
+        // 基础样式
+        // debug_selector 只在开启 gpui 的 test-support 时生效，
+        // 测试中可以用 cx.debug_bounds("button-<id>") 找到按钮位置并模拟点击
+        let debug_selector = format!("button-{}", self.id);
+        let mut button = div()
+            .debug_selector(|| debug_selector)
+            .id(self.id)
+            .flex()
+            .items_center()
+            .justify_center()
+            .cursor_pointer()
+            .rounded_md()
+            .font_weight(FontWeight::MEDIUM);
+
+        // 根据尺寸设置 padding 和字体大小
+        button = match self.size {
+            ButtonSize::Small => button.px_2().py_1().text_xs(),
+            ButtonSize::Medium => button.px_4().py_2().text_sm(),
+            ButtonSize::Large => button.px_6().py_3().text_base(),
+        };
+
+        // 根据变体设置颜色（hover/active 样式单独处理，禁用时不附加）
+        let (hover_bg, active_bg) = match self.variant {
+            ButtonVariant::Primary => (rgb(0x2563EB), rgb(0x1D4ED8)),
+            ButtonVariant::Secondary => (rgb(0x4B5563), rgb(0x374151)),
+            ButtonVariant::Outline => (rgb(0xEFF6FF), rgb(0xDBEAFE)),
+            ButtonVariant::Ghost => (rgb(0xF3F4F6), rgb(0xE5E7EB)),
+            ButtonVariant::Danger => (rgb(0xDC2626), rgb(0xB91C1C)),
+            ButtonVariant::Success => (rgb(0x059669), rgb(0x047857)),
+        };
+
+        button = match self.variant {
+            ButtonVariant::Primary => button.bg(rgb(0x3B82F6)).text_color(rgb(0xFFFFFF)),
+            ButtonVariant::Secondary => button.bg(rgb(0x6B7280)).text_color(rgb(0xFFFFFF)),
+            ButtonVariant::Outline => button
+                .bg(rgb(0xFFFFFF))
+                .text_color(rgb(0x3B82F6))
+                .border_1()
+                .border_color(rgb(0x3B82F6)),
+            ButtonVariant::Ghost => button.bg(rgba(0x00000000)).text_color(rgb(0x374151)),
+            ButtonVariant::Danger => button.bg(rgb(0xEF4444)).text_color(rgb(0xFFFFFF)),
+            ButtonVariant::Success => button.bg(rgb(0x10B981)).text_color(rgb(0xFFFFFF)),
+        };
+
+        // colors() 覆盖变体的背景色，按下时沿用悬停色
+        let (hover_bg, active_bg): (Hsla, Hsla) = match self.colors {
+            Some((background, hover)) => {
+                button = button.bg(background).text_color(rgb(0xFFFFFF));
+                (hover, hover)
+            }
+            None => (hover_bg.into(), active_bg.into()),
+        };
+
+        if self.disabled {
+            // 禁用状态：
+            // 1. 不附加 hover/active 样式，也不设置 tab_index（Tab 导航会跳过它）
+            // 2. 不注册 on_click，因此 Enter/Space 也无法触发
+            // 3. 拦截鼠标按下，防止点击穿透到父元素（例如父元素的聚焦逻辑）
+            button = button
+                .opacity(0.5)
+                .cursor_default()
+                .on_mouse_down(MouseButton::Left, |_event, window, cx| {
+                    window.prevent_default();
+                    cx.stop_propagation();
+                });
+        } else {
+            button = button
+                .hover(move |s| s.bg(hover_bg))
+                .active(move |s| s.bg(active_bg));
+
+            if let Some(index) = self.tab_index {
+                button = button
+                    .tab_index(index)
+                    .focus(|s| s.border_2().border_color(rgb(0x93C5FD)));
+            }
+
+            if let Some(on_click) = self.on_click {
+                button = button.on_click(on_click);
+            }
+        }
+
+        // 调用方追加的样式覆盖默认样式
+        button.style().refine(&self.style);
+
+        button.child(self.label)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // 不能 use super::*：gpui::* 中的 test 宏会遮蔽内置的 #[test]
+    use super::StyledButton;
+    use gpui::{
+        div, Context, Entity, InteractiveElement, IntoElement, KeyUpEvent, Keystroke, Modifiers,
+        MouseButton, ParentElement, Render, Styled, TestAppContext, VisualTestContext, Window,
+    };
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// 三个按钮依次设置 tab_index，中间的被禁用
+    struct ButtonRow {
+        clicks: Rc<RefCell<Vec<&'static str>>>,
+        /// 父元素收到的鼠标按下次数，用来检查禁用按钮是否吞掉了点击
+        parent_mouse_downs: usize,
+    }
+
+    impl Render for ButtonRow {
+        fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+            let button = |id: &'static str, index: isize, disabled: bool| {
+                let clicks = self.clicks.clone();
+                StyledButton::new(id, id)
+                    .tab_index(index)
+                    .disabled(disabled)
+                    .on_click(move |_, _, _| clicks.borrow_mut().push(id))
+            };
+            div()
+                .flex()
+                .gap_2()
+                .on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|row, _, _, _| row.parent_mouse_downs += 1),
+                )
+                .child(button("first", 0, false))
+                .child(button("disabled", 1, true))
+                .child(button("last", 2, false))
+        }
+    }
+
+    fn button_row(
+        cx: &mut TestAppContext,
+    ) -> (Rc<RefCell<Vec<&'static str>>>, Entity<ButtonRow>, &mut VisualTestContext) {
+        let clicks = Rc::new(RefCell::new(Vec::new()));
+        let (row, cx) = cx.add_window_view({
+            let clicks = clicks.clone();
+            |_, _| ButtonRow {
+                clicks,
+                parent_mouse_downs: 0,
+            }
+        });
+        (clicks, row, cx)
+    }
+
+    /// selector 是 "button-<id>"
+    fn click(cx: &mut VisualTestContext, selector: &'static str) {
+        let bounds = cx
+            .debug_bounds(selector)
+            .unwrap_or_else(|| panic!("找不到 {selector}"));
+        cx.simulate_click(bounds.center(), Modifiers::none());
+    }
+
+    /// 聚焦的元素在 Enter 抬起时触发点击，simulate_keystrokes 只发送按下事件
+    fn press_enter(cx: &mut VisualTestContext) {
+        cx.simulate_event(KeyUpEvent {
+            keystroke: Keystroke::parse("enter").unwrap(),
+        });
+    }
+
+    #[gpui::test]
+    fn disabled_button_swallows_clicks(cx: &mut TestAppContext) {
+        let (clicks, row, cx) = button_row(cx);
+
+        click(cx, "button-disabled");
+        assert!(clicks.borrow().is_empty());
+        // 鼠标按下被拦截，不会传到父元素
+        assert_eq!(row.read_with(cx, |row, _| row.parent_mouse_downs), 0);
+
+        click(cx, "button-first");
+        assert_eq!(*clicks.borrow(), ["first"]);
+        assert_eq!(row.read_with(cx, |row, _| row.parent_mouse_downs), 1);
+    }
+
+    #[gpui::test]
+    fn tab_skips_disabled_buttons(cx: &mut TestAppContext) {
+        let (clicks, _row, cx) = button_row(cx);
+
+        // 禁用的按钮即使设置了 tab_index 也不是焦点停靠点
+        cx.update(|window, _| window.focus_next());
+        press_enter(cx);
+        assert_eq!(*clicks.borrow(), ["first"]);
+
+        cx.update(|window, _| window.focus_next());
+        press_enter(cx);
+        assert_eq!(*clicks.borrow(), ["first", "last"]);
+
+        // 反向切换同样跳过它
+        cx.update(|window, _| window.focus_prev());
+        press_enter(cx);
+        assert_eq!(*clicks.borrow(), ["first", "last", "first"]);
+    }
+}
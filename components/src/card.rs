@@ -0,0 +1,87 @@
+// 卡片组件
+// ========
+// 从第四章的 Card 提取而来，展示阴影和边框的组合。
+
+use gpui::*;
+
+use crate::Theme;
+
+/// 卡片样式变体
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum CardVariant {
+    Default,
+    Elevated,
+    Bordered,
+}
+
+/// 卡片容器组件
+///
+/// 实现了 ParentElement，可以像 div() 一样添加子元素：
+///
+/// ```ignore
+/// Card::new()
+///     .variant(CardVariant::Elevated)
+///     .child("内容")
+/// ```
+#[derive(IntoElement)]
+pub struct Card {
+    variant: CardVariant,
+    children: Vec<AnyElement>,
+    /// 调用方追加的样式，在变体样式之后应用
+    style: StyleRefinement,
+}
+
+impl Card {
+    pub fn new() -> Self {
+        Self {
+            variant: CardVariant::Default,
+            children: Vec::new(),
+            style: StyleRefinement::default(),
+        }
+    }
+
+    pub fn variant(mut self, variant: CardVariant) -> Self {
+        self.variant = variant;
+        self
+    }
+}
+
+impl Default for Card {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ParentElement for Card {
+    fn extend(&mut self, elements: impl IntoIterator<Item = AnyElement>) {
+        self.children.extend(elements);
+    }
+}
+
+impl Styled for Card {
+    fn style(&mut self) -> &mut StyleRefinement {
+        &mut self.style
+    }
+}
+
+impl RenderOnce for Card {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        // 设置了全局主题时跟随主题颜色，否则使用浅色默认值
+        let (surface, text, border) = match cx.try_global::<Theme>() {
+            Some(theme) => (theme.surface, theme.text, theme.border),
+            None => (rgb(0xFFFFFF).into(), rgb(0x1F2937).into(), rgb(0xE5E7EB).into()),
+        };
+
+        let mut card = div().p_4().rounded_lg().bg(surface).text_color(text);
+
+        card = match self.variant {
+            CardVariant::Default => card.shadow_sm(),
+            CardVariant::Elevated => card.shadow_lg(),
+            CardVariant::Bordered => card.border_1().border_color(border),
+        };
+
+        card.style().refine(&self.style);
+
+        card.children(self.children)
+    }
+}
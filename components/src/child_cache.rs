@@ -0,0 +1,349 @@
+// 子实体缓存
+// ==========
+// 在 render 中直接 cx.new(|_| ChildView { .. }) 是常见错误：每一帧都会创建新的实体，
+// 上一帧的实体随旧的元素树一起释放，子视图的状态（计数、输入内容、滚动位置）全部丢失。
+// 1. ChildCache 按 key 缓存子实体：同一个 key 只创建一次，key 消失时丢弃对应的实体
+// 2. watch_render_churn::<V>(cx) 是调试构建中的诊断：V 类型的实体反复"刚创建就顶替旧实体"时
+//    在全局 RenderChurnWarnings 中记录警告，视图可以观察它并显示出来
+//
+// ```ignore
+// struct ListView {
+//     names: Vec<String>,
+//     rows: ChildCache<String, RowView>,
+// }
+//
+// // render 中：已有的 key 复用原来的实体，新的 key 才调用 build
+// let rows = self.rows.sync(self.names.iter().cloned(), cx, |name, _cx| RowView::new(name));
+// div().children(rows)
+// ```
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use gpui::*;
+
+/// 按 key 缓存的子实体
+pub struct ChildCache<K, V> {
+    entries: HashMap<K, Entity<V>>,
+}
+
+impl<K: Eq + Hash + Clone, V: 'static> ChildCache<K, V> {
+    pub fn new() -> Self {
+        Self {
+            entries: HashMap::new(),
+        }
+    }
+
+    /// 返回 key 对应的实体，不存在时调用 build 创建
+    pub fn get_or_create(
+        &mut self,
+        key: K,
+        cx: &mut App,
+        build: impl FnOnce(&K, &mut Context<V>) -> V,
+    ) -> Entity<V> {
+        if let Some(entity) = self.entries.get(&key) {
+            return entity.clone();
+        }
+        let entity = cx.new(|cx| build(&key, cx));
+        self.entries.insert(key, entity.clone());
+        entity
+    }
+
+    /// 让缓存与 keys 一致：复用已有的实体，为新的 key 调用 build，丢弃不在 keys 中的实体
+    ///
+    /// 返回的实体与 keys 的顺序相同，重复的 key 共用同一个实体
+    pub fn sync(
+        &mut self,
+        keys: impl IntoIterator<Item = K>,
+        cx: &mut App,
+        mut build: impl FnMut(&K, &mut Context<V>) -> V,
+    ) -> Vec<Entity<V>> {
+        let mut previous = std::mem::take(&mut self.entries);
+        keys.into_iter()
+            .map(|key| {
+                if let Some(entity) = self.entries.get(&key) {
+                    return entity.clone();
+                }
+                let entity = previous
+                    .remove(&key)
+                    .unwrap_or_else(|| cx.new(|cx| build(&key, cx)));
+                self.entries.insert(key, entity.clone());
+                entity
+            })
+            .collect()
+        // previous 中剩下的是已经消失的 key，实体句柄在这里被丢弃
+    }
+
+    pub fn get(&self, key: &K) -> Option<&Entity<V>> {
+        self.entries.get(key)
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<Entity<V>> {
+        self.entries.remove(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl<K: Eq + Hash + Clone, V: 'static> Default for ChildCache<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 连续多少次"新实体顶替旧实体"后发出警告
+#[cfg(debug_assertions)]
+const RENDER_CHURN_THRESHOLD: usize = 3;
+
+/// 调试构建中，检查 V 类型的实体是否在 render 中被反复创建
+///
+/// GPUI 没有"实体在 render 中被创建"的钩子，这里检查它造成的现象：
+/// 父视图每次重新渲染，新的 V 实体被创建，上一帧的 V 实体随旧的元素树被释放，
+/// 两者发生在同一次 effect 刷新中。同一个窗口里连续 RENDER_CHURN_THRESHOLD 次出现这种顶替时，
+/// 在 RenderChurnWarnings 中记录一次警告（不打印到终端，由视图决定如何显示）。
+///
+/// 在事件处理中用新实体替换旧实体（例如每次右键都重新创建菜单）也符合这个现象，
+/// 所以只为作为子视图渲染的类型开启。发布构建中这个函数什么也不做。
+pub fn watch_render_churn<V: 'static>(cx: &mut App) {
+    #[cfg(debug_assertions)]
+    churn::watch::<V>(cx);
+    #[cfg(not(debug_assertions))]
+    let _ = cx;
+}
+
+/// 调试构建中发出过的实体抖动警告，新增警告时通知 observe_global 的观察者
+#[derive(Default)]
+pub struct RenderChurnWarnings(pub Vec<String>);
+
+impl Global for RenderChurnWarnings {}
+
+#[cfg(debug_assertions)]
+mod churn {
+    use std::any::type_name;
+    use std::collections::HashMap;
+
+    use gpui::*;
+
+    use super::{RenderChurnWarnings, RENDER_CHURN_THRESHOLD};
+
+    #[derive(Default)]
+    struct ChurnCounter {
+        /// 当前这次 effect 刷新中创建和释放的数量
+        created: usize,
+        released: usize,
+        /// 连续出现顶替的次数
+        streak: usize,
+        warned: bool,
+    }
+
+    /// 按 (窗口, 类型名) 统计
+    #[derive(Default)]
+    struct ChurnState {
+        counters: HashMap<(WindowId, &'static str), ChurnCounter>,
+        check_scheduled: bool,
+    }
+
+    impl Global for ChurnState {}
+
+    pub(super) fn watch<V: 'static>(cx: &mut App) {
+        cx.observe_new::<V>(|_, window, cx| {
+            let Some(window) = window else {
+                return;
+            };
+            // 只保留类型名的最后一段，例如 "WelcomeView"
+            let name = type_name::<V>().rsplit("::").next().unwrap_or_default();
+            let key = (window.window_handle().window_id(), name);
+            record(key, cx, |counter| counter.created += 1);
+            cx.on_release(move |_, cx| record(key, cx, |counter| counter.released += 1))
+                .detach();
+        })
+        .detach();
+    }
+
+    fn record(key: (WindowId, &'static str), cx: &mut App, update: impl FnOnce(&mut ChurnCounter)) {
+        let state = cx.default_global::<ChurnState>();
+        update(state.counters.entry(key).or_default());
+        if !state.check_scheduled {
+            state.check_scheduled = true;
+            // 在这次 effect 刷新的末尾统一检查，创建和释放的先后顺序不影响结果
+            cx.defer(check);
+        }
+    }
+
+    fn check(cx: &mut App) {
+        let state = cx.default_global::<ChurnState>();
+        state.check_scheduled = false;
+        let mut warnings = Vec::new();
+        for ((_, type_name), counter) in &mut state.counters {
+            if counter.created == 0 {
+                counter.released = 0;
+                continue;
+            }
+            if counter.released > 0 {
+                counter.streak += 1;
+            } else {
+                counter.streak = 0;
+            }
+            counter.created = 0;
+            counter.released = 0;
+
+            if counter.streak >= RENDER_CHURN_THRESHOLD && !counter.warned {
+                counter.warned = true;
+                warnings.push(format!(
+                    "⚠️ {} 实体连续 {} 次被新创建的实体顶替：是否在 render 中调用了 cx.new？\
+                     请在构造函数中创建，或使用 ChildCache 按 key 缓存",
+                    type_name, counter.streak
+                ));
+            }
+        }
+
+        // 只在有新警告时修改全局状态，观察 RenderChurnWarnings 的视图不会被无谓地通知
+        if !warnings.is_empty() {
+            cx.default_global::<RenderChurnWarnings>().0.extend(warnings);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // 不能 use super::*：gpui::* 中的 test 宏会遮蔽内置的 #[test]
+    use super::ChildCache;
+    use gpui::{
+        div, AppContext, Context, Entity, IntoElement, ParentElement, Render, TestAppContext,
+        Window,
+    };
+
+    struct Row {
+        label: String,
+    }
+
+    impl Render for Row {
+        fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+            div().child(self.label.clone())
+        }
+    }
+
+    fn sync(
+        cache: &mut ChildCache<&'static str, Row>,
+        keys: &[&'static str],
+        cx: &mut TestAppContext,
+    ) -> Vec<Entity<Row>> {
+        cx.update(|cx| {
+            cache.sync(keys.iter().copied(), cx, |key, _| Row {
+                label: key.to_string(),
+            })
+        })
+    }
+
+    fn labels(rows: &[Entity<Row>], cx: &mut TestAppContext) -> Vec<String> {
+        cx.read(|cx| rows.iter().map(|row| row.read(cx).label.clone()).collect())
+    }
+
+    #[gpui::test]
+    fn sync_reuses_entities_for_the_same_key(cx: &mut TestAppContext) {
+        let mut cache = ChildCache::new();
+        let first = sync(&mut cache, &["a", "b"], cx);
+        // 修改实体的状态，复用时状态应该保留
+        first[0].update(cx, |row, _| row.label = "a (edited)".to_string());
+
+        let second = sync(&mut cache, &["a", "b"], cx);
+        assert_eq!(first, second);
+        assert_eq!(labels(&second, cx), ["a (edited)", "b"]);
+    }
+
+    #[gpui::test]
+    fn sync_releases_entities_for_removed_keys(cx: &mut TestAppContext) {
+        let mut cache = ChildCache::new();
+        let rows = sync(&mut cache, &["a", "b"], cx);
+        let removed = rows[1].downgrade();
+        drop(rows);
+
+        sync(&mut cache, &["a"], cx);
+        cx.run_until_parked();
+        assert!(removed.upgrade().is_none());
+        assert!(cache.get(&"b").is_none());
+        assert_eq!(cache.len(), 1);
+
+        // key 再次出现时重新创建
+        let rows = sync(&mut cache, &["a", "b"], cx);
+        assert_ne!(rows[1].entity_id(), removed.entity_id());
+    }
+
+    #[gpui::test]
+    fn sync_shares_one_entity_for_duplicate_keys(cx: &mut TestAppContext) {
+        let mut cache = ChildCache::new();
+        let rows = sync(&mut cache, &["a", "b", "a"], cx);
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0], rows[2]);
+        assert_ne!(rows[0], rows[1]);
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[gpui::test]
+    fn sync_returns_entities_in_key_order(cx: &mut TestAppContext) {
+        let mut cache = ChildCache::new();
+        let rows = sync(&mut cache, &["a", "b", "c"], cx);
+
+        let reordered = sync(&mut cache, &["c", "a", "d", "b"], cx);
+        assert_eq!(labels(&reordered, cx), ["c", "a", "d", "b"]);
+        assert_eq!(reordered[0], rows[2]);
+        assert_eq!(reordered[1], rows[0]);
+        assert_eq!(reordered[3], rows[1]);
+    }
+
+    /// 错误的写法：每次 render 都创建新的 Row
+    struct ChurningList {
+        renders: usize,
+    }
+
+    impl Render for ChurningList {
+        fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+            self.renders += 1;
+            let row = cx.new(|_| Row {
+                label: format!("第 {} 次渲染", self.renders),
+            });
+            div().child(row)
+        }
+    }
+
+    #[cfg(debug_assertions)]
+    #[gpui::test]
+    fn render_churn_is_reported_after_the_threshold(cx: &mut TestAppContext) {
+        use super::{watch_render_churn, RenderChurnWarnings, RENDER_CHURN_THRESHOLD};
+
+        cx.update(watch_render_churn::<Row>);
+        let (list, cx) = cx.add_window_view(|_, _| ChurningList { renders: 0 });
+        let warnings = |cx: &mut gpui::VisualTestContext| {
+            cx.read(|cx| {
+                cx.try_global::<RenderChurnWarnings>()
+                    .map_or(0, |warnings| warnings.0.len())
+            })
+        };
+
+        // 第一次渲染只有创建没有顶替；之后每次重新渲染都顶替一次
+        for _ in 1..RENDER_CHURN_THRESHOLD {
+            list.update(cx, |_, cx| cx.notify());
+            cx.run_until_parked();
+        }
+        assert_eq!(warnings(cx), 0);
+
+        list.update(cx, |_, cx| cx.notify());
+        cx.run_until_parked();
+        assert_eq!(warnings(cx), 1);
+        let warning = cx.read(|cx| cx.global::<RenderChurnWarnings>().0[0].clone());
+        assert!(warning.contains("Row"), "{warning}");
+
+        // 同一个类型只警告一次
+        for _ in 0..RENDER_CHURN_THRESHOLD {
+            list.update(cx, |_, cx| cx.notify());
+            cx.run_until_parked();
+        }
+        assert_eq!(warnings(cx), 1);
+    }
+}
@@ -0,0 +1,413 @@
+// 命令面板
+// ========
+// 按快捷键弹出，列出当前焦点位置可用的所有 Action，输入文字模糊过滤后回车执行：
+// 1. 打开时记录之前获得焦点的元素，用 window.available_actions() 收集它能处理的 Action
+// 2. 每个命令旁边显示该焦点位置上优先级最高的快捷键
+// 3. 执行时先把焦点还给之前的元素，再分发 Action，效果与直接按快捷键相同
+//
+// 需要参数的 Action（例如 SetValue { value }）无法用默认值构造，不会出现在列表中。
+//
+// 使用前需要在启动时调用 command_palette::init(cx) 和 text_input::init(cx) 注册快捷键，
+// 宿主视图在根元素上处理 Toggle，创建 CommandPalette 并订阅 DismissEvent。
+// 根元素需要 track_focus 并在启动时获得焦点，否则没有元素聚焦时 Toggle 分发不到宿主：
+//
+// ```ignore
+// fn toggle_command_palette(&mut self, _: &command_palette::Toggle, window: &mut Window, cx: &mut Context<Self>) {
+//     let palette = cx.new(|cx| CommandPalette::new(window, cx));
+//     cx.subscribe(&palette, |view, _, _: &DismissEvent, cx| {
+//         view.command_palette = None;
+//         cx.notify();
+//     })
+//     .detach();
+//     self.command_palette = Some(palette);
+// }
+//
+// // render 中：用 deferred(anchored()) 把面板画在所有内容之上
+// .when_some(self.command_palette.clone(), |el, palette| {
+//     el.child(deferred(anchored().position(point(px(0.), px(0.))).child(palette)))
+// })
+// ```
+
+use gpui::prelude::FluentBuilder;
+use gpui::*;
+
+use crate::text_input::{TextInput, TextInputEvent};
+use crate::Theme;
+
+actions!(command_palette, [Toggle, SelectNext, SelectPrevious, Dismiss]);
+
+/// 面板内的快捷键只在 key_context("CommandPalette") 内生效
+const KEY_CONTEXT: &str = "CommandPalette";
+
+/// 注册命令面板的快捷键，在 Application::run 里调用一次
+pub fn init(cx: &mut App) {
+    cx.bind_keys([
+        // 打开面板：没有上下文限制，在窗口任何位置都能打开
+        KeyBinding::new("secondary-shift-p", Toggle, None),
+        KeyBinding::new("up", SelectPrevious, Some(KEY_CONTEXT)),
+        KeyBinding::new("down", SelectNext, Some(KEY_CONTEXT)),
+        KeyBinding::new("ctrl-p", SelectPrevious, Some(KEY_CONTEXT)),
+        KeyBinding::new("ctrl-n", SelectNext, Some(KEY_CONTEXT)),
+        KeyBinding::new("escape", Dismiss, Some(KEY_CONTEXT)),
+    ]);
+}
+
+/// 面板中的一条命令
+struct Command {
+    action: Box<dyn Action>,
+    /// 便于阅读的名字，例如 "app::ToggleTheme" -> "app: toggle theme"
+    name: SharedString,
+    /// 打开面板前的焦点位置上，这个 Action 的快捷键
+    keystrokes: Option<SharedString>,
+}
+
+/// 过滤结果：命令下标 + 得分 + 命中的字符位置（字节偏移，用于高亮）
+struct CommandMatch {
+    command_ix: usize,
+    score: i32,
+    positions: Vec<usize>,
+}
+
+/// 命令面板
+pub struct CommandPalette {
+    query_input: Entity<TextInput>,
+    commands: Vec<Command>,
+    matches: Vec<CommandMatch>,
+    selected_index: usize,
+    /// 打开面板前获得焦点的元素，执行命令或关闭面板时把焦点还给它
+    previous_focus: Option<FocusHandle>,
+    scroll_handle: ScrollHandle,
+    _subscription: Subscription,
+}
+
+impl EventEmitter<DismissEvent> for CommandPalette {}
+
+impl Focusable for CommandPalette {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.query_input.focus_handle(cx)
+    }
+}
+
+impl CommandPalette {
+    /// 必须在改变焦点之前创建：Action 列表和快捷键都取决于当前焦点位置
+    pub fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let previous_focus = window.focused(cx);
+
+        let mut commands: Vec<Command> = window
+            .available_actions(cx)
+            .into_iter()
+            .filter(|action| !action.as_any().is::<Toggle>())
+            .map(|action| {
+                let binding = match &previous_focus {
+                    Some(focus) => window.highest_precedence_binding_for_action_in(action.as_ref(), focus),
+                    None => window.highest_precedence_binding_for_action(action.as_ref()),
+                };
+                let keystrokes = binding.map(|binding| {
+                    binding
+                        .keystrokes()
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                        .into()
+                });
+                Command {
+                    name: humanize_action_name(action.name()).into(),
+                    action,
+                    keystrokes,
+                }
+            })
+            .collect();
+        commands.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let query_input = cx.new(|cx| TextInput::new(cx).placeholder("输入命令名称..."));
+        let subscription = cx.subscribe_in(&query_input, window, Self::on_query_event);
+        query_input.focus_handle(cx).focus(window);
+
+        let mut palette = Self {
+            query_input,
+            commands,
+            matches: Vec::new(),
+            selected_index: 0,
+            previous_focus,
+            scroll_handle: ScrollHandle::new(),
+            _subscription: subscription,
+        };
+        palette.update_matches("");
+        palette
+    }
+
+    /// 当前过滤后的命令名（按匹配得分排序）
+    pub fn matching_commands(&self) -> Vec<SharedString> {
+        self.matches
+            .iter()
+            .map(|m| self.commands[m.command_ix].name.clone())
+            .collect()
+    }
+
+    fn on_query_event(
+        &mut self,
+        _input: &Entity<TextInput>,
+        event: &TextInputEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            TextInputEvent::Changed(query) => {
+                self.update_matches(query);
+                cx.notify();
+            }
+            // 输入框把 Enter 转换为 Submitted 事件
+            TextInputEvent::Submitted(_) => self.confirm(self.selected_index, window, cx),
+        }
+    }
+
+    fn update_matches(&mut self, query: &str) {
+        self.matches = self
+            .commands
+            .iter()
+            .enumerate()
+            .filter_map(|(command_ix, command)| {
+                let (score, positions) = fuzzy_match(query, &command.name)?;
+                Some(CommandMatch {
+                    command_ix,
+                    score,
+                    positions,
+                })
+            })
+            .collect();
+        // 稳定排序：得分相同的保持字母顺序
+        self.matches.sort_by_key(|m| std::cmp::Reverse(m.score));
+        self.selected_index = 0;
+        self.scroll_handle.scroll_to_item(0);
+    }
+
+    fn select_next(&mut self, _: &SelectNext, _window: &mut Window, cx: &mut Context<Self>) {
+        if !self.matches.is_empty() {
+            self.selected_index = (self.selected_index + 1) % self.matches.len();
+            self.scroll_handle.scroll_to_item(self.selected_index);
+            cx.notify();
+        }
+    }
+
+    fn select_previous(&mut self, _: &SelectPrevious, _window: &mut Window, cx: &mut Context<Self>) {
+        if !self.matches.is_empty() {
+            self.selected_index = self
+                .selected_index
+                .checked_sub(1)
+                .unwrap_or(self.matches.len() - 1);
+            self.scroll_handle.scroll_to_item(self.selected_index);
+            cx.notify();
+        }
+    }
+
+    /// 把焦点还给打开面板前的元素（原来没有焦点则清除焦点）
+    fn restore_focus(&self, window: &mut Window) {
+        match &self.previous_focus {
+            Some(focus) => window.focus(focus),
+            None => window.blur(),
+        }
+    }
+
+    fn dismiss(&mut self, _: &Dismiss, window: &mut Window, cx: &mut Context<Self>) {
+        self.restore_focus(window);
+        cx.emit(DismissEvent);
+    }
+
+    /// 执行第 match_ix 个匹配的命令
+    fn confirm(&mut self, match_ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(command) = self
+            .matches
+            .get(match_ix)
+            .map(|m| &self.commands[m.command_ix])
+        else {
+            return;
+        };
+        let action = command.action.boxed_clone();
+        self.restore_focus(window);
+        // dispatch_action 从当前焦点开始分发，焦点已经还给了原来的元素
+        window.dispatch_action(action, cx);
+        cx.emit(DismissEvent);
+    }
+
+    /// 面板自身的 Toggle：面板已打开时再按一次快捷键就关闭
+    fn toggle(&mut self, _: &Toggle, window: &mut Window, cx: &mut Context<Self>) {
+        self.dismiss(&Dismiss, window, cx);
+    }
+}
+
+impl Render for CommandPalette {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = cx.try_global::<Theme>().cloned().unwrap_or_else(Theme::light);
+        let viewport = window.viewport_size();
+
+        // 半透明遮罩铺满窗口，面板居中靠上
+        div()
+            .w(viewport.width)
+            .h(viewport.height)
+            .flex()
+            .justify_center()
+            .pt(px(80.))
+            .bg(hsla(0., 0., 0., 0.3))
+            .occlude()
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|palette, _, window, cx| palette.dismiss(&Dismiss, window, cx)),
+            )
+            .child(
+                div()
+                    .key_context(KEY_CONTEXT)
+                    .on_action(cx.listener(Self::select_next))
+                    .on_action(cx.listener(Self::select_previous))
+                    .on_action(cx.listener(Self::dismiss))
+                    .on_action(cx.listener(Self::toggle))
+                    // 点击面板内部不触发遮罩上的关闭
+                    .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                    .w(px(480.))
+                    .h_full()
+                    .max_h(px(400.))
+                    .flex()
+                    .flex_col()
+                    .gap(theme.spacing.sm)
+                    .p(theme.spacing.md)
+                    .rounded(theme.radius.lg)
+                    .bg(theme.surface)
+                    .border_1()
+                    .border_color(theme.border)
+                    .shadow_lg()
+                    .child(self.query_input.clone())
+                    .child(
+                        div()
+                            .id("command-palette-matches")
+                            .flex_1()
+                            .flex()
+                            .flex_col()
+                            .overflow_y_scroll()
+                            .track_scroll(&self.scroll_handle)
+                            .when(self.matches.is_empty(), |el| {
+                                el.child(
+                                    div()
+                                        .p(theme.spacing.sm)
+                                        .text_sm()
+                                        .text_color(theme.subtext)
+                                        .child("没有匹配的命令"),
+                                )
+                            })
+                            .children(self.matches.iter().enumerate().map(|(ix, m)| {
+                                let command = &self.commands[m.command_ix];
+                                let selected = ix == self.selected_index;
+                                // 高亮命中的字符
+                                let highlights = m
+                                    .positions
+                                    .iter()
+                                    .map(|&start| {
+                                        let len = command.name[start..]
+                                            .chars()
+                                            .next()
+                                            .map_or(1, char::len_utf8);
+                                        (
+                                            start..start + len,
+                                            HighlightStyle {
+                                                font_weight: Some(FontWeight::BOLD),
+                                                color: Some(theme.accent),
+                                                ..Default::default()
+                                            },
+                                        )
+                                    })
+                                    .collect::<Vec<_>>();
+
+                                div()
+                                    .id(ix)
+                                    .flex()
+                                    .justify_between()
+                                    .items_center()
+                                    .flex_none()
+                                    .px(theme.spacing.sm)
+                                    .py(theme.spacing.xs)
+                                    .rounded(theme.radius.sm)
+                                    .text_sm()
+                                    .text_color(theme.text)
+                                    .cursor_pointer()
+                                    .when(selected, |el| el.bg(theme.surface_muted))
+                                    .hover(|el| el.bg(theme.surface_muted))
+                                    .on_click(cx.listener(move |palette, _, window, cx| {
+                                        palette.confirm(ix, window, cx)
+                                    }))
+                                    .child(
+                                        StyledText::new(command.name.clone())
+                                            .with_highlights(highlights),
+                                    )
+                                    .when_some(command.keystrokes.clone(), |el, keystrokes| {
+                                        el.child(
+                                            div()
+                                                .px(theme.spacing.xs)
+                                                .rounded(theme.radius.sm)
+                                                .border_1()
+                                                .border_color(theme.border)
+                                                .text_xs()
+                                                .text_color(theme.subtext)
+                                                .child(keystrokes),
+                                        )
+                                    })
+                            })),
+                    ),
+            )
+    }
+}
+
+/// "app::ToggleTheme" -> "app: toggle theme"
+fn humanize_action_name(name: &str) -> String {
+    let (namespace, name) = name.rsplit_once("::").unwrap_or(("", name));
+    let mut result = String::new();
+    if !namespace.is_empty() {
+        result.push_str(&namespace.replace("::", " "));
+        result.push_str(": ");
+    }
+    for (ix, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if ix > 0 {
+                result.push(' ');
+            }
+            result.extend(c.to_lowercase());
+        } else {
+            result.push(c);
+        }
+    }
+    result.replace('_', " ")
+}
+
+/// 模糊匹配：query 的字符按顺序出现在 candidate 中即算匹配（忽略大小写和空格）
+///
+/// 得分规则：连续命中、命中单词开头得分更高，跳过的字符扣分。
+/// 返回得分和命中字符的字节偏移；query 为空时所有命令都匹配。
+fn fuzzy_match(query: &str, candidate: &str) -> Option<(i32, Vec<usize>)> {
+    let mut positions = Vec::new();
+    let mut score = 0;
+    let mut candidate_chars = candidate.char_indices().peekable();
+    let mut previous: Option<(usize, char)> = None;
+    let mut last_match: Option<usize> = None;
+
+    for query_char in query.chars().filter(|c| !c.is_whitespace()) {
+        let query_char = query_char.to_lowercase().next()?;
+        loop {
+            let (ix, c) = candidate_chars.next()?;
+            let is_word_start = previous.is_none_or(|(_, p)| !p.is_alphanumeric());
+            previous = Some((ix, c));
+            if c.to_lowercase().next() == Some(query_char) {
+                score += 1;
+                if is_word_start {
+                    score += 8;
+                }
+                if last_match.is_some_and(|last| candidate[last..ix].chars().count() == 1) {
+                    score += 5;
+                }
+                positions.push(ix);
+                last_match = Some(ix);
+                break;
+            }
+            score -= 1;
+        }
+    }
+
+    Some((score, positions))
+}
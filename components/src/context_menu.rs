@@ -0,0 +1,466 @@
+// 右键菜单
+// ========
+// 在鼠标位置弹出的菜单，支持普通项、分隔线、子菜单、禁用项和快捷键提示：
+// 1. 创建时记录之前获得焦点的元素，每一项旁边显示该位置上 Action 的快捷键
+// 2. 选中一项时先把焦点还给之前的元素，再分发 Action（与命令面板相同）
+// 3. ↑/↓ 选择（跳过分隔线和禁用项），→/Enter 打开子菜单，← 返回上一级，Esc 关闭
+// 4. 点击菜单外部关闭
+//
+// 使用前需要在启动时调用 context_menu::init(cx) 注册快捷键。宿主视图保存菜单和位置，
+// 订阅 DismissEvent，在 render 中用 deferred(anchored()) 画在鼠标位置：
+//
+// ```ignore
+// fn deploy_context_menu(&mut self, event: &MouseDownEvent, window: &mut Window, cx: &mut Context<Self>) {
+//     let menu = ContextMenu::build(window, cx, |menu| {
+//         menu.entry("复制", Copy)
+//             .disabled_entry("粘贴", Paste)
+//             .separator()
+//             .submenu("更多", |menu| menu.entry("全选", SelectAll))
+//     });
+//     cx.subscribe(&menu, |view, menu, _: &DismissEvent, cx| {
+//         // 在菜单外右键会先关闭旧菜单再打开新菜单，只清除自己
+//         if view.context_menu.as_ref().is_some_and(|(current, _)| current == &menu) {
+//             view.context_menu = None;
+//             cx.notify();
+//         }
+//     })
+//     .detach();
+//     self.context_menu = Some((menu, event.position));
+//     // 外层元素 track_focus 时，阻止它在冒泡阶段把焦点从菜单抢回来
+//     window.prevent_default();
+// }
+//
+// // render 中
+// .when_some(self.context_menu.clone(), |el, (menu, position)| {
+//     el.child(deferred(anchored().position(position).snap_to_window_with_margin(px(8.)).child(menu)))
+// })
+// ```
+
+use gpui::prelude::FluentBuilder;
+use gpui::*;
+
+use crate::Theme;
+
+actions!(
+    context_menu,
+    [SelectNext, SelectPrevious, SelectParent, SelectChild, Confirm, Cancel]
+);
+
+/// 菜单内的快捷键只在 key_context("ContextMenu") 内生效
+const KEY_CONTEXT: &str = "ContextMenu";
+
+/// 每一行的高度固定，子菜单面板据此与父菜单项对齐
+const ITEM_HEIGHT: Pixels = px(28.);
+const SEPARATOR_HEIGHT: Pixels = px(9.);
+const MENU_WIDTH: Pixels = px(200.);
+const MENU_PADDING: Pixels = px(4.);
+
+/// 注册右键菜单的快捷键，在 Application::run 里调用一次
+pub fn init(cx: &mut App) {
+    cx.bind_keys([
+        KeyBinding::new("down", SelectNext, Some(KEY_CONTEXT)),
+        KeyBinding::new("up", SelectPrevious, Some(KEY_CONTEXT)),
+        KeyBinding::new("ctrl-n", SelectNext, Some(KEY_CONTEXT)),
+        KeyBinding::new("ctrl-p", SelectPrevious, Some(KEY_CONTEXT)),
+        KeyBinding::new("left", SelectParent, Some(KEY_CONTEXT)),
+        KeyBinding::new("right", SelectChild, Some(KEY_CONTEXT)),
+        KeyBinding::new("enter", Confirm, Some(KEY_CONTEXT)),
+        KeyBinding::new("escape", Cancel, Some(KEY_CONTEXT)),
+    ]);
+}
+
+enum MenuItem {
+    Entry {
+        label: SharedString,
+        action: Box<dyn Action>,
+        disabled: bool,
+        /// 打开菜单前的焦点位置上，这个 Action 的快捷键
+        keystrokes: Option<SharedString>,
+    },
+    Separator,
+    Submenu {
+        label: SharedString,
+        items: Vec<MenuItem>,
+    },
+}
+
+impl MenuItem {
+    /// 能否被键盘选中：分隔线、禁用项和空的子菜单都会被跳过
+    fn is_selectable(&self) -> bool {
+        match self {
+            MenuItem::Entry { disabled, .. } => !disabled,
+            MenuItem::Separator => false,
+            MenuItem::Submenu { items, .. } => !items.is_empty(),
+        }
+    }
+
+    fn label(&self) -> Option<&SharedString> {
+        match self {
+            MenuItem::Entry { label, .. } | MenuItem::Submenu { label, .. } => Some(label),
+            MenuItem::Separator => None,
+        }
+    }
+
+    fn height(&self) -> Pixels {
+        match self {
+            MenuItem::Separator => SEPARATOR_HEIGHT,
+            _ => ITEM_HEIGHT,
+        }
+    }
+}
+
+/// 菜单内容的构建器，按书写顺序排列
+#[derive(Default)]
+pub struct ContextMenuItems {
+    items: Vec<MenuItem>,
+}
+
+impl FluentBuilder for ContextMenuItems {}
+
+impl ContextMenuItems {
+    /// 选中后分发 action
+    pub fn entry(self, label: impl Into<SharedString>, action: impl Action) -> Self {
+        self.push_entry(label, action, false)
+    }
+
+    /// 显示为灰色，不能选中
+    pub fn disabled_entry(self, label: impl Into<SharedString>, action: impl Action) -> Self {
+        self.push_entry(label, action, true)
+    }
+
+    pub fn separator(mut self) -> Self {
+        self.items.push(MenuItem::Separator);
+        self
+    }
+
+    /// 子菜单：悬停、→ 或 Enter 打开
+    pub fn submenu(
+        mut self,
+        label: impl Into<SharedString>,
+        build: impl FnOnce(ContextMenuItems) -> ContextMenuItems,
+    ) -> Self {
+        self.items.push(MenuItem::Submenu {
+            label: label.into(),
+            items: build(ContextMenuItems::default()).items,
+        });
+        self
+    }
+
+    fn push_entry(mut self, label: impl Into<SharedString>, action: impl Action, disabled: bool) -> Self {
+        self.items.push(MenuItem::Entry {
+            label: label.into(),
+            action: Box::new(action),
+            disabled,
+            keystrokes: None,
+        });
+        self
+    }
+}
+
+/// 右键菜单
+pub struct ContextMenu {
+    items: Vec<MenuItem>,
+    /// 每一级打开的面板中选中的项，最后一级是键盘操作的对象；至少有一级
+    levels: Vec<Option<usize>>,
+    focus_handle: FocusHandle,
+    /// 打开菜单前获得焦点的元素，选中或关闭时把焦点还给它
+    previous_focus: Option<FocusHandle>,
+}
+
+impl EventEmitter<DismissEvent> for ContextMenu {}
+
+impl Focusable for ContextMenu {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl ContextMenu {
+    /// 必须在改变焦点之前创建：快捷键提示和 Action 的分发目标都取决于当前焦点位置
+    pub fn build(
+        window: &mut Window,
+        cx: &mut App,
+        build: impl FnOnce(ContextMenuItems) -> ContextMenuItems,
+    ) -> Entity<Self> {
+        cx.new(|cx| {
+            let previous_focus = window.focused(cx);
+            let mut items = build(ContextMenuItems::default()).items;
+            fill_keystrokes(&mut items, previous_focus.as_ref(), window);
+
+            let focus_handle = cx.focus_handle();
+            focus_handle.focus(window);
+
+            Self {
+                items,
+                levels: vec![None],
+                focus_handle,
+                previous_focus,
+            }
+        })
+    }
+
+    /// 当前键盘操作的项的名字（最深一级面板中选中的项）
+    pub fn selected_label(&self) -> Option<SharedString> {
+        let level = self.levels.len() - 1;
+        let ix = self.levels[level]?;
+        self.items_at(level).get(ix)?.label().cloned()
+    }
+
+    /// 打开的面板数：1 表示只有顶层菜单
+    pub fn depth(&self) -> usize {
+        self.levels.len()
+    }
+
+    /// 第 level 级面板的菜单项
+    fn items_at(&self, level: usize) -> &[MenuItem] {
+        let mut items = self.items.as_slice();
+        for selected in &self.levels[..level] {
+            match selected.and_then(|ix| items.get(ix)) {
+                Some(MenuItem::Submenu { items: children, .. }) => items = children,
+                _ => return &[],
+            }
+        }
+        items
+    }
+
+    /// 在当前面板中按 step（+1/-1）循环移动到下一个可选中的项
+    fn move_selection(&mut self, step: isize, cx: &mut Context<Self>) {
+        let level = self.levels.len() - 1;
+        let items = self.items_at(level);
+        let len = items.len() as isize;
+        if len == 0 {
+            return;
+        }
+        let start = match self.levels[level] {
+            Some(ix) => ix as isize,
+            None if step > 0 => -1,
+            None => len,
+        };
+        let next = (1..=len)
+            .map(|offset| (start + step * offset).rem_euclid(len) as usize)
+            .find(|&ix| items[ix].is_selectable());
+        if next.is_some() {
+            self.levels[level] = next;
+            cx.notify();
+        }
+    }
+
+    fn select_next(&mut self, _: &SelectNext, _window: &mut Window, cx: &mut Context<Self>) {
+        self.move_selection(1, cx);
+    }
+
+    fn select_previous(&mut self, _: &SelectPrevious, _window: &mut Window, cx: &mut Context<Self>) {
+        self.move_selection(-1, cx);
+    }
+
+    /// 关闭最深一级的子菜单
+    fn select_parent(&mut self, _: &SelectParent, _window: &mut Window, cx: &mut Context<Self>) {
+        if self.levels.len() > 1 {
+            self.levels.pop();
+            cx.notify();
+        }
+    }
+
+    /// 选中的是子菜单时打开它，并选中其中第一个可选中的项
+    fn select_child(&mut self, _: &SelectChild, _window: &mut Window, cx: &mut Context<Self>) {
+        self.open_submenu(cx);
+    }
+
+    fn open_submenu(&mut self, cx: &mut Context<Self>) {
+        let level = self.levels.len() - 1;
+        let Some(ix) = self.levels[level] else {
+            return;
+        };
+        if let Some(MenuItem::Submenu { items, .. }) = self.items_at(level).get(ix) {
+            let first = items.iter().position(MenuItem::is_selectable);
+            self.levels.push(first);
+            cx.notify();
+        }
+    }
+
+    fn confirm(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let level = self.levels.len() - 1;
+        if let Some(ix) = self.levels[level] {
+            self.activate(level, ix, window, cx);
+        }
+    }
+
+    fn cancel(&mut self, _: &Cancel, window: &mut Window, cx: &mut Context<Self>) {
+        self.restore_focus(window);
+        cx.emit(DismissEvent);
+    }
+
+    /// 鼠标悬停：选中该项并关闭更深的子菜单，悬停在子菜单上时直接打开它
+    fn hover(&mut self, level: usize, ix: usize, cx: &mut Context<Self>) {
+        if level >= self.levels.len() || self.levels.get(level + 1).is_some() && self.levels[level] == Some(ix) {
+            return;
+        }
+        self.levels.truncate(level + 1);
+        self.levels[level] = Some(ix);
+        if matches!(self.items_at(level).get(ix), Some(MenuItem::Submenu { .. })) {
+            self.open_submenu(cx);
+        }
+        cx.notify();
+    }
+
+    /// 执行第 level 级面板中的第 ix 项：普通项分发 Action 并关闭菜单，子菜单则打开
+    fn activate(&mut self, level: usize, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let action = match self.items_at(level).get(ix) {
+            Some(MenuItem::Entry {
+                action,
+                disabled: false,
+                ..
+            }) => action.boxed_clone(),
+            Some(MenuItem::Submenu { .. }) => {
+                self.levels.truncate(level + 1);
+                self.levels[level] = Some(ix);
+                self.open_submenu(cx);
+                return;
+            }
+            _ => return,
+        };
+        self.restore_focus(window);
+        // dispatch_action 从当前焦点开始分发，焦点已经还给了原来的元素
+        window.dispatch_action(action, cx);
+        cx.emit(DismissEvent);
+    }
+
+    /// 把焦点还给打开菜单前的元素（原来没有焦点则清除焦点）
+    fn restore_focus(&self, window: &mut Window) {
+        match &self.previous_focus {
+            Some(focus) => window.focus(focus),
+            None => window.blur(),
+        }
+    }
+
+    fn render_panel(&self, level: usize, top: Pixels, theme: &Theme, cx: &mut Context<Self>) -> Div {
+        let selected = self.levels[level];
+        let open_child = self.levels.get(level + 1).and(selected);
+
+        div()
+            .mt(top)
+            .w(MENU_WIDTH)
+            .p(MENU_PADDING)
+            .flex()
+            .flex_col()
+            .bg(theme.surface)
+            .border_1()
+            .border_color(theme.border)
+            .rounded(theme.radius.md)
+            .shadow_lg()
+            .children(self.items_at(level).iter().enumerate().map(|(ix, item)| {
+                let (label, disabled, keystrokes, is_submenu) = match item {
+                    MenuItem::Separator => {
+                        return div()
+                            .h(SEPARATOR_HEIGHT)
+                            .flex()
+                            .items_center()
+                            .child(div().h(px(1.)).w_full().bg(theme.border))
+                            .into_any_element();
+                    }
+                    MenuItem::Entry {
+                        label,
+                        disabled,
+                        keystrokes,
+                        ..
+                    } => (label.clone(), *disabled, keystrokes.clone(), false),
+                    MenuItem::Submenu { label, .. } => (label.clone(), !item.is_selectable(), None, true),
+                };
+                let highlighted = selected == Some(ix) || open_child == Some(ix);
+                let debug_selector = format!("context-menu-item-{}", label);
+
+                div()
+                    .id(SharedString::from(format!("context-menu-{}-{}", level, ix)))
+                    .debug_selector(|| debug_selector)
+                    .h(ITEM_HEIGHT)
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .gap(theme.spacing.sm)
+                    .px(theme.spacing.sm)
+                    .rounded(theme.radius.sm)
+                    .text_sm()
+                    .text_color(if disabled { theme.subtext } else { theme.text })
+                    .when(highlighted && !disabled, |el| el.bg(theme.surface_muted))
+                    .when(!disabled, |el| {
+                        el.cursor_pointer()
+                            .on_hover(cx.listener(move |menu, hovered: &bool, _window, cx| {
+                                if *hovered {
+                                    menu.hover(level, ix, cx);
+                                }
+                            }))
+                            .on_click(cx.listener(move |menu, _, window, cx| {
+                                menu.activate(level, ix, window, cx)
+                            }))
+                    })
+                    .child(label)
+                    .when_some(keystrokes, |el, keystrokes| {
+                        el.child(div().text_xs().text_color(theme.subtext).child(keystrokes))
+                    })
+                    .when(is_submenu, |el| {
+                        el.child(div().text_xs().text_color(theme.subtext).child("▸"))
+                    })
+                    .into_any_element()
+            }))
+    }
+}
+
+impl Render for ContextMenu {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = cx.try_global::<Theme>().cloned().unwrap_or_else(Theme::light);
+
+        // 各级面板从左到右排成一行，都在根元素内部，点击子菜单不算"菜单外部"
+        let mut top = px(0.);
+        let mut panels = Vec::with_capacity(self.levels.len());
+        for level in 0..self.levels.len() {
+            panels.push(self.render_panel(level, top, &theme, cx));
+            // 下一级面板与打开它的菜单项顶部对齐
+            if let Some(ix) = self.levels[level] {
+                let items = self.items_at(level);
+                top += items[..ix].iter().map(MenuItem::height).fold(px(0.), |a, b| a + b);
+            }
+        }
+
+        div()
+            .key_context(KEY_CONTEXT)
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::select_parent))
+            .on_action(cx.listener(Self::select_child))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .on_mouse_down_out(cx.listener(|menu, _, window, cx| menu.cancel(&Cancel, window, cx)))
+            .occlude()
+            .flex()
+            .items_start()
+            .children(panels)
+    }
+}
+
+/// 查出每个普通项在打开菜单前的焦点位置上的快捷键
+fn fill_keystrokes(items: &mut [MenuItem], focus: Option<&FocusHandle>, window: &Window) {
+    for item in items {
+        match item {
+            MenuItem::Entry {
+                action, keystrokes, ..
+            } => {
+                let binding = match focus {
+                    Some(focus) => window.highest_precedence_binding_for_action_in(action.as_ref(), focus),
+                    None => window.highest_precedence_binding_for_action(action.as_ref()),
+                };
+                *keystrokes = binding.map(|binding| {
+                    binding
+                        .keystrokes()
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                        .into()
+                });
+            }
+            MenuItem::Submenu { items, .. } => fill_keystrokes(items, focus, window),
+            MenuItem::Separator => {}
+        }
+    }
+}
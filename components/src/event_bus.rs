@@ -0,0 +1,162 @@
+// 类型化事件总线
+// ==============
+// EventEmitter 需要订阅者拿到发出者的 Entity，兄弟组件之间只能通过共同的父组件转发。
+// 事件总线是一个全局状态，按消息类型分发：
+// 1. 任何实体都可以发布任意 'static 类型的消息，不需要知道谁在监听
+// 2. 任何实体都可以按消息类型订阅，返回 Subscription，丢弃时自动取消订阅
+// 3. 发布在当前更新结束后才分发（cx.defer），发布者和订阅者可以是同一个实体
+// 4. 按订阅顺序调用；订阅者实体已释放时跳过
+//
+// ```ignore
+// use gpui_components::EventBusContext;
+//
+// struct CounterChanged { value: i32 }
+//
+// // 发布方（在 Context<T> 中）
+// cx.publish(CounterChanged { value: self.count });
+//
+// // 订阅方：保存返回的 Subscription，和 cx.subscribe 一样
+// let subscription = cx.subscribe_bus(|this: &mut TotalDisplay, message: &CounterChanged, cx| {
+//     this.total += message.value;
+//     cx.notify();
+// });
+// ```
+
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use gpui::*;
+
+type Handler = Rc<RefCell<dyn FnMut(&dyn Any, &mut App)>>;
+
+#[derive(Default)]
+struct BusState {
+    /// 按消息类型保存订阅者，保持订阅顺序
+    handlers: HashMap<TypeId, Vec<(usize, Handler)>>,
+    next_id: usize,
+}
+
+impl BusState {
+    fn contains(&self, message_type: TypeId, id: usize) -> bool {
+        self.handlers
+            .get(&message_type)
+            .is_some_and(|handlers| handlers.iter().any(|(handler_id, _)| *handler_id == id))
+    }
+
+    fn remove(&mut self, message_type: TypeId, id: usize) {
+        if let Some(handlers) = self.handlers.get_mut(&message_type) {
+            handlers.retain(|(handler_id, _)| *handler_id != id);
+            if handlers.is_empty() {
+                self.handlers.remove(&message_type);
+            }
+        }
+    }
+}
+
+/// 全局事件总线，第一次发布或订阅时自动创建
+///
+/// 订阅表放在 Rc 中：Subscription 被丢弃时拿不到 App，只能通过弱引用取消订阅
+#[derive(Default)]
+pub struct EventBus(Rc<RefCell<BusState>>);
+
+impl Global for EventBus {}
+
+impl EventBus {
+    fn state(cx: &mut App) -> Rc<RefCell<BusState>> {
+        cx.default_global::<EventBus>().0.clone()
+    }
+
+    /// 发布一条消息，当前更新结束后按订阅顺序交给所有订阅了 M 的回调
+    pub fn publish<M: 'static>(message: M, cx: &mut App) {
+        let state = Self::state(cx);
+        cx.defer(move |cx| {
+            let message_type = TypeId::of::<M>();
+            let handlers = state
+                .borrow()
+                .handlers
+                .get(&message_type)
+                .cloned()
+                .unwrap_or_default();
+            for (id, handler) in handlers {
+                // 前面的订阅者可能在回调中取消了这个订阅
+                if !state.borrow().contains(message_type, id) {
+                    continue;
+                }
+                (handler.borrow_mut())(&message, cx);
+            }
+        });
+    }
+
+    /// 订阅类型为 M 的消息，返回的 Subscription 被丢弃时取消订阅
+    pub fn subscribe<M: 'static>(
+        cx: &mut App,
+        mut on_message: impl FnMut(&M, &mut App) + 'static,
+    ) -> Subscription {
+        let state = Self::state(cx);
+        let message_type = TypeId::of::<M>();
+        let id = {
+            let mut state = state.borrow_mut();
+            let id = state.next_id;
+            state.next_id += 1;
+            let handler: Handler = Rc::new(RefCell::new(move |message: &dyn Any, cx: &mut App| {
+                if let Some(message) = message.downcast_ref::<M>() {
+                    on_message(message, cx);
+                }
+            }));
+            state.handlers.entry(message_type).or_default().push((id, handler));
+            id
+        };
+
+        let state = Rc::downgrade(&state);
+        Subscription::new(move || {
+            if let Some(state) = state.upgrade() {
+                state.borrow_mut().remove(message_type, id);
+            }
+        })
+    }
+
+    /// 当前订阅了 M 的回调数量
+    pub fn subscriber_count<M: 'static>(cx: &App) -> usize {
+        cx.try_global::<EventBus>().map_or(0, |bus| {
+            bus.0
+                .borrow()
+                .handlers
+                .get(&TypeId::of::<M>())
+                .map_or(0, Vec::len)
+        })
+    }
+}
+
+/// 在 Context<T> 上发布和订阅总线消息
+pub trait EventBusContext<T> {
+    /// 发布一条消息，见 EventBus::publish
+    fn publish<M: 'static>(&mut self, message: M);
+
+    /// 订阅类型为 M 的消息，回调中可以修改当前实体
+    ///
+    /// 只持有实体的弱引用：实体释放后回调不再执行，和 cx.subscribe 一样需要保存返回的 Subscription
+    fn subscribe_bus<M: 'static>(
+        &mut self,
+        on_message: impl FnMut(&mut T, &M, &mut Context<T>) + 'static,
+    ) -> Subscription;
+}
+
+impl<T: 'static> EventBusContext<T> for Context<'_, T> {
+    fn publish<M: 'static>(&mut self, message: M) {
+        EventBus::publish(message, self);
+    }
+
+    fn subscribe_bus<M: 'static>(
+        &mut self,
+        mut on_message: impl FnMut(&mut T, &M, &mut Context<T>) + 'static,
+    ) -> Subscription {
+        let entity = self.weak_entity();
+        EventBus::subscribe(self, move |message: &M, cx| {
+            entity
+                .update(cx, |this, cx| on_message(this, message, cx))
+                .ok();
+        })
+    }
+}
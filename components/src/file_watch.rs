@@ -0,0 +1,131 @@
+// 命令行文件参数与文件热重载
+// ==========================
+// theme_file、keymap_file 以及各章节的 --state / --macro 参数共用的两个工具：
+// 1. file_from_args() 从命令行参数中读取 `<flag> <path>`
+// 2. watch_file() 轮询文件修改时间，变化后读取文件内容，再回到主线程交给回调
+//
+// 读取元数据和文件内容都在 background_executor 上完成，UI 线程只运行回调。
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use gpui::*;
+
+/// 轮询文件修改时间的间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// 从命令行参数中读取 `<flag> <path>`，例如 file_from_args("--theme")
+pub fn file_from_args(flag: &str) -> Option<PathBuf> {
+    let mut args = std::env::args().skip(1).skip_while(|arg| arg != flag);
+    args.next()?;
+    args.next().map(PathBuf::from)
+}
+
+/// 监听文件：立即读取一次，之后每当文件修改时间变化就重新读取
+///
+/// on_change 在主线程收到文件内容（或读取错误）；文件被删除时也会收到一次错误。
+/// 返回的 Task 被 drop 时停止监听，通常直接 `.detach()`。
+pub fn watch_file(
+    path: PathBuf,
+    mut on_change: impl FnMut(&Path, io::Result<String>, &mut App) + 'static,
+    cx: &mut App,
+) -> Task<()> {
+    cx.spawn(async move |cx| {
+        let mut last_modified: Option<SystemTime> = None;
+        let mut loaded_once = false;
+
+        loop {
+            // 在后台线程检查修改时间，只有变化时才读取内容
+            let changed = cx
+                .background_executor()
+                .spawn({
+                    let path = path.clone();
+                    async move {
+                        let modified = std::fs::metadata(&path).and_then(|meta| meta.modified()).ok();
+                        if loaded_once && modified == last_modified {
+                            None
+                        } else {
+                            Some((modified, std::fs::read_to_string(&path)))
+                        }
+                    }
+                })
+                .await;
+
+            if let Some((modified, source)) = changed {
+                loaded_once = true;
+                last_modified = modified;
+
+                if cx.update(|cx| on_change(&path, source, cx)).is_err() {
+                    // 应用已经退出
+                    break;
+                }
+            }
+
+            cx.background_executor().timer(POLL_INTERVAL).await;
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    // 不能 use super::*：gpui::* 中的 test 宏会遮蔽内置的 #[test]
+    use super::{watch_file, POLL_INTERVAL};
+    use gpui::TestAppContext;
+    use std::cell::RefCell;
+    use std::fs::File;
+    use std::path::PathBuf;
+    use std::rc::Rc;
+    use std::time::{Duration, SystemTime};
+
+    fn temp_file(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("gpui_components_file_watch_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    /// 写入内容并显式设置修改时间，避免文件系统时间精度导致两次写入的时间相同
+    fn write(path: &PathBuf, contents: &str, seconds: u64) {
+        std::fs::write(path, contents).unwrap();
+        File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(seconds))
+            .unwrap();
+    }
+
+    #[gpui::test]
+    fn reloads_only_when_the_file_changes(cx: &mut TestAppContext) {
+        let path = temp_file("watched.txt");
+        write(&path, "one", 1);
+
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let _watch = cx.update(|cx| {
+            let seen = seen.clone();
+            watch_file(
+                path.clone(),
+                move |_, source, _| seen.borrow_mut().push(source.ok()),
+                cx,
+            )
+        });
+        cx.run_until_parked();
+        assert_eq!(*seen.borrow(), vec![Some("one".to_string())]);
+
+        // 修改时间没变：不重新读取
+        cx.executor().advance_clock(POLL_INTERVAL);
+        assert_eq!(seen.borrow().len(), 1);
+
+        write(&path, "two", 2);
+        cx.executor().advance_clock(POLL_INTERVAL);
+        assert_eq!(seen.borrow().last(), Some(&Some("two".to_string())));
+
+        // 删除文件：回调收到读取错误
+        std::fs::remove_file(&path).unwrap();
+        cx.executor().advance_clock(POLL_INTERVAL);
+        assert_eq!(seen.borrow().last(), Some(&None));
+        assert_eq!(seen.borrow().len(), 3);
+    }
+}
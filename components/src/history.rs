@@ -0,0 +1,316 @@
+// 撤销/重做历史
+// ============
+// 记录针对 Entity<T> 的可逆修改，支持跨多个实体的事务：
+// 1. Undoable - 实体提供 snapshot() / restore()，描述"修改前后"的状态
+// 2. Transaction - 一次用户操作，可以包含多个实体的修改（例如"重置所有"）
+// 3. UndoHistory - 撤销栈 + 重做栈，同一分组的连续操作在短时间内合并为一步
+//
+// 历史只保存 WeakEntity，实体被释放后对应的修改在撤销时自动跳过。
+// 快捷键通过 history::init(cx) 注册：secondary-z 撤销，secondary-shift-z 重做，
+// 焦点在 TextInput 内时不生效。
+
+use std::any::Any;
+use std::time::{Duration, Instant};
+
+use gpui::*;
+
+actions!(history, [Undo, Redo]);
+
+/// 同一分组的操作间隔小于它时合并为一步撤销
+const GROUP_INTERVAL: Duration = Duration::from_millis(1000);
+
+/// 历史最多保留的步数，超出时丢弃最早的记录
+const MAX_UNDO_STEPS: usize = 100;
+
+/// 注册撤销/重做快捷键，在 Application::run 里调用一次
+///
+/// 谓词 `!TextInput` 让输入框里的 Ctrl+Z 不会撤销输入框之外的修改（TextInput 没有自己的撤销历史）。
+/// 谓词需要至少一层 key_context 才能匹配，处理 Undo/Redo 的视图要在根元素上设置 key_context。
+/// TextArea 在自己的上下文中绑定了同样的按键，焦点在它内部时由它自己处理。
+pub fn init(cx: &mut App) {
+    cx.bind_keys([
+        KeyBinding::new("secondary-z", Undo, Some("!TextInput")),
+        KeyBinding::new("secondary-shift-z", Redo, Some("!TextInput")),
+    ]);
+}
+
+/// 可以被撤销的实体：能保存快照，也能从快照恢复
+///
+/// ```ignore
+/// impl Undoable for Counter {
+///     type Snapshot = i32;
+///     fn snapshot(&self) -> i32 { self.count }
+///     fn restore(&mut self, count: i32, cx: &mut Context<Self>) {
+///         self.count = count;
+///         cx.notify();
+///     }
+/// }
+/// ```
+pub trait Undoable: Sized + 'static {
+    type Snapshot: Clone + PartialEq + 'static;
+
+    fn snapshot(&self) -> Self::Snapshot;
+
+    /// 恢复到快照，需要自己调用 cx.notify()
+    fn restore(&mut self, snapshot: Self::Snapshot, cx: &mut Context<Self>);
+}
+
+/// 单个实体的一次修改（类型擦除后存入 Transaction）
+trait Change {
+    fn entity_id(&self) -> EntityId;
+    fn undo(&self, cx: &mut App);
+    fn redo(&self, cx: &mut App);
+    /// 把同一实体的更新修改并入自己：保留最早的 before，换成最新的 after
+    fn absorb(&mut self, newer: Box<dyn Change>) -> Result<(), Box<dyn Change>>;
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+}
+
+struct EntityChange<T: Undoable> {
+    entity: WeakEntity<T>,
+    before: T::Snapshot,
+    after: T::Snapshot,
+}
+
+impl<T: Undoable> Change for EntityChange<T> {
+    fn entity_id(&self) -> EntityId {
+        self.entity.entity_id()
+    }
+
+    fn undo(&self, cx: &mut App) {
+        let snapshot = self.before.clone();
+        // 实体已被释放时跳过
+        self.entity
+            .update(cx, |entity, cx| entity.restore(snapshot, cx))
+            .ok();
+    }
+
+    fn redo(&self, cx: &mut App) {
+        let snapshot = self.after.clone();
+        self.entity
+            .update(cx, |entity, cx| entity.restore(snapshot, cx))
+            .ok();
+    }
+
+    fn absorb(&mut self, newer: Box<dyn Change>) -> Result<(), Box<dyn Change>> {
+        if newer.entity_id() != self.entity_id() {
+            return Err(newer);
+        }
+        match newer.into_any().downcast::<EntityChange<T>>() {
+            Ok(newer) => {
+                self.after = newer.after;
+                Ok(())
+            }
+            // 实体 ID 相同，类型一定相同
+            Err(_) => unreachable!("同一个实体的修改类型不同"),
+        }
+    }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+/// 一次用户操作：按顺序记录的若干实体修改，撤销时逆序恢复
+pub struct Transaction {
+    label: SharedString,
+    group: Option<SharedString>,
+    changes: Vec<Box<dyn Change>>,
+}
+
+impl Transaction {
+    /// label 显示在撤销/重做按钮上，例如 "重置所有"
+    pub fn new(label: impl Into<SharedString>) -> Self {
+        Self {
+            label: label.into(),
+            group: None,
+            changes: Vec::new(),
+        }
+    }
+
+    /// 同一分组的连续操作在 GROUP_INTERVAL 内合并为一步，例如连续点击同一个 + 按钮
+    pub fn group(mut self, group: impl Into<SharedString>) -> Self {
+        self.group = Some(group.into());
+        self
+    }
+
+    /// 记录已经发生的修改（例如在事件处理器中，修改由子组件自己完成）
+    pub fn record<T: Undoable>(
+        &mut self,
+        entity: &Entity<T>,
+        before: T::Snapshot,
+        after: T::Snapshot,
+    ) {
+        if before == after {
+            return;
+        }
+        self.push_change(Box::new(EntityChange {
+            entity: entity.downgrade(),
+            before,
+            after,
+        }));
+    }
+
+    /// 修改实体并自动记录修改前后的快照
+    pub fn update<T: Undoable, R>(
+        &mut self,
+        entity: &Entity<T>,
+        cx: &mut App,
+        update: impl FnOnce(&mut T, &mut Context<T>) -> R,
+    ) -> R {
+        let (before, after, result) = entity.update(cx, |entity, cx| {
+            let before = entity.snapshot();
+            let result = update(entity, cx);
+            (before, entity.snapshot(), result)
+        });
+        self.record(entity, before, after);
+        result
+    }
+
+    pub fn label(&self) -> &SharedString {
+        &self.label
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// 同一实体在一个事务中只保留一条修改
+    fn push_change(&mut self, change: Box<dyn Change>) {
+        let existing = self
+            .changes
+            .iter_mut()
+            .find(|existing| existing.entity_id() == change.entity_id());
+        if let Some(existing) = existing {
+            // 实体 ID 相同时 absorb 总是成功
+            let _ = existing.absorb(change);
+        } else {
+            self.changes.push(change);
+        }
+    }
+
+    fn undo(&self, cx: &mut App) {
+        for change in self.changes.iter().rev() {
+            change.undo(cx);
+        }
+    }
+
+    fn redo(&self, cx: &mut App) {
+        for change in &self.changes {
+            change.redo(cx);
+        }
+    }
+}
+
+struct Entry {
+    transaction: Transaction,
+    /// 最后一次并入修改的时间，用于判断是否继续合并；None 表示不再合并
+    last_edit: Option<Instant>,
+}
+
+/// 撤销栈 + 重做栈
+///
+/// ```ignore
+/// let mut transaction = Transaction::new("重置所有");
+/// transaction.update(&self.counter_a, cx, |counter, cx| counter.set(0, cx));
+/// transaction.update(&self.counter_b, cx, |counter, cx| counter.set(0, cx));
+/// self.history.push(transaction);
+///
+/// self.history.undo(cx); // 两个计数器一起恢复
+/// ```
+pub struct UndoHistory {
+    undo_stack: Vec<Entry>,
+    redo_stack: Vec<Transaction>,
+    group_interval: Duration,
+}
+
+impl Default for UndoHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UndoHistory {
+    pub fn new() -> Self {
+        Self {
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            group_interval: GROUP_INTERVAL,
+        }
+    }
+
+    /// 修改合并间隔，Duration::ZERO 表示不合并
+    pub fn set_group_interval(&mut self, interval: Duration) {
+        self.group_interval = interval;
+    }
+
+    /// 记录一次操作；新操作会清空重做栈
+    pub fn push(&mut self, transaction: Transaction) {
+        if transaction.is_empty() {
+            return;
+        }
+        self.redo_stack.clear();
+
+        let now = Instant::now();
+        if let Some(last) = self.undo_stack.last_mut()
+            && transaction.group.is_some()
+            && last.transaction.group == transaction.group
+            && last
+                .last_edit
+                .is_some_and(|last_edit| now.duration_since(last_edit) < self.group_interval)
+        {
+            for change in transaction.changes {
+                last.transaction.push_change(change);
+            }
+            last.last_edit = Some(now);
+            return;
+        }
+
+        self.undo_stack.push(Entry {
+            transaction,
+            last_edit: Some(now),
+        });
+        if self.undo_stack.len() > MAX_UNDO_STEPS {
+            self.undo_stack.remove(0);
+        }
+    }
+
+    /// 撤销最近一步，返回它的名字
+    pub fn undo(&mut self, cx: &mut App) -> Option<SharedString> {
+        let entry = self.undo_stack.pop()?;
+        entry.transaction.undo(cx);
+        let label = entry.transaction.label.clone();
+        self.redo_stack.push(entry.transaction);
+        Some(label)
+    }
+
+    /// 重做最近撤销的一步，返回它的名字
+    pub fn redo(&mut self, cx: &mut App) -> Option<SharedString> {
+        let transaction = self.redo_stack.pop()?;
+        transaction.redo(cx);
+        let label = transaction.label.clone();
+        // 重做后的步骤不再与后续操作合并
+        self.undo_stack.push(Entry {
+            transaction,
+            last_edit: None,
+        });
+        Some(label)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    /// 下一次撤销的操作名
+    pub fn undo_label(&self) -> Option<&SharedString> {
+        self.undo_stack.last().map(|entry| entry.transaction.label())
+    }
+
+    /// 下一次重做的操作名
+    pub fn redo_label(&self) -> Option<&SharedString> {
+        self.redo_stack.last().map(Transaction::label)
+    }
+}
@@ -0,0 +1,435 @@
+// 用户快捷键文件
+// ==============
+// 不同键盘布局的用户可以在 JSON 文件中重新绑定快捷键，无需重新编译：
+// 1. load_keymap() 按名字通过 Action 注册表构造 Action，逐条校验并收集诊断信息
+// 2. watch_keymap_file() 通过 watch_file() 监听文件修改，变化后重新应用默认快捷键 + 用户快捷键
+//
+// 文件格式：上下文 -> 按键 -> Action
+//
+//     {
+//       "ActionDemo": {
+//         "=": "app::Increment",                   // Action 名字（命名空间::名字）
+//         "ctrl-0": ["app::SetValue", { "value": 0 }], // 带参数的 Action：[名字, 参数]
+//         "r": null,                               // null 表示解除这个按键的绑定
+//         "ctrl-k ctrl-c": "app::Reset"            // 空格分隔的多个按键是组合键序列
+//       },
+//       "": {                                      // 空字符串表示不限上下文
+//         "ctrl-shift-t": "app::ToggleTheme"
+//       }
+//     }
+//
+// 有问题的条目会被跳过并记录在 KeymapFileStatus 中，其余条目照常生效。
+//
+// 组合键序列（"g g"、"ctrl-k ctrl-c"）由 GPUI 匹配：按下前缀后进入等待状态，
+// 1 秒内没有按下后续按键则放弃前缀，按普通按键处理。pending_chord_bindings() 列出
+// 当前前缀可以继续完成的绑定，用于显示提示。
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use gpui::*;
+use serde::de::{Deserializer, MapAccess, Visitor};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::{file_from_args, watch_file};
+
+/// 当前等待中的组合键前缀可以继续完成的绑定（按注册顺序，后注册的优先级更高）
+///
+/// 只包含在当前焦点位置的上下文中生效的绑定；没有等待中的前缀时返回空列表。
+pub fn pending_chord_bindings(window: &Window, cx: &App) -> Vec<KeyBinding> {
+    let Some(pending) = window.pending_input_keystrokes() else {
+        return Vec::new();
+    };
+    let context_stack = window.context_stack();
+    let keymap = cx.key_bindings();
+    let keymap = keymap.borrow();
+    keymap
+        .bindings()
+        .filter(|binding| binding.match_keystrokes(pending) == Some(true))
+        .filter(|binding| !binding.action().as_any().is::<NoAction>())
+        .filter(|binding| {
+            binding
+                .predicate()
+                .is_none_or(|predicate| predicate.depth_of(&context_stack).is_some())
+        })
+        .cloned()
+        .collect()
+}
+
+/// 保留书写顺序的 JSON 对象，用于发现同一上下文中重复的按键
+struct OrderedEntries<T>(Vec<(String, T)>);
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for OrderedEntries<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct EntriesVisitor<T>(std::marker::PhantomData<T>);
+
+        impl<'de, T: Deserialize<'de>> Visitor<'de> for EntriesVisitor<T> {
+            type Value = OrderedEntries<T>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a JSON object")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut entries = Vec::new();
+                while let Some(entry) = map.next_entry()? {
+                    entries.push(entry);
+                }
+                Ok(OrderedEntries(entries))
+            }
+        }
+
+        deserializer.deserialize_map(EntriesVisitor(std::marker::PhantomData))
+    }
+}
+
+/// 快捷键文件的原始结构：上下文 -> 按键 -> Action
+type KeymapFile = OrderedEntries<OrderedEntries<Value>>;
+
+/// 整个文件无法使用时的错误
+#[derive(Clone, Debug, PartialEq)]
+pub enum KeymapError {
+    /// 读取文件失败
+    Io { path: PathBuf, message: String },
+    /// JSON 语法错误，或结构不是 上下文 -> 按键 -> Action
+    Parse { path: PathBuf, message: String },
+}
+
+impl fmt::Display for KeymapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeymapError::Io { path, message } => {
+                write!(f, "无法读取快捷键文件 {}: {}", path.display(), message)
+            }
+            KeymapError::Parse { path, message } => {
+                write!(f, "解析快捷键文件 {} 失败: {}", path.display(), message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for KeymapError {}
+
+/// 单个条目的问题，该条目被跳过，不影响其他条目
+#[derive(Clone, Debug, PartialEq)]
+pub enum KeymapDiagnostic {
+    /// 上下文谓词无法解析，例如 "Editor &&"
+    InvalidContext { context: String, message: String },
+    /// 按键无法解析，例如 "ctrl-"
+    InvalidKeystroke {
+        context: String,
+        keystroke: String,
+        message: String,
+    },
+    /// 注册表中没有这个名字的 Action
+    UnknownAction {
+        context: String,
+        keystroke: String,
+        action: String,
+    },
+    /// Action 存在，但参数不符合它的结构
+    InvalidArguments {
+        context: String,
+        keystroke: String,
+        action: String,
+        message: String,
+    },
+    /// 值既不是字符串、[名字, 参数] 也不是 null
+    InvalidEntry { context: String, keystroke: String },
+    /// 同一上下文中重复绑定了同一个按键，后出现的条目被忽略
+    Duplicate {
+        context: String,
+        keystroke: String,
+        first: String,
+    },
+}
+
+/// 把空上下文显示为 "(全局)"
+fn context_label(context: &str) -> &str {
+    if context.is_empty() { "(全局)" } else { context }
+}
+
+impl fmt::Display for KeymapDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeymapDiagnostic::InvalidContext { context, message } => {
+                write!(f, "[{}] 上下文无法解析: {}", context, message)
+            }
+            KeymapDiagnostic::InvalidKeystroke {
+                context,
+                keystroke,
+                message,
+            } => write!(
+                f,
+                "[{}] 按键 \"{}\" 无法解析: {}",
+                context_label(context),
+                keystroke,
+                message
+            ),
+            KeymapDiagnostic::UnknownAction {
+                context,
+                keystroke,
+                action,
+            } => write!(
+                f,
+                "[{}] {}: 未知的 Action `{}`",
+                context_label(context),
+                keystroke,
+                action
+            ),
+            KeymapDiagnostic::InvalidArguments {
+                context,
+                keystroke,
+                action,
+                message,
+            } => write!(
+                f,
+                "[{}] {}: `{}` 的参数不合法: {}",
+                context_label(context),
+                keystroke,
+                action,
+                message
+            ),
+            KeymapDiagnostic::InvalidEntry { context, keystroke } => write!(
+                f,
+                "[{}] {}: 值必须是 Action 名字、[名字, 参数] 或 null",
+                context_label(context),
+                keystroke
+            ),
+            KeymapDiagnostic::Duplicate {
+                context,
+                keystroke,
+                first,
+            } => write!(
+                f,
+                "[{}] {}: 与前面的 \"{}\" 重复，已忽略",
+                context_label(context),
+                keystroke,
+                first
+            ),
+        }
+    }
+}
+
+/// 解析结果：可以直接交给 cx.bind_keys() 的绑定 + 被跳过条目的诊断
+pub struct LoadedKeymap {
+    pub bindings: Vec<KeyBinding>,
+    pub diagnostics: Vec<KeymapDiagnostic>,
+}
+
+/// 最近一次加载快捷键文件的结果，诊断面板读取它来显示问题
+#[derive(Clone, Debug)]
+pub struct KeymapFileStatus {
+    pub path: PathBuf,
+    /// 成功应用的用户绑定数量
+    pub binding_count: usize,
+    pub error: Option<KeymapError>,
+    pub diagnostics: Vec<KeymapDiagnostic>,
+}
+
+impl Global for KeymapFileStatus {}
+
+/// 从命令行参数 `--keymap <path>` 中读取快捷键文件路径
+pub fn keymap_file_from_args() -> Option<PathBuf> {
+    file_from_args("--keymap")
+}
+
+/// 解析快捷键文件内容
+///
+/// 需要 App：Action 通过 cx.build_action() 按名字构造，只有已注册的 Action 才能被绑定。
+pub fn load_keymap(path: &Path, source: &str, cx: &App) -> Result<LoadedKeymap, KeymapError> {
+    let file: KeymapFile = serde_json::from_str(source).map_err(|err| KeymapError::Parse {
+        path: path.to_path_buf(),
+        message: err.to_string(),
+    })?;
+
+    let mut bindings = Vec::new();
+    let mut diagnostics = Vec::new();
+
+    for (context, entries) in file.0 {
+        let predicate = if context.is_empty() {
+            None
+        } else {
+            match KeyBindingContextPredicate::parse(&context) {
+                Ok(predicate) => Some(Rc::new(predicate)),
+                Err(err) => {
+                    diagnostics.push(KeymapDiagnostic::InvalidContext {
+                        context,
+                        message: err.to_string(),
+                    });
+                    continue;
+                }
+            }
+        };
+
+        // 规范化后的按键 -> 第一次出现时的写法（"shift-ctrl-a" 与 "ctrl-shift-a" 视为重复）
+        let mut seen: HashMap<String, String> = HashMap::new();
+
+        for (keystroke, value) in entries.0 {
+            let normalized = match keystroke
+                .split_whitespace()
+                .map(|source| Keystroke::parse(source).map(|k| k.unparse()))
+                .collect::<Result<Vec<_>, _>>()
+            {
+                Ok(parts) if !parts.is_empty() => parts.join(" "),
+                Ok(_) => {
+                    diagnostics.push(KeymapDiagnostic::InvalidKeystroke {
+                        context: context.clone(),
+                        keystroke,
+                        message: "按键为空".to_string(),
+                    });
+                    continue;
+                }
+                Err(err) => {
+                    diagnostics.push(KeymapDiagnostic::InvalidKeystroke {
+                        context: context.clone(),
+                        keystroke,
+                        message: err.to_string(),
+                    });
+                    continue;
+                }
+            };
+            if let Some(first) = seen.get(&normalized) {
+                diagnostics.push(KeymapDiagnostic::Duplicate {
+                    context: context.clone(),
+                    keystroke,
+                    first: first.clone(),
+                });
+                continue;
+            }
+            seen.insert(normalized, keystroke.clone());
+
+            let (name, arguments) = match value {
+                // null：用 NoAction 覆盖默认绑定，相当于解除绑定
+                Value::Null => (None, None),
+                Value::String(name) => (Some(name), None),
+                Value::Array(items) => match <[Value; 2]>::try_from(items) {
+                    Ok([Value::String(name), arguments]) => (Some(name), Some(arguments)),
+                    _ => {
+                        diagnostics.push(KeymapDiagnostic::InvalidEntry {
+                            context: context.clone(),
+                            keystroke,
+                        });
+                        continue;
+                    }
+                },
+                _ => {
+                    diagnostics.push(KeymapDiagnostic::InvalidEntry {
+                        context: context.clone(),
+                        keystroke,
+                    });
+                    continue;
+                }
+            };
+
+            let action_input = arguments.as_ref().map(|args| args.to_string().into());
+            let action = match name {
+                None => NoAction.boxed_clone(),
+                Some(name) => match cx.build_action(&name, arguments) {
+                    Ok(action) => action,
+                    Err(ActionBuildError::NotFound { .. }) => {
+                        diagnostics.push(KeymapDiagnostic::UnknownAction {
+                            context: context.clone(),
+                            keystroke,
+                            action: name,
+                        });
+                        continue;
+                    }
+                    Err(ActionBuildError::BuildError { error, .. }) => {
+                        diagnostics.push(KeymapDiagnostic::InvalidArguments {
+                            context: context.clone(),
+                            keystroke,
+                            action: name,
+                            message: error.to_string(),
+                        });
+                        continue;
+                    }
+                },
+            };
+
+            match KeyBinding::load(
+                &keystroke,
+                action,
+                predicate.clone(),
+                false,
+                action_input,
+                cx.keyboard_mapper().as_ref(),
+            ) {
+                Ok(binding) => bindings.push(binding),
+                Err(err) => diagnostics.push(KeymapDiagnostic::InvalidKeystroke {
+                    context: context.clone(),
+                    keystroke,
+                    message: err.to_string(),
+                }),
+            }
+        }
+    }
+
+    Ok(LoadedKeymap {
+        bindings,
+        diagnostics,
+    })
+}
+
+/// 重新组装 keymap：清空 -> 默认快捷键 -> 用户快捷键（后注册的优先）
+fn apply_keymap(
+    path: &Path,
+    source: Result<String, KeymapError>,
+    defaults: &dyn Fn(&mut App),
+    cx: &mut App,
+) {
+    let result = source.and_then(|source| load_keymap(path, &source, cx));
+
+    cx.clear_key_bindings();
+    defaults(cx);
+
+    let status = match result {
+        Ok(loaded) => {
+            let binding_count = loaded.bindings.len();
+            cx.bind_keys(loaded.bindings);
+            KeymapFileStatus {
+                path: path.to_path_buf(),
+                binding_count,
+                error: None,
+                diagnostics: loaded.diagnostics,
+            }
+        }
+        Err(error) => {
+            KeymapFileStatus {
+                path: path.to_path_buf(),
+                binding_count: 0,
+                error: Some(error),
+                diagnostics: Vec::new(),
+            }
+        }
+    };
+    cx.set_global(status);
+    cx.refresh_windows();
+}
+
+/// 监听快捷键文件：立即加载一次，之后每当文件修改时间变化就重新加载
+///
+/// keymap 不支持单独移除绑定，所以每次重新加载都会清空全部绑定，
+/// 再调用 defaults 注册应用的默认快捷键，最后叠加用户文件中的绑定。
+/// defaults 必须包含应用需要的所有快捷键（包括 text_input::init 之类的组件快捷键）。
+pub fn watch_keymap_file(
+    path: PathBuf,
+    defaults: impl Fn(&mut App) + 'static,
+    cx: &mut App,
+) -> Task<()> {
+    // 在后台线程读取文件；构造 Action 需要 App，在主线程完成
+    watch_file(
+        path,
+        move |path, source, cx| {
+            let source = source.map_err(|err| KeymapError::Io {
+                path: path.to_path_buf(),
+                message: err.to_string(),
+            });
+            apply_keymap(path, source, &defaults, cx);
+        },
+        cx,
+    )
+}
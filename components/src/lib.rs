@@ -0,0 +1,60 @@
+// GPUI 共享组件库
+// ================
+// 各章节共用的 UI 组件，修改一处即可在所有示例中生效：
+// 1. StyledButton - 带变体、尺寸、禁用状态的按钮
+// 2. Card - 带阴影/边框变体的卡片容器
+// 3. Theme - 全局主题（语义化设计令牌 + 浅色/深色预设）
+// 4. theme_file - 从 JSON/TOML 文件加载主题并热重载
+// 5. TextInput - 单行文本输入框（光标、选区、剪贴板、中文输入法）
+// 6. TextArea - 多行文本编辑器（软换行、行号、垂直滚动、撤销/重做）
+// 7. keymap_file - 从 JSON 文件加载用户快捷键并热重载，报告无效条目
+// 8. CommandPalette - 命令面板（列出可用 Action、模糊搜索、显示快捷键）
+// 9. history - 针对 Entity<T> 的撤销/重做历史（跨实体事务、连续操作合并）
+// 10. action_recorder - 录制分发的 Action 为 JSON 脚本，按原速或加速回放
+// 11. ContextMenu - 右键菜单（子菜单、禁用项、快捷键提示、键盘导航）
+// 12. LogPanel - 共享日志面板（有容量上限、级别/来源过滤、搜索、虚拟列表、导出）
+// 13. EventBus - 类型化事件总线（按消息类型发布/订阅，Subscription 丢弃时自动取消）
+// 14. ChildCache - 按 key 缓存子实体，调试构建中检查 render 里反复创建的实体
+// 15. Store - 单一状态实体（纯函数 reduce、中间件、只在选中部分变化时通知的 select）
+// 16. Signal / Memo / Effect - 细粒度响应式（写入后只通知读过它的视图，派生值缓存，变化时运行副作用）
+// 17. file_watch - 读取命令行中的文件参数，在后台轮询文件修改并热重载（主题、快捷键文件共用）
+
+mod button;
+mod card;
+mod theme;
+mod file_watch;
+mod theme_file;
+mod keymap_file;
+mod action_recorder;
+mod log_panel;
+mod event_bus;
+mod child_cache;
+mod store;
+mod reactive;
+
+// 输入框/编辑器/命令面板/历史/菜单的 Action（Copy、Paste、Undo...）名字很通用，不整体导出，
+// 快捷键通过 text_input::init(cx) / text_area::init(cx) / command_palette::init(cx) /
+// history::init(cx) / context_menu::init(cx) 注册
+pub mod text_input;
+pub mod text_area;
+pub mod command_palette;
+pub mod history;
+pub mod context_menu;
+
+pub use button::*;
+pub use card::*;
+pub use theme::*;
+pub use file_watch::*;
+pub use theme_file::*;
+pub use keymap_file::*;
+pub use action_recorder::*;
+pub use log_panel::*;
+pub use event_bus::*;
+pub use child_cache::*;
+pub use store::*;
+pub use reactive::*;
+pub use text_input::{TextInput, TextInputEvent};
+pub use text_area::{TextArea, TextAreaEvent};
+pub use command_palette::CommandPalette;
+pub use context_menu::{ContextMenu, ContextMenuItems};
+pub use history::{Transaction, UndoHistory, Undoable};
@@ -0,0 +1,477 @@
+// 日志面板
+// ========
+// 多个视图共用的日志实体，取代各自手写的"Vec<String> + 超过 5 条就 remove(0)"：
+// 1. 有容量上限的 VecDeque，超出时丢弃最早的条目（O(1)，记录丢弃的数量）
+// 2. 每条日志带时间（距离面板创建）、级别和来源
+// 3. uniform_list 虚拟列表，只渲染可见的行，条目再多也不影响渲染速度
+// 4. 按最低级别、来源过滤，搜索框匹配消息和来源
+// 5. 把缓冲区中的全部日志导出为文本文件
+//
+// 搜索框是 TextInput，需要在启动时调用 text_input::init(cx)。
+//
+// ```ignore
+// let logs = cx.new(|cx| LogPanel::new(500, cx).export_path("event_log.txt"));
+// // 传给多个视图共用，每个视图用自己的来源名写入
+// logs.update(cx, |logs, cx| logs.info("ActionDemo", "Increment → 1", cx));
+//
+// // render 中，放在有固定高度的容器里
+// .child(div().h(px(240.)).child(self.logs.clone()))
+// ```
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use gpui::prelude::FluentBuilder;
+use gpui::*;
+
+use crate::{ButtonSize, ButtonVariant, StyledButton, TextInput, TextInputEvent, Theme};
+
+/// 每一行的高度，uniform_list 按第一行测量，所有行必须一样高
+const ROW_HEIGHT: Pixels = px(20.);
+
+/// 日志级别，从低到高排列，过滤时显示不低于所选级别的条目
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    pub const ALL: [LogLevel; 4] = [LogLevel::Debug, LogLevel::Info, LogLevel::Warn, LogLevel::Error];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            LogLevel::Debug => "DEBUG",
+            LogLevel::Info => "INFO",
+            LogLevel::Warn => "WARN",
+            LogLevel::Error => "ERROR",
+        }
+    }
+
+    fn color(self, theme: &Theme) -> Hsla {
+        match self {
+            LogLevel::Debug => theme.subtext,
+            LogLevel::Info => theme.accent,
+            LogLevel::Warn => theme.warning,
+            LogLevel::Error => theme.danger,
+        }
+    }
+}
+
+impl fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+/// 一条日志
+#[derive(Clone, Debug, PartialEq)]
+pub struct LogEntry {
+    /// 距离面板创建的时间
+    pub time: Duration,
+    pub level: LogLevel,
+    /// 写入日志的视图或模块，例如 "ActionDemo"
+    pub source: SharedString,
+    pub message: SharedString,
+}
+
+impl LogEntry {
+    /// "+01:02.345"（分:秒.毫秒）
+    pub fn time_text(&self) -> String {
+        let millis = self.time.as_millis();
+        format!("+{:02}:{:02}.{:03}", millis / 60_000, millis / 1000 % 60, millis % 1000)
+    }
+}
+
+impl fmt::Display for LogEntry {
+    /// 导出文件中的一行："[+00:01.234] INFO  [ActionDemo] Increment → 1"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{}] {:<5} [{}] {}",
+            self.time_text(),
+            self.level.label(),
+            self.source,
+            self.message
+        )
+    }
+}
+
+/// 日志面板
+pub struct LogPanel {
+    entries: VecDeque<LogEntry>,
+    capacity: usize,
+    /// 因超出容量被丢弃的条目数
+    dropped: usize,
+    started_at: Instant,
+    /// 出现过的来源，按第一次出现的顺序，用于生成来源过滤按钮
+    sources: Vec<SharedString>,
+
+    /// 只显示不低于这个级别的条目
+    min_level: LogLevel,
+    /// 只显示这个来源的条目，None 表示全部
+    source_filter: Option<SharedString>,
+    /// 搜索词（不区分大小写），匹配消息和来源
+    query: SharedString,
+    /// 通过过滤的条目在 entries 中的下标，过滤条件或条目变化时重新计算
+    visible: Vec<usize>,
+
+    search: Entity<TextInput>,
+    scroll_handle: UniformListScrollHandle,
+    /// 有新条目时滚动到底部
+    follow_tail: bool,
+    export_path: Option<PathBuf>,
+    /// 最近一次导出的结果
+    export_status: Option<String>,
+    _subscription: Subscription,
+}
+
+impl LogPanel {
+    /// capacity 是缓冲区最多保存的条目数
+    pub fn new(capacity: usize, cx: &mut Context<Self>) -> Self {
+        let search = cx.new(|cx| TextInput::new(cx).placeholder("搜索日志..."));
+        let subscription = cx.subscribe(&search, |panel, _, event: &TextInputEvent, cx| {
+            if let TextInputEvent::Changed(query) = event {
+                panel.set_query(query.clone(), cx);
+            }
+        });
+
+        Self {
+            entries: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+            dropped: 0,
+            started_at: Instant::now(),
+            sources: Vec::new(),
+            min_level: LogLevel::Debug,
+            source_filter: None,
+            query: SharedString::default(),
+            visible: Vec::new(),
+            search,
+            scroll_handle: UniformListScrollHandle::new(),
+            follow_tail: true,
+            export_path: None,
+            export_status: None,
+            _subscription: subscription,
+        }
+    }
+
+    /// 设置后显示"导出"按钮，点击时写入这个文件
+    pub fn export_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.export_path = Some(path.into());
+        self
+    }
+
+    /// 写入一条日志
+    pub fn log(
+        &mut self,
+        level: LogLevel,
+        source: impl Into<SharedString>,
+        message: impl Into<SharedString>,
+        cx: &mut Context<Self>,
+    ) {
+        let source = source.into();
+        if !self.sources.contains(&source) {
+            self.sources.push(source.clone());
+        }
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+            self.dropped += 1;
+        }
+        self.entries.push_back(LogEntry {
+            time: self.started_at.elapsed(),
+            level,
+            source,
+            message: message.into(),
+        });
+        self.refilter();
+        cx.notify();
+    }
+
+    pub fn debug(&mut self, source: impl Into<SharedString>, message: impl Into<SharedString>, cx: &mut Context<Self>) {
+        self.log(LogLevel::Debug, source, message, cx);
+    }
+
+    pub fn info(&mut self, source: impl Into<SharedString>, message: impl Into<SharedString>, cx: &mut Context<Self>) {
+        self.log(LogLevel::Info, source, message, cx);
+    }
+
+    pub fn warn(&mut self, source: impl Into<SharedString>, message: impl Into<SharedString>, cx: &mut Context<Self>) {
+        self.log(LogLevel::Warn, source, message, cx);
+    }
+
+    pub fn error(&mut self, source: impl Into<SharedString>, message: impl Into<SharedString>, cx: &mut Context<Self>) {
+        self.log(LogLevel::Error, source, message, cx);
+    }
+
+    /// 清空缓冲区（保留过滤条件）
+    pub fn clear(&mut self, cx: &mut Context<Self>) {
+        self.entries.clear();
+        self.dropped = 0;
+        self.refilter();
+        cx.notify();
+    }
+
+    /// 缓冲区中的全部条目，从旧到新
+    pub fn entries(&self) -> impl DoubleEndedIterator<Item = &LogEntry> {
+        self.entries.iter()
+    }
+
+    /// 最新的一条
+    pub fn last(&self) -> Option<&LogEntry> {
+        self.entries.back()
+    }
+
+    /// 指定来源的全部消息，从旧到新
+    pub fn messages_from(&self, source: &str) -> Vec<SharedString> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.source == source)
+            .map(|entry| entry.message.clone())
+            .collect()
+    }
+
+    /// 通过当前过滤条件的条目，从旧到新
+    pub fn visible_entries(&self) -> impl Iterator<Item = &LogEntry> {
+        self.visible.iter().map(|&ix| &self.entries[ix])
+    }
+
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+
+    pub fn set_min_level(&mut self, level: LogLevel, cx: &mut Context<Self>) {
+        self.min_level = level;
+        self.refilter();
+        cx.notify();
+    }
+
+    pub fn set_source_filter(&mut self, source: Option<SharedString>, cx: &mut Context<Self>) {
+        self.source_filter = source;
+        self.refilter();
+        cx.notify();
+    }
+
+    /// 一般由搜索框触发，也可以直接调用
+    pub fn set_query(&mut self, query: impl Into<SharedString>, cx: &mut Context<Self>) {
+        self.query = query.into();
+        self.refilter();
+        cx.notify();
+    }
+
+    pub fn set_follow_tail(&mut self, follow_tail: bool, cx: &mut Context<Self>) {
+        self.follow_tail = follow_tail;
+        self.scroll_to_tail();
+        cx.notify();
+    }
+
+    /// 缓冲区的文本形式：一行一条，开头说明被丢弃的条目数
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        if self.dropped > 0 {
+            text.push_str(&format!("# 更早的 {} 条日志超出容量已被丢弃\n", self.dropped));
+        }
+        for entry in &self.entries {
+            text.push_str(&entry.to_string());
+            text.push('\n');
+        }
+        text
+    }
+
+    /// 导出缓冲区中的全部条目（不受过滤条件影响）
+    pub fn export(&self, path: &Path) -> std::io::Result<()> {
+        std::fs::write(path, self.to_text())
+    }
+
+    fn export_to_file(&mut self, cx: &mut Context<Self>) {
+        let Some(path) = self.export_path.clone() else {
+            return;
+        };
+        self.export_status = Some(match self.export(&path) {
+            Ok(()) => format!("已导出 {} 条到 {}", self.entries.len(), path.display()),
+            Err(err) => format!("导出失败: {}", err),
+        });
+        cx.notify();
+    }
+
+    fn matches(&self, entry: &LogEntry) -> bool {
+        if entry.level < self.min_level {
+            return false;
+        }
+        if let Some(source) = &self.source_filter
+            && entry.source != *source
+        {
+            return false;
+        }
+        if self.query.is_empty() {
+            return true;
+        }
+        let query = self.query.to_lowercase();
+        entry.message.to_lowercase().contains(&query) || entry.source.to_lowercase().contains(&query)
+    }
+
+    fn refilter(&mut self) {
+        self.visible = (0..self.entries.len())
+            .filter(|&ix| self.matches(&self.entries[ix]))
+            .collect();
+        self.scroll_to_tail();
+    }
+
+    /// 跟随最新时，条目或过滤条件变化后滚动到最后一行
+    fn scroll_to_tail(&self) {
+        if self.follow_tail && !self.visible.is_empty() {
+            self.scroll_handle
+                .scroll_to_item(self.visible.len() - 1, ScrollStrategy::Bottom);
+        }
+    }
+
+    fn render_row(&self, ix: usize, theme: &Theme) -> Div {
+        let entry = &self.entries[self.visible[ix]];
+        div()
+            .debug_selector(|| format!("log-row-{}", ix))
+            .h(ROW_HEIGHT)
+            .flex()
+            .items_center()
+            .gap(theme.spacing.sm)
+            .px(theme.spacing.sm)
+            .text_xs()
+            .whitespace_nowrap()
+            .overflow_hidden()
+            .child(div().text_color(theme.subtext).child(entry.time_text()))
+            .child(
+                div()
+                    .w(px(44.))
+                    .text_color(entry.level.color(theme))
+                    .font_weight(FontWeight::SEMIBOLD)
+                    .child(entry.level.label()),
+            )
+            .child(div().text_color(theme.subtext).child(format!("[{}]", entry.source)))
+            .child(div().text_color(theme.text).child(entry.message.clone()))
+    }
+}
+
+impl Render for LogPanel {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = cx.try_global::<Theme>().cloned().unwrap_or_else(Theme::light);
+
+        let level_buttons = LogLevel::ALL.map(|level| {
+            StyledButton::new(SharedString::from(format!("log-level-{}", level.label())), level.label())
+                .size(ButtonSize::Small)
+                .variant(if self.min_level == level {
+                    ButtonVariant::Primary
+                } else {
+                    ButtonVariant::Ghost
+                })
+                .on_click(cx.listener(move |panel, _, _window, cx| panel.set_min_level(level, cx)))
+        });
+
+        let source_buttons = std::iter::once(None)
+            .chain(self.sources.iter().cloned().map(Some))
+            .enumerate()
+            .map(|(ix, source)| {
+                let label = source.clone().unwrap_or_else(|| "全部来源".into());
+                StyledButton::new(SharedString::from(format!("log-source-{}", ix)), label)
+                    .size(ButtonSize::Small)
+                    .variant(if self.source_filter == source {
+                        ButtonVariant::Primary
+                    } else {
+                        ButtonVariant::Ghost
+                    })
+                    .on_click(cx.listener(move |panel, _, _window, cx| {
+                        panel.set_source_filter(source.clone(), cx)
+                    }))
+            })
+            .collect::<Vec<_>>();
+
+        let summary = if self.dropped > 0 {
+            format!("{} / {} 条（已丢弃 {} 条）", self.visible.len(), self.entries.len(), self.dropped)
+        } else {
+            format!("{} / {} 条", self.visible.len(), self.entries.len())
+        };
+
+        div()
+            .size_full()
+            .flex()
+            .flex_col()
+            .gap(theme.spacing.xs)
+            .p(theme.spacing.sm)
+            .bg(theme.surface)
+            .border_1()
+            .border_color(theme.border)
+            .rounded(theme.radius.md)
+            // 工具栏：级别、来源过滤
+            .child(
+                div()
+                    .flex()
+                    .flex_wrap()
+                    .items_center()
+                    .gap(theme.spacing.xs)
+                    .children(level_buttons)
+                    .child(div().w(px(1.)).h(px(16.)).bg(theme.border))
+                    .children(source_buttons),
+            )
+            // 搜索框和操作按钮
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap(theme.spacing.xs)
+                    .child(
+                        div()
+                            .debug_selector(|| "log-search".into())
+                            .flex_1()
+                            .child(self.search.clone()),
+                    )
+                    .child(
+                        StyledButton::new(
+                            "log-follow",
+                            if self.follow_tail { "跟随最新 ✓" } else { "跟随最新" },
+                        )
+                        .size(ButtonSize::Small)
+                        .variant(ButtonVariant::Ghost)
+                        .on_click(cx.listener(|panel, _, _window, cx| {
+                            panel.set_follow_tail(!panel.follow_tail, cx)
+                        })),
+                    )
+                    .child(
+                        StyledButton::new("log-clear", "清空")
+                            .size(ButtonSize::Small)
+                            .variant(ButtonVariant::Ghost)
+                            .on_click(cx.listener(|panel, _, _window, cx| panel.clear(cx))),
+                    )
+                    .when(self.export_path.is_some(), |el| {
+                        el.child(
+                            StyledButton::new("log-export", "导出")
+                                .size(ButtonSize::Small)
+                                .variant(ButtonVariant::Secondary)
+                                .on_click(cx.listener(|panel, _, _window, cx| panel.export_to_file(cx))),
+                        )
+                    }),
+            )
+            .child(
+                div()
+                    .flex()
+                    .justify_between()
+                    .text_xs()
+                    .text_color(theme.subtext)
+                    .child(summary)
+                    .when_some(self.export_status.clone(), |el, status| el.child(status)),
+            )
+            // 虚拟列表：只为可见范围内的行调用 render_row
+            .child(
+                uniform_list(
+                    "log-entries",
+                    self.visible.len(),
+                    cx.processor(move |panel, range: std::ops::Range<usize>, _window, _cx| {
+                        range.map(|ix| panel.render_row(ix, &theme)).collect()
+                    }),
+                )
+                .track_scroll(self.scroll_handle.clone())
+                .flex_1()
+                .min_h(ROW_HEIGHT * 3.),
+            )
+    }
+}
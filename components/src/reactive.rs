@@ -0,0 +1,253 @@
+// 细粒度响应式：Signal / Memo / Effect
+// ====================================
+// 普通视图的写法是"修改字段，再手动 cx.notify()"，漏掉 notify 界面就不更新，
+// 而且 notify 的是整个视图。这里的三个类型都建立在实体之上，自动记录"谁读取过我"：
+// 1. Signal<T> - 可写的值。render 中用 get/with 读取时，当前视图被登记为读者；
+//    set/update 之后只 notify 读过它的实体，不需要手动调用 cx.notify()
+// 2. Memo<T> - 由 Signal（或其他 Memo）计算出的值。计算时读取的值就是它的依赖，
+//    依赖变化后重新计算，结果没变时不会通知自己的读者
+// 3. Effect - 依赖变化时运行的副作用（写日志、保存文件），丢弃 Effect 后停止
+//
+// 读者在每次通知后清空，重新渲染（或重新计算）时再次登记，条件分支中不再读取的值不会再触发通知。
+// GPUI 重新渲染一个视图时，它的祖先视图也会重新渲染；兄弟视图只有用 AnyView::cached 渲染时才会被复用。
+//
+// ```ignore
+// struct CounterView {
+//     count: Signal<i32>,
+//     doubled: Memo<i32>,
+// }
+//
+// // 构造时
+// let count = Signal::new(0, cx);
+// let doubled = Memo::new(cx, { let count = count.clone(); move |cx| count.get(cx) * 2 });
+//
+// // render 中读取：当前视图成为 count 和 doubled 的读者
+// div().child(format!("{} × 2 = {}", self.count.get(cx), self.doubled.get(cx)))
+//
+// // 事件处理中写入：不需要 cx.notify()
+// .on_click(cx.listener(|view, _, _, cx| view.count.update(cx, |count| *count += 1)))
+// ```
+
+use std::collections::HashSet;
+
+use gpui::*;
+
+/// 一个值和读取过它的实体
+struct Tracked<T> {
+    value: T,
+    readers: HashSet<EntityId>,
+}
+
+impl<T> Tracked<T> {
+    fn new(value: T) -> Self {
+        Self {
+            value,
+            readers: HashSet::new(),
+        }
+    }
+}
+
+/// 通知读者；读者重新渲染或重新计算时会再次登记
+fn notify_readers(readers: HashSet<EntityId>, cx: &mut App) {
+    for reader in readers {
+        cx.notify(reader);
+    }
+}
+
+/// 可写的响应式值，克隆得到的是同一个值的句柄
+pub struct Signal<T> {
+    cell: Entity<Tracked<T>>,
+}
+
+impl<T> Clone for Signal<T> {
+    fn clone(&self) -> Self {
+        Self {
+            cell: self.cell.clone(),
+        }
+    }
+}
+
+impl<T: 'static> Signal<T> {
+    pub fn new(value: T, cx: &mut App) -> Self {
+        Self {
+            cell: cx.new(|_| Tracked::new(value)),
+        }
+    }
+
+    /// 读取当前值，并把 cx 所属的实体登记为读者
+    pub fn get<V: 'static>(&self, cx: &mut Context<V>) -> T
+    where
+        T: Clone,
+    {
+        self.with(cx, T::clone)
+    }
+
+    /// 借用当前值，并把 cx 所属的实体登记为读者
+    pub fn with<V: 'static, R>(&self, cx: &mut Context<V>, read: impl FnOnce(&T) -> R) -> R {
+        let reader = cx.entity_id();
+        self.cell.update(cx, |cell, _| {
+            cell.readers.insert(reader);
+            read(&cell.value)
+        })
+    }
+
+    /// 读取当前值但不登记读者，用于事件处理等 render 之外的地方
+    pub fn peek<'a>(&self, cx: &'a App) -> &'a T {
+        &self.cell.read(cx).value
+    }
+
+    /// 写入新值；与当前值相等时什么也不做
+    pub fn set(&self, value: T, cx: &mut App)
+    where
+        T: PartialEq,
+    {
+        self.write(cx, |current| {
+            if *current == value {
+                return false;
+            }
+            *current = value;
+            true
+        });
+    }
+
+    /// 原地修改，修改后总是通知读者
+    pub fn update(&self, cx: &mut App, update: impl FnOnce(&mut T)) {
+        self.write(cx, |value| {
+            update(value);
+            true
+        });
+    }
+
+    /// 当前登记的读者数量
+    pub fn reader_count(&self, cx: &App) -> usize {
+        self.cell.read(cx).readers.len()
+    }
+
+    fn write(&self, cx: &mut App, write: impl FnOnce(&mut T) -> bool) {
+        let readers = self.cell.update(cx, |cell, _| {
+            if write(&mut cell.value) {
+                std::mem::take(&mut cell.readers)
+            } else {
+                HashSet::new()
+            }
+        });
+        notify_readers(readers, cx);
+    }
+}
+
+type Compute<T> = Box<dyn Fn(&mut Context<MemoState<T>>) -> T>;
+
+/// Memo 的实体，计算闭包的 cx 参数就是它的 Context，在闭包中读取的值成为依赖
+pub struct MemoState<T> {
+    tracked: Tracked<T>,
+    compute: Compute<T>,
+    /// 计算的次数，包括创建时的第一次
+    computations: usize,
+    _observe_self: Subscription,
+}
+
+impl<T: PartialEq + 'static> MemoState<T> {
+    /// 依赖变化时被 notify，重新计算；结果没变时不通知读者
+    fn recompute(&mut self, cx: &mut Context<Self>) {
+        let value = (self.compute)(cx);
+        self.computations += 1;
+        if value != self.tracked.value {
+            self.tracked.value = value;
+            notify_readers(std::mem::take(&mut self.tracked.readers), cx);
+        }
+    }
+}
+
+/// 缓存的派生值，克隆得到的是同一个值的句柄
+pub struct Memo<T> {
+    cell: Entity<MemoState<T>>,
+}
+
+impl<T> Clone for Memo<T> {
+    fn clone(&self) -> Self {
+        Self {
+            cell: self.cell.clone(),
+        }
+    }
+}
+
+impl<T: PartialEq + 'static> Memo<T> {
+    /// 立即计算一次；compute 中通过 cx 读取的 Signal / Memo 变化后重新计算
+    pub fn new(cx: &mut App, compute: impl Fn(&mut Context<MemoState<T>>) -> T + 'static) -> Self {
+        let cell = cx.new(|cx| MemoState {
+            tracked: Tracked::new(compute(cx)),
+            compute: Box::new(compute),
+            computations: 1,
+            _observe_self: cx.observe_self(MemoState::recompute),
+        });
+        Self { cell }
+    }
+
+    /// 读取缓存的值，并把 cx 所属的实体登记为读者
+    pub fn get<V: 'static>(&self, cx: &mut Context<V>) -> T
+    where
+        T: Clone,
+    {
+        self.with(cx, T::clone)
+    }
+
+    /// 借用缓存的值，并把 cx 所属的实体登记为读者
+    pub fn with<V: 'static, R>(&self, cx: &mut Context<V>, read: impl FnOnce(&T) -> R) -> R {
+        let reader = cx.entity_id();
+        self.cell.update(cx, |cell, _| {
+            cell.tracked.readers.insert(reader);
+            read(&cell.tracked.value)
+        })
+    }
+
+    /// 读取缓存的值但不登记读者
+    pub fn peek<'a>(&self, cx: &'a App) -> &'a T {
+        &self.cell.read(cx).tracked.value
+    }
+
+    /// 到目前为止计算的次数
+    pub fn computations(&self, cx: &App) -> usize {
+        self.cell.read(cx).computations
+    }
+}
+
+type Run = Box<dyn FnMut(&mut Context<EffectState>)>;
+
+/// Effect 的实体，run 闭包的 cx 参数就是它的 Context，在闭包中读取的值成为依赖
+pub struct EffectState {
+    run: Run,
+    runs: usize,
+    _observe_self: Subscription,
+}
+
+impl EffectState {
+    fn run(&mut self, cx: &mut Context<Self>) {
+        (self.run)(cx);
+        self.runs += 1;
+    }
+}
+
+/// 副作用：依赖变化时运行，丢弃后停止
+///
+/// 和 Subscription 一样需要保存，通常作为视图的字段
+pub struct Effect {
+    state: Entity<EffectState>,
+}
+
+impl Effect {
+    /// 立即运行一次 run；run 中通过 cx 读取的 Signal / Memo 变化后再次运行
+    pub fn new(cx: &mut App, run: impl FnMut(&mut Context<EffectState>) + 'static) -> Self {
+        let state = cx.new(|cx| EffectState {
+            run: Box::new(run),
+            runs: 0,
+            _observe_self: cx.observe_self(EffectState::run),
+        });
+        state.update(cx, |state, cx| state.run(cx));
+        Self { state }
+    }
+
+    /// 到目前为止运行的次数
+    pub fn runs(&self, cx: &App) -> usize {
+        self.state.read(cx).runs
+    }
+}
@@ -0,0 +1,355 @@
+// 单一状态 Store
+// ==============
+// 状态分散在多个视图的字段里、在各个监听器中直接修改时，很难回答"状态为什么变成这样"。
+// Store 是一个实体，只持有一个状态结构体，修改只有一条路径：
+// 1. dispatch(msg) 把消息交给纯函数 reduce(&state, &msg) -> state，得到新状态
+// 2. 中间件在 reduce 前后被调用：拦截消息、写日志、持久化，而 reduce 本身保持纯函数
+// 3. 每次 dispatch 后发出 StateChanged；Store::select 只在选中的那部分状态变化时回调，
+//    视图把选中的值保存在自己的字段里渲染，无关的变化不会让它重新渲染
+//    （父视图重绘时子视图默认一起渲染，需要用 AnyView::cached 渲染子视图才能看出区别）
+//
+// ```ignore
+// #[derive(Clone, Default, PartialEq)]
+// struct CounterState { count: i32 }
+//
+// enum CounterMsg { Increment, Reset }
+//
+// fn reduce(state: &CounterState, msg: &CounterMsg) -> CounterState {
+//     match msg {
+//         CounterMsg::Increment => CounterState { count: state.count + 1 },
+//         CounterMsg::Reset => CounterState::default(),
+//     }
+// }
+//
+// let store = cx.new(|_| Store::new(CounterState::default(), reduce));
+//
+// // 视图中：只关心 count，返回当前值和订阅
+// let (count, subscription) = Store::select(&store, cx, |state| state.count, |view, count, cx| {
+//     view.count = *count;
+//     cx.notify();
+// });
+//
+// store.update(cx, |store, cx| store.dispatch(CounterMsg::Increment, cx));
+// ```
+
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::time::Duration;
+
+use gpui::*;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::LogPanel;
+
+/// 中间件：在 reduce 前后观察每条消息
+///
+/// 两个方法都有默认实现，只需要实现关心的那个
+pub trait Middleware<S, M>: 'static {
+    /// reduce 之前调用，返回 false 时丢弃这条消息（状态不变，也不发出 StateChanged）
+    fn before_dispatch(&mut self, _state: &S, _msg: &M) -> bool {
+        true
+    }
+
+    /// 状态已经更新之后调用，previous 是 reduce 之前的状态
+    fn after_dispatch(&mut self, _msg: &M, _previous: &S, _state: &S, _cx: &mut App) {}
+}
+
+/// Store 的状态被 dispatch 或 replace_state 修改后发出
+#[derive(Clone, Debug)]
+pub struct StateChanged;
+
+/// 持有单一状态的实体，状态只能通过 dispatch 修改
+pub struct Store<S, M> {
+    state: S,
+    reduce: fn(&S, &M) -> S,
+    /// 按添加顺序调用
+    middleware: Vec<Box<dyn Middleware<S, M>>>,
+}
+
+impl<S: 'static, M: 'static> EventEmitter<StateChanged> for Store<S, M> {}
+
+impl<S: 'static, M: 'static> Store<S, M> {
+    /// reduce 是普通函数而不是闭包：不能捕获外部状态，新状态只由旧状态和消息决定
+    pub fn new(initial: S, reduce: fn(&S, &M) -> S) -> Self {
+        Self {
+            state: initial,
+            reduce,
+            middleware: Vec::new(),
+        }
+    }
+
+    pub fn with_middleware(mut self, middleware: impl Middleware<S, M>) -> Self {
+        self.add_middleware(middleware);
+        self
+    }
+
+    /// 在 Store 创建之后添加中间件，例如根据命令行参数开启持久化
+    pub fn add_middleware(&mut self, middleware: impl Middleware<S, M>) {
+        self.middleware.push(Box::new(middleware));
+    }
+
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+
+    /// 修改状态的唯一入口：中间件 → reduce → 中间件 → StateChanged
+    pub fn dispatch(&mut self, msg: M, cx: &mut Context<Self>) {
+        for middleware in &mut self.middleware {
+            if !middleware.before_dispatch(&self.state, &msg) {
+                return;
+            }
+        }
+
+        let next = (self.reduce)(&self.state, &msg);
+        let previous = std::mem::replace(&mut self.state, next);
+        for middleware in &mut self.middleware {
+            middleware.after_dispatch(&msg, &previous, &self.state, cx);
+        }
+
+        cx.emit(StateChanged);
+        cx.notify();
+    }
+
+    /// 直接替换整个状态，不经过 reduce 和中间件
+    ///
+    /// 用于恢复快照（撤销、加载持久化的状态），不是普通的修改途径
+    pub fn replace_state(&mut self, state: S, cx: &mut Context<Self>) {
+        self.state = state;
+        cx.emit(StateChanged);
+        cx.notify();
+    }
+
+    /// 订阅状态中的一部分：selector 的结果变化时才调用 on_change
+    ///
+    /// 返回当前选中的值和订阅；和 cx.subscribe 一样需要保存 Subscription。
+    /// 视图应该渲染返回的值（保存在自己的字段中），而不是在 render 中读取 Store：
+    /// render 中读取过的实体每次 notify 都会让视图重新渲染
+    pub fn select<V: 'static, T: PartialEq + Clone + 'static>(
+        store: &Entity<Self>,
+        cx: &mut Context<V>,
+        selector: impl Fn(&S) -> T + 'static,
+        mut on_change: impl FnMut(&mut V, &T, &mut Context<V>) + 'static,
+    ) -> (T, Subscription) {
+        let current = selector(store.read(cx).state());
+        let mut last = current.clone();
+        let subscription = cx.subscribe(store, move |view, store, _: &StateChanged, cx| {
+            let selected = selector(store.read(cx).state());
+            if selected != last {
+                last = selected;
+                on_change(view, &last, cx);
+            }
+        });
+        (current, subscription)
+    }
+}
+
+type LogFormat<S, M> = Box<dyn Fn(&M, &S) -> String>;
+
+/// 日志中间件：每条消息处理完后向 LogPanel 写入一条日志
+///
+/// format 收到消息和处理后的状态，返回日志内容
+pub struct LogMiddleware<S, M> {
+    logs: Entity<LogPanel>,
+    source: SharedString,
+    format: LogFormat<S, M>,
+}
+
+impl<S, M> LogMiddleware<S, M> {
+    pub fn new(
+        logs: Entity<LogPanel>,
+        source: impl Into<SharedString>,
+        format: impl Fn(&M, &S) -> String + 'static,
+    ) -> Self {
+        Self {
+            logs,
+            source: source.into(),
+            format: Box::new(format),
+        }
+    }
+}
+
+impl<S: 'static, M: 'static> Middleware<S, M> for LogMiddleware<S, M> {
+    fn after_dispatch(&mut self, msg: &M, _previous: &S, state: &S, cx: &mut App) {
+        let message = (self.format)(msg, state);
+        let source = self.source.clone();
+        self.logs.update(cx, |logs, cx| logs.info(source, message, cx));
+    }
+}
+
+/// 状态变化后等待这么久没有新的变化才写盘，连续的修改只写一次
+const PERSIST_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// PersistMiddleware 写入 LogPanel 时使用的来源
+const PERSIST_SOURCE: &str = "PersistMiddleware";
+
+/// 持久化中间件：状态变化后以 JSON 写入文件，启动时用 load 读回
+///
+/// 状态在 UI 线程序列化成 JSON（一份拷贝），防抖之后在后台线程写盘；
+/// 写入失败记录到 LogPanel，不影响状态更新。应用退出时同步写入还没保存的最新状态。
+pub struct PersistMiddleware {
+    path: PathBuf,
+    logs: Entity<LogPanel>,
+    /// 等待写入的 JSON；写盘任务开始写入时取走，退出时还在这里的内容同步写入
+    pending: Rc<RefCell<Option<String>>>,
+    /// 替换时丢弃上一个任务，相当于重新开始计时
+    save_task: Option<Task<()>>,
+    _quit_subscription: Option<Subscription>,
+}
+
+impl PersistMiddleware {
+    pub fn new(path: impl Into<PathBuf>, logs: Entity<LogPanel>) -> Self {
+        Self {
+            path: path.into(),
+            logs,
+            pending: Rc::new(RefCell::new(None)),
+            save_task: None,
+            _quit_subscription: None,
+        }
+    }
+
+    /// 读取之前保存的状态；JSON 格式错误以 io::ErrorKind::InvalidData 返回
+    pub fn load<S: DeserializeOwned>(&self) -> std::io::Result<S> {
+        let source = std::fs::read_to_string(&self.path)?;
+        Ok(serde_json::from_str(&source)?)
+    }
+
+    fn report_error(logs: &Entity<LogPanel>, message: String, cx: &mut App) {
+        logs.update(cx, |logs, cx| logs.error(PERSIST_SOURCE, message, cx));
+    }
+
+    fn schedule_save(&mut self, json: String, cx: &mut App) {
+        *self.pending.borrow_mut() = Some(json);
+
+        // 第一次保存时注册：防抖期间退出应用也不会丢失最后的修改
+        if self._quit_subscription.is_none() {
+            let path = self.path.clone();
+            let pending = self.pending.clone();
+            self._quit_subscription = Some(cx.on_app_quit(move |_| {
+                // 界面已经关闭，写入失败也无处显示
+                if let Some(json) = pending.borrow_mut().take() {
+                    std::fs::write(&path, json).ok();
+                }
+                async {}
+            }));
+        }
+
+        let path = self.path.clone();
+        let pending = self.pending.clone();
+        let logs = self.logs.clone();
+        self.save_task = Some(cx.spawn(async move |cx| {
+            cx.background_executor().timer(PERSIST_DEBOUNCE).await;
+
+            let Some(json) = pending.borrow_mut().take() else {
+                return;
+            };
+            let result = cx
+                .background_executor()
+                .spawn({
+                    let path = path.clone();
+                    async move { std::fs::write(&path, json) }
+                })
+                .await;
+
+            if let Err(err) = result {
+                let message = format!("保存状态到 {} 失败: {}", path.display(), err);
+                cx.update(|cx| Self::report_error(&logs, message, cx)).ok();
+            }
+        }));
+    }
+}
+
+impl<S: Serialize + PartialEq + 'static, M: 'static> Middleware<S, M> for PersistMiddleware {
+    fn after_dispatch(&mut self, _msg: &M, previous: &S, state: &S, cx: &mut App) {
+        if previous == state {
+            return;
+        }
+        match serde_json::to_string_pretty(state) {
+            Ok(json) => self.schedule_save(json, cx),
+            Err(err) => {
+                let message = format!("无法序列化状态: {}", err);
+                Self::report_error(&self.logs, message, cx);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // 不能 use super::*：gpui::* 中的 test 宏会遮蔽内置的 #[test]
+    use super::{PersistMiddleware, Store, PERSIST_DEBOUNCE, PERSIST_SOURCE};
+    use crate::{LogLevel, LogPanel};
+    use gpui::{AppContext, Entity, TestAppContext};
+    use serde::{Deserialize, Serialize};
+    use std::path::{Path, PathBuf};
+
+    #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+    struct Counter {
+        count: i32,
+    }
+
+    fn add(state: &Counter, delta: &i32) -> Counter {
+        Counter {
+            count: state.count + delta,
+        }
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("gpui_components_store_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        std::fs::remove_file(&path).ok();
+        path
+    }
+
+    fn persisted_store(
+        path: &Path,
+        cx: &mut TestAppContext,
+    ) -> (Entity<Store<Counter, i32>>, Entity<LogPanel>) {
+        let logs = cx.new(|cx| LogPanel::new(10, cx));
+        let persist = PersistMiddleware::new(path, logs.clone());
+        let store = cx.new(|_| Store::new(Counter::default(), add).with_middleware(persist));
+        (store, logs)
+    }
+
+    #[gpui::test]
+    fn persisted_state_loads_back(cx: &mut TestAppContext) {
+        let path = temp_path("counter.json");
+        let (store, logs) = persisted_store(&path, cx);
+
+        store.update(cx, |store, cx| {
+            store.dispatch(1, cx);
+            store.dispatch(2, cx);
+        });
+        // 防抖期间还没有写盘
+        cx.run_until_parked();
+        assert!(!path.exists());
+
+        cx.executor().advance_clock(PERSIST_DEBOUNCE);
+        let loaded: Counter = PersistMiddleware::new(path.clone(), logs.clone())
+            .load()
+            .unwrap();
+        assert_eq!(loaded, Counter { count: 3 });
+        assert!(logs.read_with(cx, |logs, _| logs.last().is_none()));
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[gpui::test]
+    fn write_errors_are_logged(cx: &mut TestAppContext) {
+        // 目录不存在，写入失败
+        let path = temp_path("missing").join("counter.json");
+        let (store, logs) = persisted_store(&path, cx);
+
+        store.update(cx, |store, cx| store.dispatch(1, cx));
+        cx.executor().advance_clock(PERSIST_DEBOUNCE);
+
+        // 状态照常更新，错误出现在日志面板中
+        assert_eq!(store.read_with(cx, |store, _| store.state().count), 1);
+        logs.read_with(cx, |logs, _| {
+            let entry = logs.last().expect("写入失败没有记录");
+            assert_eq!(entry.level, LogLevel::Error);
+            assert_eq!(entry.source.as_ref(), PERSIST_SOURCE);
+        });
+    }
+}
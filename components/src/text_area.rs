@@ -0,0 +1,1665 @@
+// 多行文本编辑器
+// ==============
+// 在 TextInput 的基础上扩展到多行，供笔记应用、代码编辑器等项目使用：
+// 1. LineBuffer - 按行存储文本，编辑时只替换受影响的行
+// 2. 软换行 - 用 text_system().shape_text() 按可用宽度折行
+// 3. 可选的行号栏（gutter）
+// 4. overflow_y_scroll 垂直滚动，光标移动时自动滚动到可见区域
+// 5. 跨行选择、剪贴板、撤销/重做（连续输入会合并为一步）
+//
+// 与 TextInput 一样实现了 EntityInputHandler，支持中文输入法。
+// 使用前需要在启动时调用一次 text_area::init(cx) 注册快捷键。
+
+use std::ops::Range;
+use std::time::{Duration, Instant};
+
+use gpui::*;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::Theme;
+
+actions!(
+    text_area,
+    [
+        Backspace,
+        Delete,
+        Left,
+        Right,
+        Up,
+        Down,
+        WordLeft,
+        WordRight,
+        SelectLeft,
+        SelectRight,
+        SelectUp,
+        SelectDown,
+        SelectWordLeft,
+        SelectWordRight,
+        Home,
+        End,
+        SelectToHome,
+        SelectToEnd,
+        SelectAll,
+        Newline,
+        Tab,
+        Copy,
+        Cut,
+        Paste,
+        Undo,
+        Redo,
+    ]
+);
+
+/// 快捷键只在 key_context("TextArea") 内生效
+const KEY_CONTEXT: &str = "TextArea";
+
+/// 间隔小于这个时间的连续输入合并为一个撤销步骤
+const UNDO_GROUP_INTERVAL: Duration = Duration::from_millis(1000);
+
+/// Tab 键插入的空格
+const TAB_TEXT: &str = "    ";
+
+/// 注册多行编辑器的快捷键，在 Application::run 里调用一次
+pub fn init(cx: &mut App) {
+    let word = if cfg!(target_os = "macos") { "alt" } else { "ctrl" };
+
+    cx.bind_keys([
+        KeyBinding::new("backspace", Backspace, Some(KEY_CONTEXT)),
+        KeyBinding::new("delete", Delete, Some(KEY_CONTEXT)),
+        KeyBinding::new("left", Left, Some(KEY_CONTEXT)),
+        KeyBinding::new("right", Right, Some(KEY_CONTEXT)),
+        KeyBinding::new("up", Up, Some(KEY_CONTEXT)),
+        KeyBinding::new("down", Down, Some(KEY_CONTEXT)),
+        KeyBinding::new(&format!("{word}-left"), WordLeft, Some(KEY_CONTEXT)),
+        KeyBinding::new(&format!("{word}-right"), WordRight, Some(KEY_CONTEXT)),
+        KeyBinding::new("shift-left", SelectLeft, Some(KEY_CONTEXT)),
+        KeyBinding::new("shift-right", SelectRight, Some(KEY_CONTEXT)),
+        KeyBinding::new("shift-up", SelectUp, Some(KEY_CONTEXT)),
+        KeyBinding::new("shift-down", SelectDown, Some(KEY_CONTEXT)),
+        KeyBinding::new(&format!("shift-{word}-left"), SelectWordLeft, Some(KEY_CONTEXT)),
+        KeyBinding::new(&format!("shift-{word}-right"), SelectWordRight, Some(KEY_CONTEXT)),
+        KeyBinding::new("home", Home, Some(KEY_CONTEXT)),
+        KeyBinding::new("end", End, Some(KEY_CONTEXT)),
+        KeyBinding::new("shift-home", SelectToHome, Some(KEY_CONTEXT)),
+        KeyBinding::new("shift-end", SelectToEnd, Some(KEY_CONTEXT)),
+        KeyBinding::new("secondary-a", SelectAll, Some(KEY_CONTEXT)),
+        KeyBinding::new("enter", Newline, Some(KEY_CONTEXT)),
+        KeyBinding::new("tab", Tab, Some(KEY_CONTEXT)),
+        KeyBinding::new("secondary-c", Copy, Some(KEY_CONTEXT)),
+        KeyBinding::new("secondary-x", Cut, Some(KEY_CONTEXT)),
+        KeyBinding::new("secondary-v", Paste, Some(KEY_CONTEXT)),
+        KeyBinding::new("secondary-z", Undo, Some(KEY_CONTEXT)),
+        KeyBinding::new("secondary-shift-z", Redo, Some(KEY_CONTEXT)),
+        KeyBinding::new("ctrl-y", Redo, Some(KEY_CONTEXT)),
+    ]);
+}
+
+// ============================================================================
+// LineBuffer - 行缓冲区
+// ============================================================================
+
+/// 按行存储的文本，对外使用整篇文档的 UTF-8 字节偏移
+///
+/// 行之间隐含一个 '\n'，所以总长度 = 各行长度之和 + 行数 - 1。
+#[derive(Clone, Debug, PartialEq)]
+struct LineBuffer {
+    /// 至少有一行（空文档是一个空行）
+    lines: Vec<String>,
+}
+
+impl LineBuffer {
+    fn new(text: &str) -> Self {
+        Self {
+            lines: text.replace("\r\n", "\n").split('\n').map(String::from).collect(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.lines.iter().map(String::len).sum::<usize>() + self.lines.len() - 1
+    }
+
+    fn is_empty(&self) -> bool {
+        self.lines.len() == 1 && self.lines[0].is_empty()
+    }
+
+    fn line_count(&self) -> usize {
+        self.lines.len()
+    }
+
+    fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    /// 第 row 行的起始偏移
+    fn line_start(&self, row: usize) -> usize {
+        self.lines[..row].iter().map(|line| line.len() + 1).sum()
+    }
+
+    /// 偏移 -> (行, 行内偏移)
+    fn offset_to_point(&self, offset: usize) -> (usize, usize) {
+        let mut start = 0;
+        for (row, line) in self.lines.iter().enumerate() {
+            if offset <= start + line.len() {
+                return (row, offset - start);
+            }
+            start += line.len() + 1;
+        }
+        let last = self.lines.len() - 1;
+        (last, self.lines[last].len())
+    }
+
+    /// 取出 range 内的文字，只拼接受影响的行
+    fn slice(&self, range: Range<usize>) -> String {
+        let (start_row, start_col) = self.offset_to_point(range.start);
+        let (end_row, end_col) = self.offset_to_point(range.end);
+        if start_row == end_row {
+            return self.lines[start_row][start_col..end_col].to_string();
+        }
+
+        let mut text = self.lines[start_row][start_col..].to_string();
+        for line in &self.lines[start_row + 1..end_row] {
+            text.push('\n');
+            text.push_str(line);
+        }
+        text.push('\n');
+        text.push_str(&self.lines[end_row][..end_col]);
+        text
+    }
+
+    /// 用 new_text 替换 range，只重建受影响的行
+    fn replace(&mut self, range: Range<usize>, new_text: &str) {
+        let (start_row, start_col) = self.offset_to_point(range.start);
+        let (end_row, end_col) = self.offset_to_point(range.end);
+
+        let mut combined = self.lines[start_row][..start_col].to_string();
+        combined.push_str(&new_text.replace("\r\n", "\n"));
+        combined.push_str(&self.lines[end_row][end_col..]);
+
+        self.lines.splice(
+            start_row..=end_row,
+            combined.split('\n').map(String::from).collect::<Vec<_>>(),
+        );
+    }
+}
+
+// ============================================================================
+// 撤销历史
+// ============================================================================
+
+/// 一次编辑：把 start 处的 old_text 替换成了 new_text
+#[derive(Clone, Debug)]
+struct Edit {
+    start: usize,
+    old_text: String,
+    new_text: String,
+    selection_before: Range<usize>,
+    selection_after: Range<usize>,
+}
+
+impl Edit {
+    /// 尝试把紧接着的下一次编辑合并进来（连续输入、连续退格、输入法组合）
+    fn merge(&mut self, next: &Edit) -> bool {
+        let self_end = self.start + self.new_text.len();
+
+        // 在上次插入的末尾继续输入（换行作为分组边界）
+        if next.old_text.is_empty() && next.start == self_end && !next.new_text.contains('\n') {
+            self.new_text.push_str(&next.new_text);
+        }
+        // 输入法：用新的组合文字替换刚刚写入的组合文字
+        else if next.start == self.start && next.old_text == self.new_text {
+            self.new_text = next.new_text.clone();
+        }
+        // 连续退格
+        else if self.new_text.is_empty()
+            && next.new_text.is_empty()
+            && next.start + next.old_text.len() == self.start
+        {
+            self.old_text.insert_str(0, &next.old_text);
+            self.start = next.start;
+        } else {
+            return false;
+        }
+
+        self.selection_after = next.selection_after.clone();
+        true
+    }
+}
+
+// ============================================================================
+// TextArea
+// ============================================================================
+
+/// 编辑器发出的事件
+#[derive(Clone, Debug, PartialEq)]
+pub enum TextAreaEvent {
+    /// 内容发生变化（输入、删除、粘贴、撤销/重做）
+    Changed,
+}
+
+/// 多行文本编辑器
+///
+/// 根元素是 size_full()，应放在有固定高度的容器里，超出部分垂直滚动：
+///
+/// ```ignore
+/// let editor = cx.new(|cx| TextArea::new(cx).line_numbers(true));
+/// div().h(px(300.0)).child(editor.clone())
+/// ```
+pub struct TextArea {
+    focus_handle: FocusHandle,
+    buffer: LineBuffer,
+    placeholder: SharedString,
+    show_line_numbers: bool,
+    selected_range: Range<usize>,
+    selection_reversed: bool,
+    marked_range: Option<Range<usize>>,
+    /// 上下移动时保持的目标横坐标（连续按 ↑/↓ 时不会越走越偏）
+    preferred_x: Option<Pixels>,
+    undo_stack: Vec<Edit>,
+    redo_stack: Vec<Edit>,
+    last_edit_at: Option<Instant>,
+    scroll_handle: ScrollHandle,
+    /// 下一帧需要把光标滚动到可见区域
+    autoscroll: bool,
+    last_layout: Option<TextAreaLayout>,
+    is_selecting: bool,
+}
+
+/// 上一帧的排版结果，用于鼠标命中测试、上下移动和输入法定位
+struct TextAreaLayout {
+    lines: Vec<LaidOutLine>,
+    /// 文字区域左上角（窗口坐标，已包含滚动偏移）
+    text_origin: Point<Pixels>,
+    line_height: Pixels,
+}
+
+struct LaidOutLine {
+    /// 这一行在文档中的起始偏移
+    start: usize,
+    /// 相对 text_origin 的纵坐标
+    top: Pixels,
+    wrapped: WrappedLine,
+}
+
+impl LaidOutLine {
+    fn height(&self, line_height: Pixels) -> Pixels {
+        line_height * (self.wrapped.wrap_boundaries().len() + 1) as f32
+    }
+}
+
+impl TextAreaLayout {
+    /// 偏移 -> 相对 text_origin 的坐标（光标左上角）
+    fn position_for_offset(&self, offset: usize) -> Option<Point<Pixels>> {
+        let line = self
+            .lines
+            .iter()
+            .rev()
+            .find(|line| line.start <= offset)?;
+        let position = line
+            .wrapped
+            .position_for_index(offset - line.start, self.line_height)?;
+        Some(point(position.x, line.top + position.y))
+    }
+
+    /// 相对 text_origin 的坐标 -> 最近的偏移
+    fn offset_for_position(&self, position: Point<Pixels>) -> usize {
+        let Some(line) = self
+            .lines
+            .iter()
+            .find(|line| position.y < line.top + line.height(self.line_height))
+            .or(self.lines.last())
+        else {
+            return 0;
+        };
+        let local = point(position.x, (position.y - line.top).max(px(0.)));
+        let index = match line
+            .wrapped
+            .closest_index_for_position(local, self.line_height)
+        {
+            Ok(index) | Err(index) => index,
+        };
+        line.start + index.min(line.wrapped.len())
+    }
+}
+
+impl EventEmitter<TextAreaEvent> for TextArea {}
+
+impl Focusable for TextArea {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl TextArea {
+    pub fn new(cx: &mut Context<Self>) -> Self {
+        Self {
+            focus_handle: cx.focus_handle(),
+            buffer: LineBuffer::new(""),
+            placeholder: SharedString::default(),
+            show_line_numbers: false,
+            selected_range: 0..0,
+            selection_reversed: false,
+            marked_range: None,
+            preferred_x: None,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            last_edit_at: None,
+            scroll_handle: ScrollHandle::new(),
+            autoscroll: false,
+            last_layout: None,
+            is_selecting: false,
+        }
+    }
+
+    /// 内容为空时显示的提示文字
+    pub fn placeholder(mut self, placeholder: impl Into<SharedString>) -> Self {
+        self.placeholder = placeholder.into();
+        self
+    }
+
+    /// 是否显示行号栏
+    pub fn line_numbers(mut self, show: bool) -> Self {
+        self.show_line_numbers = show;
+        self
+    }
+
+    pub fn set_line_numbers(&mut self, show: bool, cx: &mut Context<Self>) {
+        self.show_line_numbers = show;
+        cx.notify();
+    }
+
+    pub fn shows_line_numbers(&self) -> bool {
+        self.show_line_numbers
+    }
+
+    pub fn text(&self) -> String {
+        self.buffer.text()
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.buffer.line_count()
+    }
+
+    /// 替换全部内容并清空撤销历史（例如打开另一篇笔记）
+    pub fn set_text(&mut self, text: &str, cx: &mut Context<Self>) {
+        self.buffer = LineBuffer::new(text);
+        self.selected_range = 0..0;
+        self.selection_reversed = false;
+        self.marked_range = None;
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.last_edit_at = None;
+        cx.emit(TextAreaEvent::Changed);
+        cx.notify();
+    }
+
+    pub fn focus(&self, window: &mut Window) {
+        self.focus_handle.focus(window);
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    // ------------------------------------------------------------------------
+    // 编辑与撤销
+    // ------------------------------------------------------------------------
+
+    /// 所有修改都经过这里：替换文本、记录撤销步骤、通知订阅者
+    fn edit(&mut self, range: Range<usize>, new_text: &str, cx: &mut Context<Self>) {
+        let new_text = new_text.replace("\r\n", "\n");
+        let edit = Edit {
+            start: range.start,
+            old_text: self.buffer.slice(range.clone()),
+            new_text: new_text.clone(),
+            selection_before: self.selected_range.clone(),
+            selection_after: range.start + new_text.len()..range.start + new_text.len(),
+        };
+        self.buffer.replace(range, &new_text);
+        self.selected_range = edit.selection_after.clone();
+        self.selection_reversed = false;
+        self.marked_range = None;
+
+        let now = Instant::now();
+        let recent = self
+            .last_edit_at
+            .is_some_and(|at| now.duration_since(at) < UNDO_GROUP_INTERVAL);
+        let merged = recent
+            && self
+                .undo_stack
+                .last_mut()
+                .is_some_and(|last| last.merge(&edit));
+        if !merged {
+            self.undo_stack.push(edit);
+        }
+        self.redo_stack.clear();
+        self.last_edit_at = Some(now);
+
+        self.changed(cx);
+    }
+
+    fn changed(&mut self, cx: &mut Context<Self>) {
+        self.preferred_x = None;
+        self.autoscroll = true;
+        cx.emit(TextAreaEvent::Changed);
+        cx.notify();
+    }
+
+    fn undo(&mut self, _: &Undo, _: &mut Window, cx: &mut Context<Self>) {
+        let Some(edit) = self.undo_stack.pop() else {
+            return;
+        };
+        self.buffer
+            .replace(edit.start..edit.start + edit.new_text.len(), &edit.old_text);
+        self.selected_range = edit.selection_before.clone();
+        self.selection_reversed = false;
+        self.marked_range = None;
+        self.redo_stack.push(edit);
+        // 撤销之后的输入重新开始一个分组
+        self.last_edit_at = None;
+        self.changed(cx);
+    }
+
+    fn redo(&mut self, _: &Redo, _: &mut Window, cx: &mut Context<Self>) {
+        let Some(edit) = self.redo_stack.pop() else {
+            return;
+        };
+        self.buffer
+            .replace(edit.start..edit.start + edit.old_text.len(), &edit.new_text);
+        self.selected_range = edit.selection_after.clone();
+        self.selection_reversed = false;
+        self.marked_range = None;
+        self.undo_stack.push(edit);
+        self.last_edit_at = None;
+        self.changed(cx);
+    }
+
+    // ------------------------------------------------------------------------
+    // Action 处理器
+    // ------------------------------------------------------------------------
+
+    fn left(&mut self, _: &Left, _: &mut Window, cx: &mut Context<Self>) {
+        if self.selected_range.is_empty() {
+            self.move_to(self.previous_boundary(self.cursor_offset()), cx);
+        } else {
+            self.move_to(self.selected_range.start, cx)
+        }
+    }
+
+    fn right(&mut self, _: &Right, _: &mut Window, cx: &mut Context<Self>) {
+        if self.selected_range.is_empty() {
+            self.move_to(self.next_boundary(self.cursor_offset()), cx);
+        } else {
+            self.move_to(self.selected_range.end, cx)
+        }
+    }
+
+    fn up(&mut self, _: &Up, _: &mut Window, cx: &mut Context<Self>) {
+        let offset = self.vertical_offset(-1);
+        self.move_vertically_to(offset, cx);
+    }
+
+    fn down(&mut self, _: &Down, _: &mut Window, cx: &mut Context<Self>) {
+        let offset = self.vertical_offset(1);
+        self.move_vertically_to(offset, cx);
+    }
+
+    fn word_left(&mut self, _: &WordLeft, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_to(self.previous_word_boundary(self.cursor_offset()), cx);
+    }
+
+    fn word_right(&mut self, _: &WordRight, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_to(self.next_word_boundary(self.cursor_offset()), cx);
+    }
+
+    fn select_left(&mut self, _: &SelectLeft, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_to(self.previous_boundary(self.cursor_offset()), cx);
+    }
+
+    fn select_right(&mut self, _: &SelectRight, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_to(self.next_boundary(self.cursor_offset()), cx);
+    }
+
+    fn select_up(&mut self, _: &SelectUp, _: &mut Window, cx: &mut Context<Self>) {
+        let offset = self.vertical_offset(-1);
+        let preferred_x = self.preferred_x;
+        self.select_to(offset, cx);
+        self.preferred_x = preferred_x;
+    }
+
+    fn select_down(&mut self, _: &SelectDown, _: &mut Window, cx: &mut Context<Self>) {
+        let offset = self.vertical_offset(1);
+        let preferred_x = self.preferred_x;
+        self.select_to(offset, cx);
+        self.preferred_x = preferred_x;
+    }
+
+    fn select_word_left(&mut self, _: &SelectWordLeft, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_to(self.previous_word_boundary(self.cursor_offset()), cx);
+    }
+
+    fn select_word_right(&mut self, _: &SelectWordRight, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_to(self.next_word_boundary(self.cursor_offset()), cx);
+    }
+
+    fn home(&mut self, _: &Home, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_to(self.line_start_of(self.cursor_offset()), cx);
+    }
+
+    fn end(&mut self, _: &End, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_to(self.line_end_of(self.cursor_offset()), cx);
+    }
+
+    fn select_to_home(&mut self, _: &SelectToHome, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_to(self.line_start_of(self.cursor_offset()), cx);
+    }
+
+    fn select_to_end(&mut self, _: &SelectToEnd, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_to(self.line_end_of(self.cursor_offset()), cx);
+    }
+
+    fn select_all(&mut self, _: &SelectAll, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_to(0, cx);
+        self.select_to(self.buffer.len(), cx)
+    }
+
+    fn backspace(&mut self, _: &Backspace, _: &mut Window, cx: &mut Context<Self>) {
+        if self.selected_range.is_empty() {
+            self.select_to(self.previous_boundary(self.cursor_offset()), cx)
+        }
+        self.edit(self.selected_range.clone(), "", cx)
+    }
+
+    fn delete(&mut self, _: &Delete, _: &mut Window, cx: &mut Context<Self>) {
+        if self.selected_range.is_empty() {
+            self.select_to(self.next_boundary(self.cursor_offset()), cx)
+        }
+        self.edit(self.selected_range.clone(), "", cx)
+    }
+
+    fn newline(&mut self, _: &Newline, _: &mut Window, cx: &mut Context<Self>) {
+        self.edit(self.selected_range.clone(), "\n", cx);
+    }
+
+    fn tab(&mut self, _: &Tab, _: &mut Window, cx: &mut Context<Self>) {
+        self.edit(self.selected_range.clone(), TAB_TEXT, cx);
+    }
+
+    fn copy(&mut self, _: &Copy, _: &mut Window, cx: &mut Context<Self>) {
+        if !self.selected_range.is_empty() {
+            cx.write_to_clipboard(ClipboardItem::new_string(
+                self.buffer.slice(self.selected_range.clone()),
+            ));
+        }
+    }
+
+    fn cut(&mut self, _: &Cut, _: &mut Window, cx: &mut Context<Self>) {
+        if !self.selected_range.is_empty() {
+            cx.write_to_clipboard(ClipboardItem::new_string(
+                self.buffer.slice(self.selected_range.clone()),
+            ));
+            self.edit(self.selected_range.clone(), "", cx)
+        }
+    }
+
+    fn paste(&mut self, _: &Paste, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(text) = cx.read_from_clipboard().and_then(|item| item.text()) {
+            self.edit(self.selected_range.clone(), &text, cx);
+            // 粘贴单独成为一个撤销步骤
+            self.last_edit_at = None;
+        }
+    }
+
+    // ------------------------------------------------------------------------
+    // 鼠标选择
+    // ------------------------------------------------------------------------
+
+    fn on_mouse_down(&mut self, event: &MouseDownEvent, window: &mut Window, cx: &mut Context<Self>) {
+        self.is_selecting = true;
+        self.focus_handle.focus(window);
+
+        let offset = self.index_for_mouse_position(event.position);
+        if event.modifiers.shift {
+            self.select_to(offset, cx);
+        } else {
+            self.move_to(offset, cx)
+        }
+    }
+
+    fn on_mouse_up(&mut self, _: &MouseUpEvent, _window: &mut Window, _: &mut Context<Self>) {
+        self.is_selecting = false;
+    }
+
+    fn on_mouse_move(&mut self, event: &MouseMoveEvent, _: &mut Window, cx: &mut Context<Self>) {
+        if self.is_selecting {
+            self.select_to(self.index_for_mouse_position(event.position), cx);
+        }
+    }
+
+    fn index_for_mouse_position(&self, position: Point<Pixels>) -> usize {
+        // 内容为空时 last_layout 排版的是占位文字，其中的偏移在文档里不存在
+        if self.buffer.is_empty() {
+            return 0;
+        }
+
+        match &self.last_layout {
+            Some(layout) => layout
+                .offset_for_position(position - layout.text_origin)
+                .min(self.buffer.len()),
+            None => 0,
+        }
+    }
+
+    // ------------------------------------------------------------------------
+    // 光标与选区
+    // ------------------------------------------------------------------------
+
+    fn move_to(&mut self, offset: usize, cx: &mut Context<Self>) {
+        let offset = offset.min(self.buffer.len());
+        self.selected_range = offset..offset;
+        self.selection_reversed = false;
+        self.preferred_x = None;
+        self.autoscroll = true;
+        cx.notify()
+    }
+
+    /// 上下移动：保留 preferred_x
+    fn move_vertically_to(&mut self, offset: usize, cx: &mut Context<Self>) {
+        let preferred_x = self.preferred_x;
+        self.move_to(offset, cx);
+        self.preferred_x = preferred_x;
+    }
+
+    fn cursor_offset(&self) -> usize {
+        if self.selection_reversed {
+            self.selected_range.start
+        } else {
+            self.selected_range.end
+        }
+    }
+
+    fn select_to(&mut self, offset: usize, cx: &mut Context<Self>) {
+        let offset = offset.min(self.buffer.len());
+        if self.selection_reversed {
+            self.selected_range.start = offset
+        } else {
+            self.selected_range.end = offset
+        };
+        if self.selected_range.end < self.selected_range.start {
+            self.selection_reversed = !self.selection_reversed;
+            self.selected_range = self.selected_range.end..self.selected_range.start;
+        }
+        self.preferred_x = None;
+        self.autoscroll = true;
+        cx.notify()
+    }
+
+    /// 光标在视觉上移动 direction 行（-1 向上，1 向下）后的位置，考虑软换行
+    fn vertical_offset(&mut self, direction: i32) -> usize {
+        // 与 index_for_mouse_position 一样：占位文字的排版不能用来定位
+        if self.buffer.is_empty() {
+            return 0;
+        }
+
+        let cursor = self.cursor_offset();
+        let Some(layout) = &self.last_layout else {
+            return cursor;
+        };
+        let Some(position) = layout.position_for_offset(cursor) else {
+            return cursor;
+        };
+        let x = *self.preferred_x.get_or_insert(position.x);
+        let y = position.y + layout.line_height * (direction as f32 + 0.5);
+        if y < px(0.) {
+            return 0;
+        }
+        let last = layout.lines.last();
+        if last.is_some_and(|line| y >= line.top + line.height(layout.line_height)) {
+            return self.buffer.len();
+        }
+        layout.offset_for_position(point(x, y)).min(self.buffer.len())
+    }
+
+    fn line_start_of(&self, offset: usize) -> usize {
+        let (row, _) = self.buffer.offset_to_point(offset);
+        self.buffer.line_start(row)
+    }
+
+    fn line_end_of(&self, offset: usize) -> usize {
+        let (row, _) = self.buffer.offset_to_point(offset);
+        self.buffer.line_start(row) + self.buffer.lines[row].len()
+    }
+
+    // 以下几个函数先用 offset_to_point 定位到行，只在这一行里查找，
+    // 不为一次光标移动重建整篇文档
+
+    fn previous_boundary(&self, offset: usize) -> usize {
+        let (row, col) = self.buffer.offset_to_point(offset);
+        if col == 0 {
+            // 行首：退到上一行末尾（跨过换行符）
+            return if row == 0 { 0 } else { self.line_start_of(offset) - 1 };
+        }
+        let line = &self.buffer.lines[row];
+        let idx = line
+            .grapheme_indices(true)
+            .rev()
+            .find_map(|(idx, _)| (idx < col).then_some(idx))
+            .unwrap_or(0);
+        self.buffer.line_start(row) + idx
+    }
+
+    fn next_boundary(&self, offset: usize) -> usize {
+        let (row, col) = self.buffer.offset_to_point(offset);
+        let line = &self.buffer.lines[row];
+        if col >= line.len() {
+            // 行尾：进到下一行行首（跨过换行符）
+            return if row + 1 < self.buffer.line_count() {
+                self.buffer.line_start(row + 1)
+            } else {
+                self.buffer.len()
+            };
+        }
+        let idx = line
+            .grapheme_indices(true)
+            .find_map(|(idx, _)| (idx > col).then_some(idx))
+            .unwrap_or(line.len());
+        self.buffer.line_start(row) + idx
+    }
+
+    /// 向左停在词的开头；换行和空白一样被跳过，这一行没有词时继续找上一行
+    fn previous_word_boundary(&self, offset: usize) -> usize {
+        let (mut row, mut col) = self.buffer.offset_to_point(offset);
+        loop {
+            let line = &self.buffer.lines[row];
+            if let Some((idx, _)) = line[..col]
+                .split_word_bound_indices()
+                .rev()
+                .find(|(_, word)| !word.chars().all(char::is_whitespace))
+            {
+                return self.buffer.line_start(row) + idx;
+            }
+            if row == 0 {
+                return 0;
+            }
+            row -= 1;
+            col = self.buffer.lines[row].len();
+        }
+    }
+
+    /// 向右停在词的末尾；这一行后面没有词时继续找下一行
+    fn next_word_boundary(&self, offset: usize) -> usize {
+        let (mut row, mut col) = self.buffer.offset_to_point(offset);
+        loop {
+            let line = &self.buffer.lines[row];
+            if let Some((idx, word)) = line[col..]
+                .split_word_bound_indices()
+                .find(|(_, word)| !word.chars().all(char::is_whitespace))
+            {
+                return self.buffer.line_start(row) + col + idx + word.len();
+            }
+            row += 1;
+            if row == self.buffer.line_count() {
+                return self.buffer.len();
+            }
+            col = 0;
+        }
+    }
+
+    // ------------------------------------------------------------------------
+    // UTF-8 <-> UTF-16 偏移转换（平台输入法使用 UTF-16）
+    // ------------------------------------------------------------------------
+
+    fn offset_from_utf16(&self, offset: usize) -> usize {
+        let mut line_start = 0;
+        let mut remaining = offset;
+
+        for line in &self.buffer.lines {
+            let line_utf16 = line.encode_utf16().count();
+            if remaining <= line_utf16 {
+                return line_start + utf16_to_utf8(line, remaining);
+            }
+            // 跳过这一行和行尾的换行符（1 个 UTF-16 单元）
+            remaining -= line_utf16 + 1;
+            line_start += line.len() + 1;
+        }
+
+        self.buffer.len()
+    }
+
+    fn offset_to_utf16(&self, offset: usize) -> usize {
+        let (row, col) = self.buffer.offset_to_point(offset);
+        let lines_before: usize = self.buffer.lines[..row]
+            .iter()
+            .map(|line| line.encode_utf16().count() + 1)
+            .sum();
+
+        let mut utf16_offset = 0;
+        let mut utf8_count = 0;
+        for ch in self.buffer.lines[row].chars() {
+            if utf8_count >= col {
+                break;
+            }
+            utf8_count += ch.len_utf8();
+            utf16_offset += ch.len_utf16();
+        }
+
+        lines_before + utf16_offset
+    }
+
+    fn range_to_utf16(&self, range: &Range<usize>) -> Range<usize> {
+        self.offset_to_utf16(range.start)..self.offset_to_utf16(range.end)
+    }
+
+    fn range_from_utf16(&self, range_utf16: &Range<usize>) -> Range<usize> {
+        self.offset_from_utf16(range_utf16.start)..self.offset_from_utf16(range_utf16.end)
+    }
+}
+
+impl EntityInputHandler for TextArea {
+    fn text_for_range(
+        &mut self,
+        range_utf16: Range<usize>,
+        actual_range: &mut Option<Range<usize>>,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) -> Option<String> {
+        let range = self.range_from_utf16(&range_utf16);
+        actual_range.replace(self.range_to_utf16(&range));
+        Some(self.buffer.slice(range))
+    }
+
+    fn selected_text_range(
+        &mut self,
+        _ignore_disabled_input: bool,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) -> Option<UTF16Selection> {
+        Some(UTF16Selection {
+            range: self.range_to_utf16(&self.selected_range),
+            reversed: self.selection_reversed,
+        })
+    }
+
+    fn marked_text_range(&self, _window: &mut Window, _cx: &mut Context<Self>) -> Option<Range<usize>> {
+        self.marked_range
+            .as_ref()
+            .map(|range| self.range_to_utf16(range))
+    }
+
+    fn unmark_text(&mut self, _window: &mut Window, _cx: &mut Context<Self>) {
+        self.marked_range = None;
+    }
+
+    fn replace_text_in_range(
+        &mut self,
+        range_utf16: Option<Range<usize>>,
+        new_text: &str,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let range = range_utf16
+            .as_ref()
+            .map(|range_utf16| self.range_from_utf16(range_utf16))
+            .or(self.marked_range.clone())
+            .unwrap_or(self.selected_range.clone());
+
+        self.edit(range, new_text, cx);
+    }
+
+    fn replace_and_mark_text_in_range(
+        &mut self,
+        range_utf16: Option<Range<usize>>,
+        new_text: &str,
+        new_selected_range_utf16: Option<Range<usize>>,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let range = range_utf16
+            .as_ref()
+            .map(|range_utf16| self.range_from_utf16(range_utf16))
+            .or(self.marked_range.clone())
+            .unwrap_or(self.selected_range.clone());
+
+        self.edit(range.clone(), new_text, cx);
+
+        if !new_text.is_empty() {
+            self.marked_range = Some(range.start..range.start + new_text.len());
+        }
+        if let Some(new_range) = new_selected_range_utf16 {
+            let start = range.start + utf16_to_utf8(new_text, new_range.start);
+            let end = range.start + utf16_to_utf8(new_text, new_range.end);
+            self.selected_range = start..end;
+        }
+    }
+
+    fn bounds_for_range(
+        &mut self,
+        range_utf16: Range<usize>,
+        _bounds: Bounds<Pixels>,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) -> Option<Bounds<Pixels>> {
+        let layout = self.last_layout.as_ref()?;
+        let range = self.range_from_utf16(&range_utf16);
+        let start = layout.position_for_offset(range.start)?;
+        let end = layout.position_for_offset(range.end)?;
+        Some(Bounds::from_corners(
+            layout.text_origin + start,
+            layout.text_origin + point(end.x, end.y + layout.line_height),
+        ))
+    }
+
+    fn character_index_for_point(
+        &mut self,
+        point: Point<Pixels>,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) -> Option<usize> {
+        if self.buffer.is_empty() {
+            return Some(0);
+        }
+        let layout = self.last_layout.as_ref()?;
+        let offset = layout.offset_for_position(point - layout.text_origin);
+        Some(self.offset_to_utf16(offset.min(self.buffer.len())))
+    }
+}
+
+/// 把字符串内的 UTF-16 偏移转换为 UTF-8 偏移
+fn utf16_to_utf8(text: &str, offset_utf16: usize) -> usize {
+    let mut utf16_count = 0;
+    for (ix, ch) in text.char_indices() {
+        if utf16_count >= offset_utf16 {
+            return ix;
+        }
+        utf16_count += ch.len_utf16();
+    }
+    text.len()
+}
+
+// ============================================================================
+// TextAreaElement - 排版、绘制，并注册输入法处理器
+// ============================================================================
+
+struct TextAreaElement {
+    input: Entity<TextArea>,
+}
+
+/// 行号栏与文字之间的间距
+const GUTTER_PADDING: Pixels = px(12.);
+
+struct PrepaintState {
+    lines: Vec<LaidOutLine>,
+    line_numbers: Vec<(ShapedLine, Point<Pixels>)>,
+    gutter_width: Pixels,
+    selections: Vec<PaintQuad>,
+    cursor: Option<PaintQuad>,
+    text_origin: Point<Pixels>,
+}
+
+impl IntoElement for TextAreaElement {
+    type Element = Self;
+
+    fn into_element(self) -> Self::Element {
+        self
+    }
+}
+
+/// 把文本按行排版，返回每行的排版结果和总高度
+fn shape_lines(
+    text: &SharedString,
+    runs: &[TextRun],
+    font_size: Pixels,
+    line_height: Pixels,
+    wrap_width: Option<Pixels>,
+    window: &Window,
+) -> (Vec<LaidOutLine>, Pixels) {
+    let wrapped = window
+        .text_system()
+        .shape_text(text.clone(), font_size, runs, wrap_width, None)
+        .unwrap_or_default();
+
+    let mut lines = Vec::with_capacity(wrapped.len());
+    let mut start = 0;
+    let mut top = px(0.);
+    for wrapped in wrapped {
+        let line = LaidOutLine {
+            start,
+            top,
+            wrapped,
+        };
+        start += line.wrapped.len() + 1;
+        top += line.height(line_height);
+        lines.push(line);
+    }
+    (lines, top)
+}
+
+/// 行号栏宽度：按最大行号的位数计算
+fn gutter_width(line_count: usize, font: &Font, font_size: Pixels, window: &Window) -> Pixels {
+    let digits = line_count.max(1).to_string().len().max(2);
+    let sample: SharedString = "8".repeat(digits).into();
+    let run = TextRun {
+        len: sample.len(),
+        font: font.clone(),
+        color: Hsla::default(),
+        background_color: None,
+        underline: None,
+        strikethrough: None,
+    };
+    window
+        .text_system()
+        .shape_line(sample, font_size, &[run], None)
+        .width
+        + GUTTER_PADDING
+}
+
+impl Element for TextAreaElement {
+    type RequestLayoutState = ();
+    type PrepaintState = PrepaintState;
+
+    fn id(&self) -> Option<ElementId> {
+        None
+    }
+
+    fn source_location(&self) -> Option<&'static core::panic::Location<'static>> {
+        None
+    }
+
+    /// 高度取决于可用宽度（软换行），所以使用 request_measured_layout
+    fn request_layout(
+        &mut self,
+        _id: Option<&GlobalElementId>,
+        _inspector_id: Option<&InspectorElementId>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> (LayoutId, Self::RequestLayoutState) {
+        let input = self.input.read(cx);
+        let text: SharedString = input.buffer.text().into();
+        let line_count = input.buffer.line_count();
+        let show_line_numbers = input.show_line_numbers;
+
+        let style = window.text_style();
+        let font = style.font();
+        let font_size = style.font_size.to_pixels(window.rem_size());
+        let line_height = window.line_height();
+
+        let mut layout_style = Style::default();
+        layout_style.size.width = relative(1.).into();
+
+        let layout_id = window.request_measured_layout(
+            layout_style,
+            move |known, available, window, _cx| {
+                let width = known.width.or(match available.width {
+                    AvailableSpace::Definite(width) => Some(width),
+                    _ => None,
+                });
+                let gutter = if show_line_numbers {
+                    gutter_width(line_count, &font, font_size, window)
+                } else {
+                    px(0.)
+                };
+                let run = TextRun {
+                    len: text.len(),
+                    font: font.clone(),
+                    color: Hsla::default(),
+                    background_color: None,
+                    underline: None,
+                    strikethrough: None,
+                };
+                let (_, height) = shape_lines(
+                    &text,
+                    &[run],
+                    font_size,
+                    line_height,
+                    width.map(|width| width - gutter),
+                    window,
+                );
+                size(width.unwrap_or(px(0.)), height.max(line_height))
+            },
+        );
+        (layout_id, ())
+    }
+
+    fn prepaint(
+        &mut self,
+        _id: Option<&GlobalElementId>,
+        _inspector_id: Option<&InspectorElementId>,
+        bounds: Bounds<Pixels>,
+        _request_layout: &mut Self::RequestLayoutState,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Self::PrepaintState {
+        let (subtext, accent) = match cx.try_global::<Theme>() {
+            Some(theme) => (theme.subtext, theme.accent),
+            None => (rgb(0x9CA3AF).into(), rgb(0x3B82F6).into()),
+        };
+
+        let input = self.input.read(cx);
+        let style = window.text_style();
+        let font = style.font();
+        let font_size = style.font_size.to_pixels(window.rem_size());
+        let line_height = window.line_height();
+
+        let gutter_width = if input.show_line_numbers {
+            gutter_width(input.buffer.line_count(), &font, font_size, window)
+        } else {
+            px(0.)
+        };
+        let text_origin = point(bounds.left() + gutter_width, bounds.top());
+        let wrap_width = bounds.size.width - gutter_width;
+
+        // 内容为空时显示占位文字
+        let is_empty = input.buffer.is_empty();
+        let (display_text, text_color): (SharedString, Hsla) = if is_empty {
+            (input.placeholder.clone(), subtext)
+        } else {
+            (input.buffer.text().into(), style.color)
+        };
+
+        let run = TextRun {
+            len: display_text.len(),
+            font: font.clone(),
+            color: text_color,
+            background_color: None,
+            underline: None,
+            strikethrough: None,
+        };
+        // 输入法组合中的文字加下划线
+        let runs: Vec<TextRun> = match input.marked_range.as_ref() {
+            Some(marked) if !is_empty => [
+                TextRun {
+                    len: marked.start,
+                    ..run.clone()
+                },
+                TextRun {
+                    len: marked.end - marked.start,
+                    underline: Some(UnderlineStyle {
+                        color: Some(run.color),
+                        thickness: px(1.0),
+                        wavy: false,
+                    }),
+                    ..run.clone()
+                },
+                TextRun {
+                    len: display_text.len() - marked.end,
+                    ..run
+                },
+            ]
+            .into_iter()
+            .filter(|run| run.len > 0)
+            .collect(),
+            _ => vec![run],
+        };
+
+        let (lines, _) = shape_lines(
+            &display_text,
+            &runs,
+            font_size,
+            line_height,
+            Some(wrap_width),
+            window,
+        );
+        let layout = TextAreaLayout {
+            lines,
+            text_origin,
+            line_height,
+        };
+
+        // 行号：只在每个硬换行的第一行显示
+        let line_numbers = if input.show_line_numbers {
+            layout
+                .lines
+                .iter()
+                .enumerate()
+                .map(|(row, line)| {
+                    let label: SharedString = (row + 1).to_string().into();
+                    let run = TextRun {
+                        len: label.len(),
+                        font: font.clone(),
+                        color: subtext,
+                        background_color: None,
+                        underline: None,
+                        strikethrough: None,
+                    };
+                    let shaped = window
+                        .text_system()
+                        .shape_line(label, font_size, &[run], None);
+                    let origin = point(
+                        text_origin.x - GUTTER_PADDING - shaped.width,
+                        text_origin.y + line.top,
+                    );
+                    (shaped, origin)
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        // 选区：可能跨越多行，每个视觉行画一个矩形
+        let mut selections = Vec::new();
+        let selected = input.selected_range.clone();
+        if !selected.is_empty() && !is_empty {
+            for line in &layout.lines {
+                let line_end = line.start + line.wrapped.len();
+                if selected.end < line.start || selected.start > line_end {
+                    continue;
+                }
+                let start = selected.start.max(line.start) - line.start;
+                let end = selected.end.min(line_end) - line.start;
+                let (Some(p1), Some(p2)) = (
+                    line.wrapped.position_for_index(start, line_height),
+                    line.wrapped.position_for_index(end, line_height),
+                ) else {
+                    continue;
+                };
+                // 选区包含换行符时，在行尾多画一小段表示
+                let newline_width = if selected.end > line_end { px(6.) } else { px(0.) };
+
+                let mut row_top = p1.y;
+                while row_top <= p2.y {
+                    let left = if row_top == p1.y { p1.x } else { px(0.) };
+                    let right = if row_top == p2.y {
+                        p2.x + newline_width
+                    } else {
+                        wrap_width
+                    };
+                    selections.push(fill(
+                        Bounds::from_corners(
+                            text_origin + point(left, line.top + row_top),
+                            text_origin + point(right, line.top + row_top + line_height),
+                        ),
+                        accent.opacity(0.25),
+                    ));
+                    row_top += line_height;
+                }
+            }
+        }
+
+        let cursor_position = if is_empty {
+            Some(point(px(0.), px(0.)))
+        } else {
+            layout.position_for_offset(input.cursor_offset())
+        };
+        let cursor = cursor_position.map(|position| {
+            fill(
+                Bounds::new(text_origin + position, size(px(2.), line_height)),
+                accent,
+            )
+        });
+
+        // 自动滚动：光标移出可见区域时调整外层 div 的滚动偏移
+        if input.autoscroll
+            && let Some(position) = cursor_position
+        {
+            let viewport = input.scroll_handle.bounds();
+            if viewport.size.height > px(0.) {
+                let cursor_top = text_origin.y + position.y;
+                let cursor_bottom = cursor_top + line_height;
+                let mut offset = input.scroll_handle.offset();
+                if cursor_top < viewport.top() {
+                    offset.y += viewport.top() - cursor_top;
+                } else if cursor_bottom > viewport.bottom() {
+                    offset.y -= cursor_bottom - viewport.bottom();
+                }
+                if offset != input.scroll_handle.offset() {
+                    input.scroll_handle.set_offset(offset);
+                    window.request_animation_frame();
+                }
+            }
+        }
+
+        PrepaintState {
+            lines: layout.lines,
+            line_numbers,
+            gutter_width,
+            selections,
+            cursor,
+            text_origin,
+        }
+    }
+
+    fn paint(
+        &mut self,
+        _id: Option<&GlobalElementId>,
+        _inspector_id: Option<&InspectorElementId>,
+        bounds: Bounds<Pixels>,
+        _request_layout: &mut Self::RequestLayoutState,
+        prepaint: &mut Self::PrepaintState,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let focus_handle = self.input.read(cx).focus_handle.clone();
+        window.handle_input(
+            &focus_handle,
+            ElementInputHandler::new(bounds, self.input.clone()),
+            cx,
+        );
+
+        let line_height = window.line_height();
+        let text_bounds = Bounds::from_corners(
+            point(bounds.left() + prepaint.gutter_width, bounds.top()),
+            bounds.bottom_right(),
+        );
+
+        for selection in prepaint.selections.drain(..) {
+            window.paint_quad(selection);
+        }
+        for line in &prepaint.lines {
+            line.wrapped
+                .paint(
+                    prepaint.text_origin + point(px(0.), line.top),
+                    line_height,
+                    TextAlign::Left,
+                    Some(text_bounds),
+                    window,
+                    cx,
+                )
+                .ok();
+        }
+        for (number, origin) in &prepaint.line_numbers {
+            number.paint(*origin, line_height, window, cx).ok();
+        }
+        if focus_handle.is_focused(window)
+            && let Some(cursor) = prepaint.cursor.take()
+        {
+            window.paint_quad(cursor);
+        }
+
+        let lines = std::mem::take(&mut prepaint.lines);
+        let text_origin = prepaint.text_origin;
+        self.input.update(cx, |input, _cx| {
+            input.autoscroll = false;
+            input.last_layout = Some(TextAreaLayout {
+                lines,
+                text_origin,
+                line_height,
+            });
+        });
+    }
+}
+
+impl Render for TextArea {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = cx.try_global::<Theme>().cloned().unwrap_or_else(Theme::light);
+        let is_focused = self.focus_handle.is_focused(window);
+
+        div()
+            .id(("text-area", cx.entity_id()))
+            .key_context(KEY_CONTEXT)
+            .track_focus(&self.focus_handle)
+            .cursor(CursorStyle::IBeam)
+            .on_action(cx.listener(Self::backspace))
+            .on_action(cx.listener(Self::delete))
+            .on_action(cx.listener(Self::left))
+            .on_action(cx.listener(Self::right))
+            .on_action(cx.listener(Self::up))
+            .on_action(cx.listener(Self::down))
+            .on_action(cx.listener(Self::word_left))
+            .on_action(cx.listener(Self::word_right))
+            .on_action(cx.listener(Self::select_left))
+            .on_action(cx.listener(Self::select_right))
+            .on_action(cx.listener(Self::select_up))
+            .on_action(cx.listener(Self::select_down))
+            .on_action(cx.listener(Self::select_word_left))
+            .on_action(cx.listener(Self::select_word_right))
+            .on_action(cx.listener(Self::home))
+            .on_action(cx.listener(Self::end))
+            .on_action(cx.listener(Self::select_to_home))
+            .on_action(cx.listener(Self::select_to_end))
+            .on_action(cx.listener(Self::select_all))
+            .on_action(cx.listener(Self::newline))
+            .on_action(cx.listener(Self::tab))
+            .on_action(cx.listener(Self::copy))
+            .on_action(cx.listener(Self::cut))
+            .on_action(cx.listener(Self::paste))
+            .on_action(cx.listener(Self::undo))
+            .on_action(cx.listener(Self::redo))
+            .on_mouse_down(MouseButton::Left, cx.listener(Self::on_mouse_down))
+            .on_mouse_up(MouseButton::Left, cx.listener(Self::on_mouse_up))
+            .on_mouse_up_out(MouseButton::Left, cx.listener(Self::on_mouse_up))
+            .on_mouse_move(cx.listener(Self::on_mouse_move))
+            .size_full()
+            // 与 StylingApp 一样：带 id 的 div + overflow_y_scroll 实现垂直滚动
+            .overflow_y_scroll()
+            .track_scroll(&self.scroll_handle)
+            .p(theme.spacing.sm)
+            .rounded(theme.radius.md)
+            .border_1()
+            .border_color(if is_focused { theme.accent } else { theme.border })
+            .bg(theme.surface)
+            .text_color(theme.text)
+            .text_sm()
+            .child(TextAreaElement { input: cx.entity() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // 不能 use super::*：gpui::* 中的 test 宏会遮蔽内置的 #[test]
+    use super::{Edit, LineBuffer, Redo, TextArea, Undo};
+    use gpui::{
+        point, px, AppContext, ClipboardItem, Entity, EntityInputHandler, Modifiers, TestAppContext,
+        VisualTestContext,
+    };
+
+    fn edit(start: usize, old_text: &str, new_text: &str) -> Edit {
+        let end = start + new_text.len();
+        Edit {
+            start,
+            old_text: old_text.to_string(),
+            new_text: new_text.to_string(),
+            selection_before: start + old_text.len()..start + old_text.len(),
+            selection_after: end..end,
+        }
+    }
+
+    /// 打开一个获得焦点、已经绘制过一帧的编辑器（上下移动和鼠标命中测试需要排版结果）
+    fn open_editor<'a>(text: &str, cx: &'a mut TestAppContext) -> (Entity<TextArea>, &'a mut VisualTestContext) {
+        cx.update(super::init);
+        let (editor, cx) = cx.add_window_view(|_, cx| TextArea::new(cx).placeholder("在这里写点什么……"));
+        editor.update_in(cx, |editor, window, cx| {
+            editor.set_text(text, cx);
+            editor.focus(window);
+        });
+        cx.run_until_parked();
+        (editor, cx)
+    }
+
+    /// 从 start 开始反复调用 step，收集经过的偏移，直到不再移动
+    fn walk(editor: &TextArea, start: usize, step: impl Fn(&TextArea, usize) -> usize) -> Vec<usize> {
+        let mut offsets = vec![start];
+        loop {
+            let next = step(editor, *offsets.last().unwrap());
+            if next == *offsets.last().unwrap() {
+                return offsets;
+            }
+            offsets.push(next);
+        }
+    }
+
+    #[test]
+    fn line_buffer_replaces_only_the_touched_lines() {
+        let mut buffer = LineBuffer::new("one\r\ntwo\nthree");
+        assert_eq!(buffer.lines, ["one", "two", "three"]);
+        assert_eq!(buffer.len(), 13);
+
+        // 偏移 -> (行, 行内偏移)：换行符前的位置属于上一行，超出末尾停在最后一行末尾
+        assert_eq!(buffer.offset_to_point(0), (0, 0));
+        assert_eq!(buffer.offset_to_point(3), (0, 3));
+        assert_eq!(buffer.offset_to_point(4), (1, 0));
+        assert_eq!(buffer.offset_to_point(13), (2, 5));
+        assert_eq!(buffer.offset_to_point(100), (2, 5));
+
+        // 跨行切片
+        assert_eq!(buffer.slice(1..10), "ne\ntwo\nth");
+        assert_eq!(buffer.slice(4..7), "two");
+        assert_eq!(buffer.slice(3..4), "\n");
+
+        // 合并三行为一行
+        buffer.replace(2..10, "-");
+        assert_eq!(buffer.lines, ["on-ree"]);
+        // 插入换行拆成多行
+        buffer.replace(2..3, "\r\nX\n");
+        assert_eq!(buffer.lines, ["on", "X", "ree"]);
+        assert_eq!(buffer.text(), "on\nX\nree");
+
+        // 删光之后仍然保留一个空行
+        buffer.replace(0..buffer.len(), "");
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.line_count(), 1);
+    }
+
+    #[test]
+    fn typing_and_backspace_runs_merge_into_one_step() {
+        // 连续输入
+        let mut typing = edit(0, "", "a");
+        assert!(typing.merge(&edit(1, "", "b")));
+        assert!(typing.merge(&edit(2, "", "c")));
+        assert_eq!(typing.new_text, "abc");
+        assert_eq!(typing.selection_after, 3..3);
+        // 换行是分组边界，不连续的位置也不合并
+        assert!(!typing.merge(&edit(3, "", "\n")));
+        assert!(!typing.merge(&edit(0, "", "x")));
+
+        // 连续退格：从 "abc" 末尾往前删
+        let mut backspace = edit(2, "c", "");
+        assert!(backspace.merge(&edit(1, "b", "")));
+        assert!(backspace.merge(&edit(0, "a", "")));
+        assert_eq!((backspace.start, backspace.old_text.as_str()), (0, "abc"));
+        assert_eq!(backspace.selection_after, 0..0);
+        // 别处的删除不合并
+        assert!(!backspace.merge(&edit(4, "d", "")));
+    }
+
+    #[test]
+    fn ime_composition_replaces_the_marked_text() {
+        let mut composing = edit(3, "", "n");
+        assert!(composing.merge(&edit(3, "n", "ni")));
+        assert!(composing.merge(&edit(3, "ni", "你")));
+        assert_eq!((composing.start, composing.old_text.as_str()), (3, ""));
+        assert_eq!(composing.new_text, "你");
+        assert_eq!(composing.selection_after, 6..6);
+    }
+
+    #[gpui::test]
+    fn cursor_crosses_lines_by_grapheme_and_word(cx: &mut TestAppContext) {
+        let text = "ab 中\ne\u{301}😀\n\n  end";
+        let editor = cx.new(TextArea::new);
+        editor.update(cx, |editor, cx| editor.set_text(text, cx));
+        editor.read_with(cx, |editor, _| {
+            // 换行符本身算一步
+            let forward = walk(editor, 0, TextArea::next_boundary);
+            assert_eq!(forward, [0, 1, 2, 3, 6, 7, 10, 14, 15, 16, 17, 18, 19, 20, 21]);
+            let backward = walk(editor, text.len(), TextArea::previous_boundary);
+            assert_eq!(backward, forward.iter().rev().copied().collect::<Vec<_>>());
+
+            // 按词移动跳过换行和空白，并跨到相邻的行
+            let forward = walk(editor, 0, TextArea::next_word_boundary);
+            assert_eq!(forward, [0, 2, 6, 10, 14, 21]);
+            let backward = walk(editor, text.len(), TextArea::previous_word_boundary);
+            assert_eq!(backward, [21, 18, 10, 7, 3, 0]);
+        });
+    }
+
+    #[gpui::test]
+    fn utf16_offsets_count_newlines(cx: &mut TestAppContext) {
+        // "😀" 占 4 字节 / 2 个 UTF-16 单元，换行符 1 / 1
+        let editor = cx.new(TextArea::new);
+        editor.update(cx, |editor, cx| editor.set_text("a😀\n中\nz", cx));
+        editor.read_with(cx, |editor, _| {
+            for (utf8, utf16) in [(0, 0), (1, 1), (5, 3), (6, 4), (9, 5), (10, 6), (11, 7)] {
+                assert_eq!(editor.offset_to_utf16(utf8), utf16, "UTF-8 偏移 {utf8}");
+                assert_eq!(editor.offset_from_utf16(utf16), utf8, "UTF-16 偏移 {utf16}");
+            }
+            // 落在代理对中间的 UTF-16 偏移移到 emoji 后面，超出末尾停在末尾
+            assert_eq!(editor.offset_from_utf16(2), 5);
+            assert_eq!(editor.offset_from_utf16(100), 11);
+        });
+    }
+
+    #[gpui::test]
+    fn clicking_the_placeholder_keeps_the_cursor_at_zero(cx: &mut TestAppContext) {
+        let (editor, cx) = open_editor("", cx);
+
+        // 空文档绘制的是占位文字：点在它中间也不能把光标放到占位文字里
+        cx.simulate_click(point(px(120.), px(16.)), Modifiers::none());
+        editor.read_with(cx, |editor, _| assert_eq!(editor.selected_range, 0..0));
+
+        // 之后的输入和退格正常工作，而不是在切片时越界
+        cx.simulate_input("hi");
+        cx.simulate_keystrokes("backspace down");
+        editor.read_with(cx, |editor, _| {
+            assert_eq!(editor.text(), "h");
+            assert_eq!(editor.selected_range, 1..1);
+        });
+    }
+
+    #[gpui::test]
+    fn undo_and_redo_restore_the_selection(cx: &mut TestAppContext) {
+        let (editor, cx) = open_editor("hello world", cx);
+        editor.update_in(cx, |editor, window, cx| {
+            // 选中 "world" 后替换
+            editor.selected_range = 6..11;
+            editor.replace_text_in_range(None, "there", window, cx);
+            assert_eq!(editor.text(), "hello there");
+            assert_eq!(editor.selected_range, 11..11);
+
+            editor.undo(&Undo, window, cx);
+            assert_eq!(editor.text(), "hello world");
+            assert_eq!(editor.selected_range, 6..11);
+            assert!(editor.can_redo());
+
+            editor.redo(&Redo, window, cx);
+            assert_eq!(editor.text(), "hello there");
+            assert_eq!(editor.selected_range, 11..11);
+            assert!(!editor.can_redo());
+        });
+
+        // 一次连续输入是一个撤销步骤，撤销后光标回到输入前的位置
+        cx.simulate_input("!!");
+        editor.update_in(cx, |editor, window, cx| {
+            assert_eq!(editor.text(), "hello there!!");
+            editor.undo(&Undo, window, cx);
+            assert_eq!(editor.text(), "hello there");
+            assert_eq!(editor.selected_range, 11..11);
+        });
+    }
+
+    #[gpui::test]
+    fn selection_extends_across_lines(cx: &mut TestAppContext) {
+        let (editor, cx) = open_editor("one\ntwo\nthree", cx);
+        editor.update(cx, |editor, _| editor.selected_range = 1..1);
+
+        // 向下选两行：横坐标保持在第 1 列
+        cx.simulate_keystrokes("shift-down shift-down");
+        editor.read_with(cx, |editor, _| {
+            assert_eq!(editor.selected_range, 1..9);
+            assert!(!editor.selection_reversed);
+        });
+
+        cx.simulate_keystrokes("secondary-c");
+        assert_eq!(
+            cx.read_from_clipboard().and_then(|item| item.text()).as_deref(),
+            Some("ne\ntwo\nt")
+        );
+
+        // 向上越过起点后选区反转
+        cx.simulate_keystrokes("shift-up shift-up shift-up");
+        editor.read_with(cx, |editor, _| {
+            assert_eq!(editor.selected_range, 0..1);
+            assert!(editor.selection_reversed);
+        });
+
+        // 删除跨行选区
+        editor.update(cx, |editor, _| editor.selected_range = 2..9);
+        cx.write_to_clipboard(ClipboardItem::new_string("X".into()));
+        cx.simulate_keystrokes("secondary-v");
+        editor.read_with(cx, |editor, _| {
+            assert_eq!(editor.text(), "onXhree");
+            assert_eq!(editor.line_count(), 1);
+        });
+    }
+}
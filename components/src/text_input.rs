@@ -0,0 +1,958 @@
+// 单行文本输入框
+// ==============
+// 参考 GPUI 自带的 examples/input.rs 实现，在此基础上补充了：
+// 1. 按词移动 / 选择（Ctrl+←/→，macOS 上是 Option+←/→）
+// 2. 内容超出宽度时水平滚动，保证光标可见
+// 3. Changed / Submitted 事件，父组件通过 cx.subscribe 获取输入内容
+// 4. 颜色跟随全局 Theme
+//
+// 中文输入法（IME）依赖 EntityInputHandler：
+// - 拼音组合过程中，平台调用 replace_and_mark_text_in_range 写入"标记文本"（带下划线）
+// - 选定候选词后，平台调用 replace_text_in_range 用最终文字替换标记文本
+// - 平台与我们之间的偏移量都是 UTF-16，内部存储使用 UTF-8，需要来回转换
+//
+// 使用前需要在启动时调用一次 text_input::init(cx) 注册快捷键。
+
+use std::ops::Range;
+
+use gpui::*;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::Theme;
+
+actions!(
+    text_input,
+    [
+        Backspace,
+        Delete,
+        Left,
+        Right,
+        WordLeft,
+        WordRight,
+        SelectLeft,
+        SelectRight,
+        SelectWordLeft,
+        SelectWordRight,
+        SelectAll,
+        Home,
+        End,
+        SelectToHome,
+        SelectToEnd,
+        Copy,
+        Cut,
+        Paste,
+        Submit,
+        ShowCharacterPalette,
+    ]
+);
+
+/// 快捷键只在 key_context("TextInput") 内生效
+const KEY_CONTEXT: &str = "TextInput";
+
+/// 注册文本输入框的快捷键，在 Application::run 里调用一次
+pub fn init(cx: &mut App) {
+    // 按词移动：macOS 习惯用 Option，其他平台用 Ctrl
+    let word = if cfg!(target_os = "macos") { "alt" } else { "ctrl" };
+
+    cx.bind_keys([
+        KeyBinding::new("backspace", Backspace, Some(KEY_CONTEXT)),
+        KeyBinding::new("delete", Delete, Some(KEY_CONTEXT)),
+        KeyBinding::new("left", Left, Some(KEY_CONTEXT)),
+        KeyBinding::new("right", Right, Some(KEY_CONTEXT)),
+        KeyBinding::new(&format!("{word}-left"), WordLeft, Some(KEY_CONTEXT)),
+        KeyBinding::new(&format!("{word}-right"), WordRight, Some(KEY_CONTEXT)),
+        KeyBinding::new("shift-left", SelectLeft, Some(KEY_CONTEXT)),
+        KeyBinding::new("shift-right", SelectRight, Some(KEY_CONTEXT)),
+        KeyBinding::new(&format!("shift-{word}-left"), SelectWordLeft, Some(KEY_CONTEXT)),
+        KeyBinding::new(&format!("shift-{word}-right"), SelectWordRight, Some(KEY_CONTEXT)),
+        KeyBinding::new("secondary-a", SelectAll, Some(KEY_CONTEXT)),
+        KeyBinding::new("home", Home, Some(KEY_CONTEXT)),
+        KeyBinding::new("end", End, Some(KEY_CONTEXT)),
+        KeyBinding::new("shift-home", SelectToHome, Some(KEY_CONTEXT)),
+        KeyBinding::new("shift-end", SelectToEnd, Some(KEY_CONTEXT)),
+        KeyBinding::new("secondary-c", Copy, Some(KEY_CONTEXT)),
+        KeyBinding::new("secondary-x", Cut, Some(KEY_CONTEXT)),
+        KeyBinding::new("secondary-v", Paste, Some(KEY_CONTEXT)),
+        KeyBinding::new("enter", Submit, Some(KEY_CONTEXT)),
+        KeyBinding::new("ctrl-cmd-space", ShowCharacterPalette, Some(KEY_CONTEXT)),
+    ]);
+}
+
+/// 输入框发出的事件
+#[derive(Clone, Debug, PartialEq)]
+pub enum TextInputEvent {
+    /// 内容发生变化（包括输入法组合中的临时文字）
+    Changed(SharedString),
+    /// 按下 Enter
+    Submitted(SharedString),
+}
+
+/// 单行文本输入框
+///
+/// ```ignore
+/// let input = cx.new(|cx| TextInput::new(cx).placeholder("需要做什么？"));
+/// cx.subscribe(&input, |view, input, event: &TextInputEvent, cx| {
+///     if let TextInputEvent::Submitted(text) = event { /* ... */ }
+/// }).detach();
+/// ```
+pub struct TextInput {
+    focus_handle: FocusHandle,
+    content: SharedString,
+    placeholder: SharedString,
+    /// 选区（UTF-8 字节偏移），为空时表示光标位置
+    selected_range: Range<usize>,
+    /// 选区是否从右往左选（光标在 start 一侧）
+    selection_reversed: bool,
+    /// 输入法正在组合的文字范围
+    marked_range: Option<Range<usize>>,
+    /// 上一帧的排版结果和位置，用于鼠标命中测试和输入法候选框定位
+    last_layout: Option<ShapedLine>,
+    last_bounds: Option<Bounds<Pixels>>,
+    /// 水平滚动距离
+    scroll_x: Pixels,
+    is_selecting: bool,
+}
+
+impl EventEmitter<TextInputEvent> for TextInput {}
+
+impl Focusable for TextInput {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl TextInput {
+    pub fn new(cx: &mut Context<Self>) -> Self {
+        Self {
+            focus_handle: cx.focus_handle(),
+            content: SharedString::default(),
+            placeholder: SharedString::default(),
+            selected_range: 0..0,
+            selection_reversed: false,
+            marked_range: None,
+            last_layout: None,
+            last_bounds: None,
+            scroll_x: px(0.0),
+            is_selecting: false,
+        }
+    }
+
+    /// 内容为空时显示的提示文字
+    pub fn placeholder(mut self, placeholder: impl Into<SharedString>) -> Self {
+        self.placeholder = placeholder.into();
+        self
+    }
+
+    pub fn text(&self) -> SharedString {
+        self.content.clone()
+    }
+
+    /// 替换全部内容，光标移到末尾
+    pub fn set_text(&mut self, text: impl Into<SharedString>, cx: &mut Context<Self>) {
+        self.content = text.into();
+        self.selected_range = self.content.len()..self.content.len();
+        self.selection_reversed = false;
+        self.marked_range = None;
+        cx.emit(TextInputEvent::Changed(self.content.clone()));
+        cx.notify();
+    }
+
+    pub fn clear(&mut self, cx: &mut Context<Self>) {
+        self.set_text("", cx);
+    }
+
+    pub fn focus(&self, window: &mut Window) {
+        self.focus_handle.focus(window);
+    }
+
+    // ------------------------------------------------------------------------
+    // Action 处理器
+    // ------------------------------------------------------------------------
+
+    fn left(&mut self, _: &Left, _: &mut Window, cx: &mut Context<Self>) {
+        if self.selected_range.is_empty() {
+            self.move_to(self.previous_boundary(self.cursor_offset()), cx);
+        } else {
+            self.move_to(self.selected_range.start, cx)
+        }
+    }
+
+    fn right(&mut self, _: &Right, _: &mut Window, cx: &mut Context<Self>) {
+        if self.selected_range.is_empty() {
+            self.move_to(self.next_boundary(self.selected_range.end), cx);
+        } else {
+            self.move_to(self.selected_range.end, cx)
+        }
+    }
+
+    fn word_left(&mut self, _: &WordLeft, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_to(self.previous_word_boundary(self.cursor_offset()), cx);
+    }
+
+    fn word_right(&mut self, _: &WordRight, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_to(self.next_word_boundary(self.cursor_offset()), cx);
+    }
+
+    fn select_left(&mut self, _: &SelectLeft, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_to(self.previous_boundary(self.cursor_offset()), cx);
+    }
+
+    fn select_right(&mut self, _: &SelectRight, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_to(self.next_boundary(self.cursor_offset()), cx);
+    }
+
+    fn select_word_left(&mut self, _: &SelectWordLeft, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_to(self.previous_word_boundary(self.cursor_offset()), cx);
+    }
+
+    fn select_word_right(&mut self, _: &SelectWordRight, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_to(self.next_word_boundary(self.cursor_offset()), cx);
+    }
+
+    fn select_all(&mut self, _: &SelectAll, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_to(0, cx);
+        self.select_to(self.content.len(), cx)
+    }
+
+    fn home(&mut self, _: &Home, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_to(0, cx);
+    }
+
+    fn end(&mut self, _: &End, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_to(self.content.len(), cx);
+    }
+
+    fn select_to_home(&mut self, _: &SelectToHome, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_to(0, cx);
+    }
+
+    fn select_to_end(&mut self, _: &SelectToEnd, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_to(self.content.len(), cx);
+    }
+
+    fn backspace(&mut self, _: &Backspace, window: &mut Window, cx: &mut Context<Self>) {
+        if self.selected_range.is_empty() {
+            self.select_to(self.previous_boundary(self.cursor_offset()), cx)
+        }
+        self.replace_text_in_range(None, "", window, cx)
+    }
+
+    fn delete(&mut self, _: &Delete, window: &mut Window, cx: &mut Context<Self>) {
+        if self.selected_range.is_empty() {
+            self.select_to(self.next_boundary(self.cursor_offset()), cx)
+        }
+        self.replace_text_in_range(None, "", window, cx)
+    }
+
+    fn copy(&mut self, _: &Copy, _: &mut Window, cx: &mut Context<Self>) {
+        if !self.selected_range.is_empty() {
+            cx.write_to_clipboard(ClipboardItem::new_string(
+                self.content[self.selected_range.clone()].to_string(),
+            ));
+        }
+    }
+
+    fn cut(&mut self, _: &Cut, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.selected_range.is_empty() {
+            cx.write_to_clipboard(ClipboardItem::new_string(
+                self.content[self.selected_range.clone()].to_string(),
+            ));
+            self.replace_text_in_range(None, "", window, cx)
+        }
+    }
+
+    fn paste(&mut self, _: &Paste, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(text) = cx.read_from_clipboard().and_then(|item| item.text()) {
+            // 单行输入框：换行替换成空格
+            self.replace_text_in_range(None, &text.replace(['\r', '\n'], " "), window, cx);
+        }
+    }
+
+    fn submit(&mut self, _: &Submit, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(TextInputEvent::Submitted(self.content.clone()));
+    }
+
+    fn show_character_palette(
+        &mut self,
+        _: &ShowCharacterPalette,
+        window: &mut Window,
+        _: &mut Context<Self>,
+    ) {
+        window.show_character_palette();
+    }
+
+    // ------------------------------------------------------------------------
+    // 鼠标选择
+    // ------------------------------------------------------------------------
+
+    fn on_mouse_down(&mut self, event: &MouseDownEvent, window: &mut Window, cx: &mut Context<Self>) {
+        self.is_selecting = true;
+        self.focus_handle.focus(window);
+
+        if event.modifiers.shift {
+            self.select_to(self.index_for_mouse_position(event.position), cx);
+        } else {
+            self.move_to(self.index_for_mouse_position(event.position), cx)
+        }
+    }
+
+    fn on_mouse_up(&mut self, _: &MouseUpEvent, _window: &mut Window, _: &mut Context<Self>) {
+        self.is_selecting = false;
+    }
+
+    fn on_mouse_move(&mut self, event: &MouseMoveEvent, _: &mut Window, cx: &mut Context<Self>) {
+        if self.is_selecting {
+            self.select_to(self.index_for_mouse_position(event.position), cx);
+        }
+    }
+
+    fn index_for_mouse_position(&self, position: Point<Pixels>) -> usize {
+        if self.content.is_empty() {
+            return 0;
+        }
+
+        let (Some(bounds), Some(line)) = (self.last_bounds.as_ref(), self.last_layout.as_ref())
+        else {
+            return 0;
+        };
+        if position.y < bounds.top() {
+            return 0;
+        }
+        if position.y > bounds.bottom() {
+            return self.content.len();
+        }
+        line.closest_index_for_x(position.x - bounds.left() + self.scroll_x)
+    }
+
+    // ------------------------------------------------------------------------
+    // 光标与选区
+    // ------------------------------------------------------------------------
+
+    fn move_to(&mut self, offset: usize, cx: &mut Context<Self>) {
+        self.selected_range = offset..offset;
+        self.selection_reversed = false;
+        cx.notify()
+    }
+
+    fn cursor_offset(&self) -> usize {
+        if self.selection_reversed {
+            self.selected_range.start
+        } else {
+            self.selected_range.end
+        }
+    }
+
+    fn select_to(&mut self, offset: usize, cx: &mut Context<Self>) {
+        if self.selection_reversed {
+            self.selected_range.start = offset
+        } else {
+            self.selected_range.end = offset
+        };
+        if self.selected_range.end < self.selected_range.start {
+            self.selection_reversed = !self.selection_reversed;
+            self.selected_range = self.selected_range.end..self.selected_range.start;
+        }
+        cx.notify()
+    }
+
+    /// 上一个字素边界（一个 emoji 或带声调的字母算一个字符）
+    fn previous_boundary(&self, offset: usize) -> usize {
+        self.content
+            .grapheme_indices(true)
+            .rev()
+            .find_map(|(idx, _)| (idx < offset).then_some(idx))
+            .unwrap_or(0)
+    }
+
+    fn next_boundary(&self, offset: usize) -> usize {
+        self.content
+            .grapheme_indices(true)
+            .find_map(|(idx, _)| (idx > offset).then_some(idx))
+            .unwrap_or(self.content.len())
+    }
+
+    /// 向左跳过空白，停在上一个词的开头
+    fn previous_word_boundary(&self, offset: usize) -> usize {
+        self.content[..offset]
+            .split_word_bound_indices()
+            .rev()
+            .find(|(_, word)| !word.chars().all(char::is_whitespace))
+            .map(|(idx, _)| idx)
+            .unwrap_or(0)
+    }
+
+    /// 向右跳过空白，停在下一个词的末尾
+    fn next_word_boundary(&self, offset: usize) -> usize {
+        self.content[offset..]
+            .split_word_bound_indices()
+            .find(|(_, word)| !word.chars().all(char::is_whitespace))
+            .map(|(idx, word)| offset + idx + word.len())
+            .unwrap_or(self.content.len())
+    }
+
+    // ------------------------------------------------------------------------
+    // UTF-8 <-> UTF-16 偏移转换（平台输入法使用 UTF-16）
+    // ------------------------------------------------------------------------
+
+    fn offset_from_utf16(&self, offset: usize) -> usize {
+        let mut utf8_offset = 0;
+        let mut utf16_count = 0;
+
+        for ch in self.content.chars() {
+            if utf16_count >= offset {
+                break;
+            }
+            utf16_count += ch.len_utf16();
+            utf8_offset += ch.len_utf8();
+        }
+
+        utf8_offset
+    }
+
+    fn offset_to_utf16(&self, offset: usize) -> usize {
+        let mut utf16_offset = 0;
+        let mut utf8_count = 0;
+
+        for ch in self.content.chars() {
+            if utf8_count >= offset {
+                break;
+            }
+            utf8_count += ch.len_utf8();
+            utf16_offset += ch.len_utf16();
+        }
+
+        utf16_offset
+    }
+
+    fn range_to_utf16(&self, range: &Range<usize>) -> Range<usize> {
+        self.offset_to_utf16(range.start)..self.offset_to_utf16(range.end)
+    }
+
+    fn range_from_utf16(&self, range_utf16: &Range<usize>) -> Range<usize> {
+        self.offset_from_utf16(range_utf16.start)..self.offset_from_utf16(range_utf16.end)
+    }
+}
+
+impl EntityInputHandler for TextInput {
+    fn text_for_range(
+        &mut self,
+        range_utf16: Range<usize>,
+        actual_range: &mut Option<Range<usize>>,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) -> Option<String> {
+        let range = self.range_from_utf16(&range_utf16);
+        actual_range.replace(self.range_to_utf16(&range));
+        Some(self.content[range].to_string())
+    }
+
+    fn selected_text_range(
+        &mut self,
+        _ignore_disabled_input: bool,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) -> Option<UTF16Selection> {
+        Some(UTF16Selection {
+            range: self.range_to_utf16(&self.selected_range),
+            reversed: self.selection_reversed,
+        })
+    }
+
+    fn marked_text_range(&self, _window: &mut Window, _cx: &mut Context<Self>) -> Option<Range<usize>> {
+        self.marked_range
+            .as_ref()
+            .map(|range| self.range_to_utf16(range))
+    }
+
+    fn unmark_text(&mut self, _window: &mut Window, _cx: &mut Context<Self>) {
+        self.marked_range = None;
+    }
+
+    /// 普通输入和输入法确认候选词都会走到这里
+    fn replace_text_in_range(
+        &mut self,
+        range_utf16: Option<Range<usize>>,
+        new_text: &str,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let range = range_utf16
+            .as_ref()
+            .map(|range_utf16| self.range_from_utf16(range_utf16))
+            .or(self.marked_range.clone())
+            .unwrap_or(self.selected_range.clone());
+
+        self.content =
+            (self.content[0..range.start].to_owned() + new_text + &self.content[range.end..]).into();
+        self.selected_range = range.start + new_text.len()..range.start + new_text.len();
+        self.selection_reversed = false;
+        self.marked_range.take();
+        cx.emit(TextInputEvent::Changed(self.content.clone()));
+        cx.notify();
+    }
+
+    /// 输入法组合中：写入标记文本，并把选区设置到组合文字内部
+    fn replace_and_mark_text_in_range(
+        &mut self,
+        range_utf16: Option<Range<usize>>,
+        new_text: &str,
+        new_selected_range_utf16: Option<Range<usize>>,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let range = range_utf16
+            .as_ref()
+            .map(|range_utf16| self.range_from_utf16(range_utf16))
+            .or(self.marked_range.clone())
+            .unwrap_or(self.selected_range.clone());
+
+        self.content =
+            (self.content[0..range.start].to_owned() + new_text + &self.content[range.end..]).into();
+        if !new_text.is_empty() {
+            self.marked_range = Some(range.start..range.start + new_text.len());
+        } else {
+            self.marked_range = None;
+        }
+        // new_selected_range 是相对于 new_text 的 UTF-16 偏移
+        self.selected_range = new_selected_range_utf16
+            .as_ref()
+            .map(|range_utf16| {
+                let start = range.start + utf16_to_utf8(new_text, range_utf16.start);
+                let end = range.start + utf16_to_utf8(new_text, range_utf16.end);
+                start..end
+            })
+            .unwrap_or_else(|| range.start + new_text.len()..range.start + new_text.len());
+        self.selection_reversed = false;
+
+        cx.emit(TextInputEvent::Changed(self.content.clone()));
+        cx.notify();
+    }
+
+    /// 输入法候选框定位：返回指定文字范围在屏幕上的位置
+    fn bounds_for_range(
+        &mut self,
+        range_utf16: Range<usize>,
+        bounds: Bounds<Pixels>,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) -> Option<Bounds<Pixels>> {
+        let last_layout = self.last_layout.as_ref()?;
+        let range = self.range_from_utf16(&range_utf16);
+        Some(Bounds::from_corners(
+            point(
+                bounds.left() + last_layout.x_for_index(range.start) - self.scroll_x,
+                bounds.top(),
+            ),
+            point(
+                bounds.left() + last_layout.x_for_index(range.end) - self.scroll_x,
+                bounds.bottom(),
+            ),
+        ))
+    }
+
+    fn character_index_for_point(
+        &mut self,
+        point: Point<Pixels>,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) -> Option<usize> {
+        let line_point = self.last_bounds?.localize(&point)?;
+        let last_layout = self.last_layout.as_ref()?;
+        let utf8_index = last_layout.index_for_x(line_point.x + self.scroll_x)?;
+        Some(self.offset_to_utf16(utf8_index))
+    }
+}
+
+/// 把字符串内的 UTF-16 偏移转换为 UTF-8 偏移
+fn utf16_to_utf8(text: &str, offset_utf16: usize) -> usize {
+    let mut utf16_count = 0;
+    for (ix, ch) in text.char_indices() {
+        if utf16_count >= offset_utf16 {
+            return ix;
+        }
+        utf16_count += ch.len_utf16();
+    }
+    text.len()
+}
+
+// ============================================================================
+// TextElement - 负责排版、绘制文字/光标/选区，并注册输入法处理器
+// ============================================================================
+
+struct TextElement {
+    input: Entity<TextInput>,
+}
+
+struct PrepaintState {
+    line: Option<ShapedLine>,
+    cursor: Option<PaintQuad>,
+    selection: Option<PaintQuad>,
+    scroll_x: Pixels,
+}
+
+impl IntoElement for TextElement {
+    type Element = Self;
+
+    fn into_element(self) -> Self::Element {
+        self
+    }
+}
+
+impl Element for TextElement {
+    type RequestLayoutState = ();
+    type PrepaintState = PrepaintState;
+
+    fn id(&self) -> Option<ElementId> {
+        None
+    }
+
+    fn source_location(&self) -> Option<&'static core::panic::Location<'static>> {
+        None
+    }
+
+    fn request_layout(
+        &mut self,
+        _id: Option<&GlobalElementId>,
+        _inspector_id: Option<&InspectorElementId>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> (LayoutId, Self::RequestLayoutState) {
+        let mut style = Style::default();
+        style.size.width = relative(1.).into();
+        style.size.height = window.line_height().into();
+        (window.request_layout(style, [], cx), ())
+    }
+
+    fn prepaint(
+        &mut self,
+        _id: Option<&GlobalElementId>,
+        _inspector_id: Option<&InspectorElementId>,
+        bounds: Bounds<Pixels>,
+        _request_layout: &mut Self::RequestLayoutState,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Self::PrepaintState {
+        let (placeholder_color, accent) = match cx.try_global::<Theme>() {
+            Some(theme) => (theme.subtext, theme.accent),
+            None => (rgb(0x9CA3AF).into(), rgb(0x3B82F6).into()),
+        };
+
+        let input = self.input.read(cx);
+        let content = input.content.clone();
+        let selected_range = input.selected_range.clone();
+        let cursor = input.cursor_offset();
+        let style = window.text_style();
+
+        let (display_text, text_color) = if content.is_empty() {
+            (input.placeholder.clone(), placeholder_color)
+        } else {
+            (content, style.color)
+        };
+
+        let run = TextRun {
+            len: display_text.len(),
+            font: style.font(),
+            color: text_color,
+            background_color: None,
+            underline: None,
+            strikethrough: None,
+        };
+        // 输入法组合中的文字加下划线
+        let runs = if let Some(marked_range) = input.marked_range.as_ref() {
+            vec![
+                TextRun {
+                    len: marked_range.start,
+                    ..run.clone()
+                },
+                TextRun {
+                    len: marked_range.end - marked_range.start,
+                    underline: Some(UnderlineStyle {
+                        color: Some(run.color),
+                        thickness: px(1.0),
+                        wavy: false,
+                    }),
+                    ..run.clone()
+                },
+                TextRun {
+                    len: display_text.len() - marked_range.end,
+                    ..run
+                },
+            ]
+            .into_iter()
+            .filter(|run| run.len > 0)
+            .collect()
+        } else {
+            vec![run]
+        };
+
+        let font_size = style.font_size.to_pixels(window.rem_size());
+        let line = window
+            .text_system()
+            .shape_line(display_text, font_size, &runs, None);
+
+        // 水平滚动：保证光标始终在可见区域内
+        let cursor_pos = line.x_for_index(cursor);
+        let width = bounds.size.width;
+        let mut scroll_x = input.scroll_x;
+        if cursor_pos - scroll_x > width - px(2.) {
+            scroll_x = cursor_pos - width + px(2.);
+        } else if cursor_pos < scroll_x {
+            scroll_x = cursor_pos;
+        }
+        if line.width <= width {
+            scroll_x = px(0.);
+        }
+        let left = bounds.left() - scroll_x;
+
+        let (selection, cursor) = if selected_range.is_empty() {
+            (
+                None,
+                Some(fill(
+                    Bounds::new(
+                        point(left + cursor_pos, bounds.top()),
+                        size(px(2.), bounds.bottom() - bounds.top()),
+                    ),
+                    accent,
+                )),
+            )
+        } else {
+            (
+                Some(fill(
+                    Bounds::from_corners(
+                        point(left + line.x_for_index(selected_range.start), bounds.top()),
+                        point(left + line.x_for_index(selected_range.end), bounds.bottom()),
+                    ),
+                    accent.opacity(0.25),
+                )),
+                None,
+            )
+        };
+        PrepaintState {
+            line: Some(line),
+            cursor,
+            selection,
+            scroll_x,
+        }
+    }
+
+    fn paint(
+        &mut self,
+        _id: Option<&GlobalElementId>,
+        _inspector_id: Option<&InspectorElementId>,
+        bounds: Bounds<Pixels>,
+        _request_layout: &mut Self::RequestLayoutState,
+        prepaint: &mut Self::PrepaintState,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let focus_handle = self.input.read(cx).focus_handle.clone();
+        window.handle_input(
+            &focus_handle,
+            ElementInputHandler::new(bounds, self.input.clone()),
+            cx,
+        );
+
+        let line = prepaint.line.take().unwrap();
+        let scroll_x = prepaint.scroll_x;
+
+        // 超出输入框的部分裁剪掉
+        window.with_content_mask(Some(ContentMask { bounds }), |window| {
+            if let Some(selection) = prepaint.selection.take() {
+                window.paint_quad(selection)
+            }
+            line.paint(
+                point(bounds.left() - scroll_x, bounds.top()),
+                window.line_height(),
+                window,
+                cx,
+            )
+            .unwrap();
+
+            if focus_handle.is_focused(window)
+                && let Some(cursor) = prepaint.cursor.take()
+            {
+                window.paint_quad(cursor);
+            }
+        });
+
+        self.input.update(cx, |input, _cx| {
+            input.last_layout = Some(line);
+            input.last_bounds = Some(bounds);
+            input.scroll_x = scroll_x;
+        });
+    }
+}
+
+impl Render for TextInput {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = cx.try_global::<Theme>().cloned().unwrap_or_else(Theme::light);
+        let is_focused = self.focus_handle.is_focused(window);
+
+        div()
+            .flex()
+            .items_center()
+            .key_context(KEY_CONTEXT)
+            .track_focus(&self.focus_handle)
+            .cursor(CursorStyle::IBeam)
+            .on_action(cx.listener(Self::backspace))
+            .on_action(cx.listener(Self::delete))
+            .on_action(cx.listener(Self::left))
+            .on_action(cx.listener(Self::right))
+            .on_action(cx.listener(Self::word_left))
+            .on_action(cx.listener(Self::word_right))
+            .on_action(cx.listener(Self::select_left))
+            .on_action(cx.listener(Self::select_right))
+            .on_action(cx.listener(Self::select_word_left))
+            .on_action(cx.listener(Self::select_word_right))
+            .on_action(cx.listener(Self::select_all))
+            .on_action(cx.listener(Self::home))
+            .on_action(cx.listener(Self::end))
+            .on_action(cx.listener(Self::select_to_home))
+            .on_action(cx.listener(Self::select_to_end))
+            .on_action(cx.listener(Self::copy))
+            .on_action(cx.listener(Self::cut))
+            .on_action(cx.listener(Self::paste))
+            .on_action(cx.listener(Self::submit))
+            .on_action(cx.listener(Self::show_character_palette))
+            .on_mouse_down(MouseButton::Left, cx.listener(Self::on_mouse_down))
+            .on_mouse_up(MouseButton::Left, cx.listener(Self::on_mouse_up))
+            .on_mouse_up_out(MouseButton::Left, cx.listener(Self::on_mouse_up))
+            .on_mouse_move(cx.listener(Self::on_mouse_move))
+            .w_full()
+            .h(px(36.))
+            .px(theme.spacing.md)
+            .overflow_hidden()
+            .rounded(theme.radius.md)
+            .border_1()
+            .border_color(if is_focused { theme.accent } else { theme.border })
+            .bg(theme.surface)
+            .text_color(theme.text)
+            .text_sm()
+            .child(TextElement { input: cx.entity() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // 不能 use super::*：gpui::* 中的 test 宏会遮蔽内置的 #[test]
+    use super::{utf16_to_utf8, TextInput};
+    use gpui::{AppContext, Entity, EntityInputHandler, TestAppContext};
+
+    /// "a" 1 字节 / 1 个 UTF-16 单元，"中" 3 / 1，"😀" 4 / 2（代理对），
+    /// "e\u{301}"（e + 组合重音符）1 + 2 / 1 + 1
+    const MIXED: &str = "a中😀e\u{301}";
+
+    /// MIXED 中每个字符边界的 (UTF-8, UTF-16) 偏移
+    const MIXED_OFFSETS: [(usize, usize); 6] = [(0, 0), (1, 1), (4, 2), (8, 4), (9, 5), (11, 6)];
+
+    fn input_with(text: &str, cx: &mut TestAppContext) -> Entity<TextInput> {
+        let input = cx.new(TextInput::new);
+        input.update(cx, |input, cx| input.set_text(text.to_string(), cx));
+        input
+    }
+
+    /// 从 start 开始反复调用 step，收集经过的偏移，直到不再移动
+    fn walk(input: &TextInput, start: usize, step: impl Fn(&TextInput, usize) -> usize) -> Vec<usize> {
+        let mut offsets = vec![start];
+        loop {
+            let next = step(input, *offsets.last().unwrap());
+            if next == *offsets.last().unwrap() {
+                return offsets;
+            }
+            offsets.push(next);
+        }
+    }
+
+    #[gpui::test]
+    fn utf16_offsets_convert_at_every_char_boundary(cx: &mut TestAppContext) {
+        let input = input_with(MIXED, cx);
+        input.read_with(cx, |input, _| {
+            for (utf8, utf16) in MIXED_OFFSETS {
+                assert_eq!(input.offset_to_utf16(utf8), utf16, "UTF-8 偏移 {utf8}");
+                assert_eq!(input.offset_from_utf16(utf16), utf8, "UTF-16 偏移 {utf16}");
+                // 往返转换得到原来的偏移
+                assert_eq!(input.offset_from_utf16(input.offset_to_utf16(utf8)), utf8);
+                assert_eq!(input.offset_to_utf16(input.offset_from_utf16(utf16)), utf16);
+                assert_eq!(utf16_to_utf8(MIXED, utf16), utf8);
+            }
+
+            // 范围转换：emoji 占 2 个 UTF-16 单元、4 个字节
+            assert_eq!(input.range_from_utf16(&(2..4)), 4..8);
+            assert_eq!(input.range_to_utf16(&(4..8)), 2..4);
+            assert_eq!(input.range_to_utf16(&(0..MIXED.len())), 0..6);
+
+            // 落在代理对中间的 UTF-16 偏移不会切开 emoji，而是移到它后面
+            assert_eq!(input.offset_from_utf16(3), 8);
+            assert!(MIXED.is_char_boundary(input.offset_from_utf16(3)));
+            // 超出末尾的偏移停在末尾
+            assert_eq!(input.offset_from_utf16(100), MIXED.len());
+            assert_eq!(utf16_to_utf8(MIXED, 100), MIXED.len());
+        });
+    }
+
+    #[gpui::test]
+    fn text_for_range_uses_utf16_ranges(cx: &mut TestAppContext) {
+        let (input, cx) = cx.add_window_view(|_, cx| TextInput::new(cx));
+        input.update_in(cx, |input, window, cx| {
+            input.set_text(MIXED, cx);
+
+            let mut actual = None;
+            let text = input.text_for_range(1..4, &mut actual, window, cx);
+            assert_eq!(text.as_deref(), Some("中😀"));
+            assert_eq!(actual, Some(1..4));
+
+            // 光标在末尾：UTF-16 选区是 6..6，而不是字节偏移 11..11
+            let selection = input.selected_text_range(false, window, cx).unwrap();
+            assert_eq!(selection.range, 6..6);
+        });
+    }
+
+    #[gpui::test]
+    fn ime_composition_selects_inside_the_marked_text(cx: &mut TestAppContext) {
+        let (input, cx) = cx.add_window_view(|_, cx| TextInput::new(cx));
+        input.update_in(cx, |input, window, cx| {
+            input.set_text("😀", cx);
+
+            // 输入法在 emoji 后面组合 "你好"，选区相对于组合文字，用 UTF-16 表示
+            input.replace_and_mark_text_in_range(None, "你好", Some(1..1), window, cx);
+            assert_eq!(input.text().as_ref(), "😀你好");
+            assert_eq!(input.marked_text_range(window, cx), Some(2..4));
+            assert_eq!(input.selected_range, 7..7);
+
+            // 确认候选词：替换组合中的文字，光标移到末尾
+            input.replace_text_in_range(None, "您好", window, cx);
+            assert_eq!(input.text().as_ref(), "😀您好");
+            assert_eq!(input.marked_text_range(window, cx), None);
+            assert_eq!(input.selected_text_range(false, window, cx).unwrap().range, 4..4);
+        });
+    }
+
+    #[gpui::test]
+    fn cursor_moves_by_grapheme(cx: &mut TestAppContext) {
+        // 组合重音符、带肤色修饰的 emoji、ZWJ 连接的家庭 emoji 都只算一个字符
+        let text = "e\u{301}😀👍🏽👨\u{200D}👩\u{200D}👧中";
+        let input = input_with(text, cx);
+        input.read_with(cx, |input, _| {
+            let forward = walk(input, 0, TextInput::next_boundary);
+            assert_eq!(forward, [0, 3, 7, 15, 33, 36]);
+            let backward = walk(input, text.len(), TextInput::previous_boundary);
+            assert_eq!(backward, [36, 33, 15, 7, 3, 0]);
+            // 每个停下的位置都是合法的字符边界
+            assert!(forward.iter().all(|&offset| text.is_char_boundary(offset)));
+        });
+    }
+
+    #[gpui::test]
+    fn cursor_moves_by_word(cx: &mut TestAppContext) {
+        let text = "hello  cafe\u{301} 世界 🙂 end";
+        let input = input_with(text, cx);
+        input.read_with(cx, |input, _| {
+            // 向右停在词的末尾：带组合符的 "café" 是一个词，汉字逐字成词，emoji 也算一个词
+            let forward = walk(input, 0, TextInput::next_word_boundary);
+            assert_eq!(forward, [0, 5, 13, 17, 20, 25, 29]);
+            // 向左停在词的开头，跳过连续的空白
+            let backward = walk(input, text.len(), TextInput::previous_word_boundary);
+            assert_eq!(backward, [29, 26, 21, 17, 14, 7, 0]);
+        });
+    }
+}
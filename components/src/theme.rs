@@ -0,0 +1,180 @@
+// 主题系统
+// ========
+// 用一组语义化的设计令牌（design token）代替各处硬编码的颜色：
+// 视图只读取 theme.surface、theme.text 等语义字段，
+// 切换浅色/深色主题时不需要在每个 render 里写 if is_dark { .. } else { .. }。
+
+use gpui::*;
+
+/// 主题模式
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ThemeMode {
+    Light,
+    Dark,
+}
+
+/// 圆角尺寸表
+#[derive(Clone, Copy, Debug)]
+pub struct RadiusScale {
+    pub sm: Pixels,
+    pub md: Pixels,
+    pub lg: Pixels,
+    pub full: Pixels,
+}
+
+impl Default for RadiusScale {
+    fn default() -> Self {
+        Self {
+            sm: px(4.0),
+            md: px(6.0),
+            lg: px(8.0),
+            full: px(9999.0),
+        }
+    }
+}
+
+/// 间距尺寸表
+#[derive(Clone, Copy, Debug)]
+pub struct SpacingScale {
+    pub xs: Pixels,
+    pub sm: Pixels,
+    pub md: Pixels,
+    pub lg: Pixels,
+    pub xl: Pixels,
+}
+
+impl Default for SpacingScale {
+    fn default() -> Self {
+        Self {
+            xs: px(4.0),
+            sm: px(8.0),
+            md: px(12.0),
+            lg: px(16.0),
+            xl: px(24.0),
+        }
+    }
+}
+
+/// 全局主题 - 保存所有语义化的设计令牌
+///
+/// ```ignore
+/// let theme = cx.theme();
+/// div().bg(theme.surface).text_color(theme.text).rounded(theme.radius.lg)
+/// ```
+#[derive(Clone, Debug)]
+pub struct Theme {
+    pub mode: ThemeMode,
+    /// 窗口背景
+    pub background: Hsla,
+    /// 卡片、面板等表面
+    pub surface: Hsla,
+    /// 次级表面（日志区、输入框背景等）
+    pub surface_muted: Hsla,
+    /// 主要文字
+    pub text: Hsla,
+    /// 次要文字（说明、提示）
+    pub subtext: Hsla,
+    /// 强调色（主按钮、链接、焦点）
+    pub accent: Hsla,
+    /// 强调色上的文字
+    pub accent_foreground: Hsla,
+    pub danger: Hsla,
+    pub success: Hsla,
+    pub warning: Hsla,
+    pub border: Hsla,
+    /// 提示框（💡 知识点）的背景、边框和文字
+    pub callout_background: Hsla,
+    pub callout_border: Hsla,
+    pub callout_text: Hsla,
+    pub radius: RadiusScale,
+    pub spacing: SpacingScale,
+}
+
+impl Global for Theme {}
+
+impl Theme {
+    /// 浅色主题
+    pub fn light() -> Self {
+        Self {
+            mode: ThemeMode::Light,
+            background: rgb(0xF1F5F9).into(),
+            surface: rgb(0xFFFFFF).into(),
+            surface_muted: rgb(0xF3F4F6).into(),
+            text: rgb(0x1E293B).into(),
+            subtext: rgb(0x64748B).into(),
+            accent: rgb(0x3B82F6).into(),
+            accent_foreground: rgb(0xFFFFFF).into(),
+            danger: rgb(0xEF4444).into(),
+            success: rgb(0x10B981).into(),
+            warning: rgb(0xF59E0B).into(),
+            border: rgb(0xE5E7EB).into(),
+            callout_background: rgb(0xFEF3C7).into(),
+            callout_border: rgb(0xFCD34D).into(),
+            callout_text: rgb(0x92400E).into(),
+            radius: RadiusScale::default(),
+            spacing: SpacingScale::default(),
+        }
+    }
+
+    /// 深色主题
+    pub fn dark() -> Self {
+        Self {
+            mode: ThemeMode::Dark,
+            background: rgb(0x111827).into(),
+            surface: rgb(0x1F2937).into(),
+            surface_muted: rgb(0x374151).into(),
+            text: rgb(0xF9FAFB).into(),
+            subtext: rgb(0x9CA3AF).into(),
+            accent: rgb(0x60A5FA).into(),
+            accent_foreground: rgb(0xFFFFFF).into(),
+            danger: rgb(0xF87171).into(),
+            success: rgb(0x34D399).into(),
+            warning: rgb(0xFBBF24).into(),
+            border: rgb(0x4B5563).into(),
+            callout_background: rgb(0x374151).into(),
+            callout_border: rgb(0x4B5563).into(),
+            callout_text: rgb(0xFCD34D).into(),
+            radius: RadiusScale::default(),
+            spacing: SpacingScale::default(),
+        }
+    }
+
+    pub fn is_dark(&self) -> bool {
+        self.mode == ThemeMode::Dark
+    }
+
+    /// 如果还没有设置主题，则设置为浅色主题
+    pub fn init(cx: &mut App) {
+        if !cx.has_global::<Theme>() {
+            cx.set_global(Theme::light());
+        }
+    }
+
+    /// 读取全局主题（需要先调用 Theme::init）
+    pub fn global(cx: &App) -> &Theme {
+        cx.global::<Theme>()
+    }
+
+    /// 在浅色与深色预设之间切换
+    pub fn toggle(cx: &mut App) {
+        let next = if Theme::global(cx).is_dark() {
+            Theme::light()
+        } else {
+            Theme::dark()
+        };
+        cx.set_global(next);
+    }
+}
+
+/// 从任意上下文读取当前主题
+///
+/// Context<T> 可以解引用为 App，因此在 render 中直接写 cx.theme() 即可。
+pub trait ActiveTheme {
+    fn theme(&self) -> &Theme;
+}
+
+impl ActiveTheme for App {
+    fn theme(&self) -> &Theme {
+        Theme::global(self)
+    }
+}
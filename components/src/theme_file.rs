@@ -0,0 +1,373 @@
+// 主题文件加载与热重载
+// ====================
+// 设计师可以在 JSON 或 TOML 文件中调整配色，无需重新编译：
+// 1. load_theme_file() 解析文件并校验每个键，错误信息会指出出错的键名
+// 2. watch_theme_file() 通过 watch_file() 监听文件修改，变化后替换全局 Theme 并刷新所有窗口
+//
+// 文件格式（TOML 示例，JSON 结构相同）：
+//
+//     base = "dark"              # 以哪个预设为基础，可选 light / dark
+//
+//     [colors]                   # 只需写出要覆盖的令牌，颜色使用十六进制字符串
+//     accent = "#F472B6"
+//     surface = "#1E1B4B"
+//
+//     [radius]                   # 单位为像素
+//     lg = 12
+//
+//     [spacing]
+//     lg = 20
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use gpui::*;
+use serde::Deserialize;
+
+use crate::{file_from_args, watch_file, Theme};
+
+/// 主题文件的原始结构，所有字段都是可选的覆盖项
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct ThemeFile {
+    #[serde(default)]
+    base: Option<String>,
+    #[serde(default)]
+    colors: BTreeMap<String, String>,
+    #[serde(default)]
+    radius: BTreeMap<String, f32>,
+    #[serde(default)]
+    spacing: BTreeMap<String, f32>,
+}
+
+/// 加载主题文件时可能出现的错误
+#[derive(Clone, Debug, PartialEq)]
+pub enum ThemeError {
+    /// 读取文件失败
+    Io { path: PathBuf, message: String },
+    /// 扩展名既不是 .json 也不是 .toml
+    UnsupportedFormat { path: PathBuf },
+    /// JSON/TOML 语法错误，或出现了未知的顶层字段
+    Parse { path: PathBuf, message: String },
+    /// 未知的令牌名，例如 colors.backgruond
+    UnknownKey { key: String },
+    /// 颜色值不是合法的十六进制字符串
+    InvalidColor { key: String, value: String },
+    /// 数值或枚举值不合法，例如负数圆角、base = "blue"
+    InvalidValue { key: String, message: String },
+}
+
+impl fmt::Display for ThemeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ThemeError::Io { path, message } => {
+                write!(f, "无法读取主题文件 {}: {}", path.display(), message)
+            }
+            ThemeError::UnsupportedFormat { path } => {
+                write!(f, "不支持的主题文件格式 {}（需要 .json 或 .toml）", path.display())
+            }
+            ThemeError::Parse { path, message } => {
+                write!(f, "解析主题文件 {} 失败: {}", path.display(), message)
+            }
+            ThemeError::UnknownKey { key } => write!(f, "未知的主题键 `{}`", key),
+            ThemeError::InvalidColor { key, value } => {
+                write!(f, "`{}` 的值 \"{}\" 不是合法的十六进制颜色", key, value)
+            }
+            ThemeError::InvalidValue { key, message } => write!(f, "`{}` 的值不合法: {}", key, message),
+        }
+    }
+}
+
+impl std::error::Error for ThemeError {}
+
+/// 最近一次加载主题文件的结果，视图可以读取它来显示错误提示
+#[derive(Clone, Debug)]
+pub struct ThemeFileStatus {
+    pub path: PathBuf,
+    pub error: Option<ThemeError>,
+}
+
+impl Global for ThemeFileStatus {}
+
+/// 从命令行参数 `--theme <path>` 中读取主题文件路径
+pub fn theme_file_from_args() -> Option<PathBuf> {
+    file_from_args("--theme")
+}
+
+/// 读取并解析主题文件，格式由扩展名决定
+pub fn load_theme_file(path: &Path) -> Result<Theme, ThemeError> {
+    parse_theme_file(path, read_theme_file(path, std::fs::read_to_string(path))?)
+}
+
+fn read_theme_file(path: &Path, source: std::io::Result<String>) -> Result<String, ThemeError> {
+    source.map_err(|err| ThemeError::Io {
+        path: path.to_path_buf(),
+        message: err.to_string(),
+    })
+}
+
+/// 解析已经读取的主题文件内容，path 只用于判断格式和报告错误
+fn parse_theme_file(path: &Path, source: String) -> Result<Theme, ThemeError> {
+    let file: ThemeFile = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("json") => serde_json::from_str(&source).map_err(|err| ThemeError::Parse {
+            path: path.to_path_buf(),
+            message: err.to_string(),
+        })?,
+        Some("toml") => toml::from_str(&source).map_err(|err| ThemeError::Parse {
+            path: path.to_path_buf(),
+            message: err.to_string(),
+        })?,
+        _ => {
+            return Err(ThemeError::UnsupportedFormat {
+                path: path.to_path_buf(),
+            });
+        }
+    };
+
+    file.into_theme()
+}
+
+impl ThemeFile {
+    /// 以 base 预设为基础，逐个应用文件中的覆盖项
+    fn into_theme(self) -> Result<Theme, ThemeError> {
+        let mut theme = match self.base.as_deref() {
+            None | Some("light") => Theme::light(),
+            Some("dark") => Theme::dark(),
+            Some(other) => {
+                return Err(ThemeError::InvalidValue {
+                    key: "base".to_string(),
+                    message: format!("\"{}\" 不是 light 或 dark", other),
+                });
+            }
+        };
+
+        for (name, value) in &self.colors {
+            let key = format!("colors.{}", name);
+            let color = Rgba::try_from(value.as_str()).map_err(|_| ThemeError::InvalidColor {
+                key: key.clone(),
+                value: value.clone(),
+            })?;
+            *color_slot(&mut theme, name).ok_or(ThemeError::UnknownKey { key })? = color.into();
+        }
+
+        for (name, value) in &self.radius {
+            let key = format!("radius.{}", name);
+            let slot = match name.as_str() {
+                "sm" => &mut theme.radius.sm,
+                "md" => &mut theme.radius.md,
+                "lg" => &mut theme.radius.lg,
+                "full" => &mut theme.radius.full,
+                _ => return Err(ThemeError::UnknownKey { key }),
+            };
+            *slot = px(non_negative(&key, *value)?);
+        }
+
+        for (name, value) in &self.spacing {
+            let key = format!("spacing.{}", name);
+            let slot = match name.as_str() {
+                "xs" => &mut theme.spacing.xs,
+                "sm" => &mut theme.spacing.sm,
+                "md" => &mut theme.spacing.md,
+                "lg" => &mut theme.spacing.lg,
+                "xl" => &mut theme.spacing.xl,
+                _ => return Err(ThemeError::UnknownKey { key }),
+            };
+            *slot = px(non_negative(&key, *value)?);
+        }
+
+        Ok(theme)
+    }
+}
+
+/// 颜色令牌名 -> Theme 中对应的字段
+fn color_slot<'a>(theme: &'a mut Theme, name: &str) -> Option<&'a mut Hsla> {
+    Some(match name {
+        "background" => &mut theme.background,
+        "surface" => &mut theme.surface,
+        "surface_muted" => &mut theme.surface_muted,
+        "text" => &mut theme.text,
+        "subtext" => &mut theme.subtext,
+        "accent" => &mut theme.accent,
+        "accent_foreground" => &mut theme.accent_foreground,
+        "danger" => &mut theme.danger,
+        "success" => &mut theme.success,
+        "warning" => &mut theme.warning,
+        "border" => &mut theme.border,
+        "callout_background" => &mut theme.callout_background,
+        "callout_border" => &mut theme.callout_border,
+        "callout_text" => &mut theme.callout_text,
+        _ => return None,
+    })
+}
+
+fn non_negative(key: &str, value: f32) -> Result<f32, ThemeError> {
+    if value.is_finite() && value >= 0.0 {
+        Ok(value)
+    } else {
+        Err(ThemeError::InvalidValue {
+            key: key.to_string(),
+            message: format!("{} 必须是非负数", value),
+        })
+    }
+}
+
+/// 把加载结果应用到全局状态：成功则替换 Theme，失败则保留当前主题并记录错误
+///
+/// 错误只记录在 ThemeFileStatus 中，由视图显示
+fn apply_loaded_theme(path: &Path, result: Result<Theme, ThemeError>, cx: &mut App) {
+    let error = match result {
+        Ok(theme) => {
+            cx.set_global(theme);
+            None
+        }
+        Err(error) => Some(error),
+    };
+    cx.set_global(ThemeFileStatus {
+        path: path.to_path_buf(),
+        error,
+    });
+    cx.refresh_windows();
+}
+
+/// 监听主题文件：立即加载一次，之后每当文件修改时间变化就重新加载
+///
+/// 返回的 Task 被 drop 时停止监听，通常直接 `.detach()`。
+pub fn watch_theme_file(path: PathBuf, cx: &mut App) -> Task<()> {
+    Theme::init(cx);
+
+    watch_file(
+        path,
+        |path, source, cx| {
+            let result = read_theme_file(path, source).and_then(|source| parse_theme_file(path, source));
+            apply_loaded_theme(path, result, cx);
+        },
+        cx,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    // 不能 use super::*：gpui::* 中的 test 宏会遮蔽内置的 #[test]
+    use super::{load_theme_file, watch_theme_file, ThemeError, ThemeFileStatus};
+    use crate::Theme;
+    use gpui::{px, rgb, Hsla, TestAppContext};
+    use std::path::{Path, PathBuf};
+
+    /// 把内容写入临时目录中的主题文件，扩展名决定格式
+    fn theme_file(name: &str, contents: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("gpui_components_theme_file_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    fn hsla(hex: u32) -> Hsla {
+        rgb(hex).into()
+    }
+
+    #[test]
+    fn partial_override_keeps_the_base_defaults() {
+        let path = theme_file(
+            "partial.toml",
+            "base = \"dark\"\n[colors]\naccent = \"#F472B6\"\n[radius]\nlg = 12\n",
+        );
+        let theme = load_theme_file(&path).unwrap();
+        let dark = Theme::dark();
+
+        assert!(theme.is_dark());
+        assert_eq!(theme.accent, hsla(0xF472B6));
+        assert_eq!(theme.radius.lg, px(12.0));
+        // 没写出的令牌来自 base 预设
+        assert_eq!(theme.background, dark.background);
+        assert_eq!(theme.text, dark.text);
+        assert_eq!(theme.radius.sm, dark.radius.sm);
+        assert_eq!(theme.spacing.lg, dark.spacing.lg);
+
+        // 不写 base 时以浅色预设为基础，JSON 与 TOML 结构相同
+        let path = theme_file("partial.json", r##"{ "colors": { "surface": "#1E1B4B" } }"##);
+        let theme = load_theme_file(&path).unwrap();
+        assert!(!theme.is_dark());
+        assert_eq!(theme.surface, hsla(0x1E1B4B));
+        assert_eq!(theme.accent, Theme::light().accent);
+    }
+
+    #[test]
+    fn unknown_keys_are_reported_by_name() {
+        let path = theme_file("typo.toml", "[colors]\nbackgruond = \"#000000\"\n");
+        assert_eq!(
+            load_theme_file(&path).unwrap_err(),
+            ThemeError::UnknownKey {
+                key: "colors.backgruond".to_string()
+            }
+        );
+
+        let path = theme_file("typo_radius.json", r#"{ "radius": { "xxl": 4 } }"#);
+        assert_eq!(
+            load_theme_file(&path).unwrap_err(),
+            ThemeError::UnknownKey {
+                key: "radius.xxl".to_string()
+            }
+        );
+
+        // 未知的顶层字段由 serde 的 deny_unknown_fields 拒绝
+        let path = theme_file("typo_section.toml", "[colours]\naccent = \"#000000\"\n");
+        assert!(matches!(load_theme_file(&path), Err(ThemeError::Parse { .. })));
+    }
+
+    #[test]
+    fn bad_hex_colors_are_rejected() {
+        let path = theme_file("bad_color.toml", "[colors]\naccent = \"#GG0000\"\n");
+        let error = load_theme_file(&path).unwrap_err();
+        assert_eq!(
+            error,
+            ThemeError::InvalidColor {
+                key: "colors.accent".to_string(),
+                value: "#GG0000".to_string(),
+            }
+        );
+        assert_eq!(error.to_string(), "`colors.accent` 的值 \"#GG0000\" 不是合法的十六进制颜色");
+    }
+
+    #[test]
+    fn missing_file_is_an_io_error() {
+        let path = Path::new("/nonexistent/theme.toml");
+        assert!(matches!(
+            load_theme_file(path),
+            Err(ThemeError::Io { path: error_path, .. }) if error_path == path
+        ));
+    }
+
+    #[test]
+    fn bundled_themes_load() {
+        let themes = Path::new(env!("CARGO_MANIFEST_DIR")).join("../themes");
+        for name in ["midnight.json", "ocean.toml"] {
+            if let Err(error) = load_theme_file(&themes.join(name)) {
+                panic!("{name}: {error}");
+            }
+        }
+    }
+
+    #[gpui::test]
+    fn failed_reload_keeps_the_current_theme(cx: &mut TestAppContext) {
+        let path = theme_file("watched.toml", "base = \"dark\"\n");
+        cx.update(|cx| watch_theme_file(path.clone(), cx).detach());
+        cx.run_until_parked();
+        cx.read(|cx| {
+            assert!(Theme::global(cx).is_dark());
+            assert_eq!(cx.global::<ThemeFileStatus>().error, None);
+        });
+
+        // 错误只记录在 ThemeFileStatus 中，界面继续使用上一次成功加载的主题
+        let bad = theme_file("broken.toml", "[colors]\naccent = \"blue\"\n");
+        cx.update(|cx| watch_theme_file(bad.clone(), cx).detach());
+        cx.run_until_parked();
+        cx.read(|cx| {
+            assert!(Theme::global(cx).is_dark());
+            let status = cx.global::<ThemeFileStatus>();
+            assert_eq!(status.path, bad);
+            assert!(matches!(status.error, Some(ThemeError::InvalidColor { .. })));
+        });
+    }
+}
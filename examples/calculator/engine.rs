@@ -0,0 +1,459 @@
+// 计算器引擎
+// ==========
+// 纯 Rust 模块，不依赖 GPUI，UI 只负责拼接表达式字符串并显示结果：
+// 1. Decimal - 十进制定点数，避免 0.1 + 0.2 = 0.30000000000000004
+// 2. tokenize() - 把表达式切分成 Token
+// 3. Parser - 递归下降解析，* / 优先于 + -，支持括号和一元负号
+// 4. evaluate() - 一步完成解析和计算，除以零等错误以 CalcError 返回
+
+use std::fmt;
+
+/// 除法结果保留的最大小数位数
+const MAX_SCALE: u32 = 12;
+
+// ============================================================================
+// 第一部分：Decimal
+// ============================================================================
+
+/// 十进制数：值 = mantissa / 10^scale
+///
+/// 运算使用 checked_* 检测溢出，结果总是去掉末尾多余的 0。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Decimal {
+    mantissa: i128,
+    scale: u32,
+}
+
+impl Decimal {
+    fn new(mantissa: i128, scale: u32) -> Self {
+        Self { mantissa, scale }.normalize()
+    }
+
+    /// 去掉末尾的 0：1.500 -> 1.5
+    fn normalize(mut self) -> Self {
+        while self.scale > 0 && self.mantissa % 10 == 0 {
+            self.mantissa /= 10;
+            self.scale -= 1;
+        }
+        self
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.mantissa == 0
+    }
+
+    /// 解析 "12"、"3.25"、".5"、"7." 这样的数字字面量
+    pub fn parse(literal: &str) -> Result<Self, CalcError> {
+        let invalid = || CalcError::InvalidNumber(literal.to_string());
+        let (int_part, frac_part) = match literal.split_once('.') {
+            Some((int_part, frac_part)) => (int_part, frac_part),
+            None => (literal, ""),
+        };
+        let digits_only = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+        if (int_part.is_empty() && frac_part.is_empty())
+            || !digits_only(int_part)
+            || !digits_only(frac_part)
+        {
+            return Err(invalid());
+        }
+
+        let mut mantissa: i128 = 0;
+        for digit in int_part.chars().chain(frac_part.chars()) {
+            mantissa = mantissa
+                .checked_mul(10)
+                .and_then(|m| m.checked_add(digit.to_digit(10).unwrap_or(0) as i128))
+                .ok_or(CalcError::Overflow)?;
+        }
+        Ok(Self::new(mantissa, frac_part.len() as u32))
+    }
+
+    /// 把两个数放大到相同的小数位数
+    fn aligned(self, other: Self) -> Result<(i128, i128, u32), CalcError> {
+        let scale = self.scale.max(other.scale);
+        let lhs = rescale(self.mantissa, scale - self.scale)?;
+        let rhs = rescale(other.mantissa, scale - other.scale)?;
+        Ok((lhs, rhs, scale))
+    }
+
+    pub fn add(self, other: Self) -> Result<Self, CalcError> {
+        let (lhs, rhs, scale) = self.aligned(other)?;
+        Ok(Self::new(lhs.checked_add(rhs).ok_or(CalcError::Overflow)?, scale))
+    }
+
+    pub fn sub(self, other: Self) -> Result<Self, CalcError> {
+        let (lhs, rhs, scale) = self.aligned(other)?;
+        Ok(Self::new(lhs.checked_sub(rhs).ok_or(CalcError::Overflow)?, scale))
+    }
+
+    pub fn mul(self, other: Self) -> Result<Self, CalcError> {
+        let mantissa = self
+            .mantissa
+            .checked_mul(other.mantissa)
+            .ok_or(CalcError::Overflow)?;
+        Ok(round_to_scale(mantissa, self.scale + other.scale, MAX_SCALE))
+    }
+
+    pub fn div(self, other: Self) -> Result<Self, CalcError> {
+        if other.is_zero() {
+            return Err(CalcError::DivisionByZero);
+        }
+        // 长除法：quotient / 10^scale 是到目前为止的商，每一步把余数放大 10 倍求出下一位，
+        // 多算一位用于四舍五入。不预先把被除数放大到目标精度，那样被除数超过约 1e25 就会溢出
+        let target = (MAX_SCALE + 1) as i64;
+        let mut scale = self.scale as i64 - other.scale as i64;
+        let mut numerator = self.mantissa;
+        if scale > target {
+            // 先截掉多余的小数位：trunc(trunc(a / 10^k) / b) = trunc(a / (10^k * b))
+            numerator = 10i128
+                .checked_pow((scale - target) as u32)
+                .map_or(0, |factor| numerator / factor);
+            scale = target;
+        }
+
+        let divisor = other.mantissa;
+        let mut quotient = numerator.checked_div(divisor).ok_or(CalcError::Overflow)?;
+        let mut remainder = numerator % divisor;
+        while scale < target {
+            let shifted = remainder.checked_mul(10).ok_or(CalcError::Overflow)?;
+            let digit = shifted / divisor;
+            match quotient.checked_mul(10).and_then(|q| q.checked_add(digit)) {
+                Some(next) => quotient = next,
+                // 放不下更多小数位了：商已经是整数或小数时，按下一位四舍五入后返回
+                None if scale >= 0 => {
+                    let rounded = match digit {
+                        5.. => quotient.checked_add(1),
+                        ..=-5 => quotient.checked_sub(1),
+                        _ => Some(quotient),
+                    };
+                    return Ok(Self::new(rounded.ok_or(CalcError::Overflow)?, scale as u32));
+                }
+                None => return Err(CalcError::Overflow),
+            }
+            remainder = shifted % divisor;
+            scale += 1;
+        }
+        Ok(round_to_scale(quotient, target as u32, MAX_SCALE))
+    }
+
+    /// 取负；i128::MIN 没有对应的正数，返回 Overflow
+    pub fn neg(self) -> Result<Self, CalcError> {
+        Ok(Self {
+            mantissa: self.mantissa.checked_neg().ok_or(CalcError::Overflow)?,
+            scale: self.scale,
+        })
+    }
+}
+
+/// mantissa * 10^places，溢出时报错
+fn rescale(mantissa: i128, places: u32) -> Result<i128, CalcError> {
+    10i128
+        .checked_pow(places)
+        .and_then(|factor| mantissa.checked_mul(factor))
+        .ok_or(CalcError::Overflow)
+}
+
+/// 小数位数超过 max_scale 时四舍五入（远离零）
+fn round_to_scale(mut mantissa: i128, mut scale: u32, max_scale: u32) -> Decimal {
+    while scale > max_scale {
+        let remainder = mantissa % 10;
+        mantissa /= 10;
+        if remainder >= 5 {
+            mantissa += 1;
+        } else if remainder <= -5 {
+            mantissa -= 1;
+        }
+        scale -= 1;
+    }
+    Decimal::new(mantissa, scale)
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = self.mantissa.unsigned_abs().to_string();
+        let sign = if self.mantissa < 0 { "-" } else { "" };
+        let scale = self.scale as usize;
+        if scale == 0 {
+            return write!(f, "{}{}", sign, digits);
+        }
+        let digits = format!("{:0>width$}", digits, width = scale + 1);
+        let (int_part, frac_part) = digits.split_at(digits.len() - scale);
+        write!(f, "{}{}.{}", sign, int_part, frac_part)
+    }
+}
+
+// ============================================================================
+// 第二部分：错误类型
+// ============================================================================
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CalcError {
+    /// 数字格式不对，例如 "1.2.3"
+    InvalidNumber(String),
+    /// 不认识的字符
+    UnexpectedChar(char),
+    /// 表达式在需要操作数或右括号的地方结束了
+    UnexpectedEnd,
+    /// 出现了不该出现的 Token，例如 "1 + * 2"、"(1))"
+    UnexpectedToken(String),
+    DivisionByZero,
+    /// 超出 i128 能表示的范围
+    Overflow,
+}
+
+impl fmt::Display for CalcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CalcError::InvalidNumber(literal) => write!(f, "无效的数字 \"{}\"", literal),
+            CalcError::UnexpectedChar(c) => write!(f, "无法识别的字符 '{}'", c),
+            CalcError::UnexpectedEnd => write!(f, "表达式不完整"),
+            CalcError::UnexpectedToken(token) => write!(f, "意外的 \"{}\"", token),
+            CalcError::DivisionByZero => write!(f, "不能除以零"),
+            CalcError::Overflow => write!(f, "数值超出范围"),
+        }
+    }
+}
+
+impl std::error::Error for CalcError {}
+
+// ============================================================================
+// 第三部分：词法分析
+// ============================================================================
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Token {
+    Number(Decimal),
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    LeftParen,
+    RightParen,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Number(value) => write!(f, "{}", value),
+            Token::Plus => write!(f, "+"),
+            Token::Minus => write!(f, "-"),
+            Token::Star => write!(f, "×"),
+            Token::Slash => write!(f, "÷"),
+            Token::LeftParen => write!(f, "("),
+            Token::RightParen => write!(f, ")"),
+        }
+    }
+}
+
+/// 切分表达式，同时接受 ASCII 运算符和显示用的 × ÷ −
+pub fn tokenize(input: &str) -> Result<Vec<Token>, CalcError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '0'..='9' | '.' => {
+                let mut end = start + c.len_utf8();
+                while let Some(&(ix, next)) = chars.peek() {
+                    if !(next.is_ascii_digit() || next == '.') {
+                        break;
+                    }
+                    end = ix + next.len_utf8();
+                    chars.next();
+                }
+                Token::Number(Decimal::parse(&input[start..end])?)
+            }
+            '+' => Token::Plus,
+            '-' | '−' => Token::Minus,
+            '*' | '×' => Token::Star,
+            '/' | '÷' => Token::Slash,
+            '(' => Token::LeftParen,
+            ')' => Token::RightParen,
+            other => return Err(CalcError::UnexpectedChar(other)),
+        };
+        tokens.push(token);
+    }
+
+    Ok(tokens)
+}
+
+// ============================================================================
+// 第四部分：语法分析与求值
+// ============================================================================
+
+// 文法（优先级从低到高）：
+//   expr   = term (('+' | '-') term)*
+//   term   = unary (('*' | '/') unary)*
+//   unary  = ('+' | '-') unary | atom
+//   atom   = Number | '(' expr ')'
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn expr(&mut self) -> Result<Decimal, CalcError> {
+        let mut value = self.term()?;
+        loop {
+            match self.peek() {
+                Some(Token::Plus) => {
+                    self.next();
+                    value = value.add(self.term()?)?;
+                }
+                Some(Token::Minus) => {
+                    self.next();
+                    value = value.sub(self.term()?)?;
+                }
+                _ => return Ok(value),
+            }
+        }
+    }
+
+    fn term(&mut self) -> Result<Decimal, CalcError> {
+        let mut value = self.unary()?;
+        loop {
+            match self.peek() {
+                Some(Token::Star) => {
+                    self.next();
+                    value = value.mul(self.unary()?)?;
+                }
+                Some(Token::Slash) => {
+                    self.next();
+                    value = value.div(self.unary()?)?;
+                }
+                _ => return Ok(value),
+            }
+        }
+    }
+
+    fn unary(&mut self) -> Result<Decimal, CalcError> {
+        match self.peek() {
+            Some(Token::Minus) => {
+                self.next();
+                self.unary()?.neg()
+            }
+            Some(Token::Plus) => {
+                self.next();
+                self.unary()
+            }
+            _ => self.atom(),
+        }
+    }
+
+    fn atom(&mut self) -> Result<Decimal, CalcError> {
+        match self.next() {
+            Some(Token::Number(value)) => Ok(value),
+            Some(Token::LeftParen) => {
+                let value = self.expr()?;
+                match self.next() {
+                    Some(Token::RightParen) => Ok(value),
+                    Some(token) => Err(CalcError::UnexpectedToken(token.to_string())),
+                    None => Err(CalcError::UnexpectedEnd),
+                }
+            }
+            Some(token) => Err(CalcError::UnexpectedToken(token.to_string())),
+            None => Err(CalcError::UnexpectedEnd),
+        }
+    }
+}
+
+/// 计算表达式的值
+///
+/// ```ignore
+/// assert_eq!(evaluate("0.1 + 0.2")?.to_string(), "0.3");
+/// assert_eq!(evaluate("2 + 3 × 4")?.to_string(), "14");
+/// assert_eq!(evaluate("1 ÷ 0"), Err(CalcError::DivisionByZero));
+/// ```
+pub fn evaluate(input: &str) -> Result<Decimal, CalcError> {
+    let mut parser = Parser {
+        tokens: tokenize(input)?,
+        position: 0,
+    };
+    let value = parser.expr()?;
+    match parser.next() {
+        None => Ok(value),
+        Some(token) => Err(CalcError::UnexpectedToken(token.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{evaluate, tokenize, CalcError, Decimal, Token};
+
+    /// 计算并格式化结果
+    fn eval(input: &str) -> Result<String, CalcError> {
+        evaluate(input).map(|value| value.to_string())
+    }
+
+    #[test]
+    fn multiplication_and_division_bind_tighter() {
+        assert_eq!(eval("2 + 3 × 4"), Ok("14".into()));
+        assert_eq!(eval("2 × 3 + 4"), Ok("10".into()));
+        assert_eq!(eval("(2 + 3) × 4"), Ok("20".into()));
+        assert_eq!(eval("10 − 4 − 3"), Ok("3".into()));
+        assert_eq!(eval("24 ÷ 4 ÷ 2"), Ok("3".into()));
+        assert_eq!(eval("3 × −2"), Ok("-6".into()));
+        assert_eq!(eval("−(1 + 2) × +3"), Ok("-9".into()));
+    }
+
+    #[test]
+    fn decimals_are_exact() {
+        assert_eq!(eval("0.1 + 0.2"), Ok("0.3".into()));
+        assert_eq!(eval("0.3 − 0.1"), Ok("0.2".into()));
+        assert_eq!(eval("1.10 × 3"), Ok("3.3".into()));
+        assert_eq!(eval(".5 + 7."), Ok("7.5".into()));
+        // 除不尽时保留 12 位小数并四舍五入
+        assert_eq!(eval("1 ÷ 3"), Ok("0.333333333333".into()));
+        assert_eq!(eval("2 ÷ 3"), Ok("0.666666666667".into()));
+        assert_eq!(eval("−2 ÷ 3"), Ok("-0.666666666667".into()));
+        assert_eq!(eval("1 ÷ 0.001"), Ok("1000".into()));
+    }
+
+    #[test]
+    fn division_by_zero_is_an_error() {
+        assert_eq!(eval("1 ÷ 0"), Err(CalcError::DivisionByZero));
+        assert_eq!(eval("1 ÷ (2 − 2)"), Err(CalcError::DivisionByZero));
+        assert_eq!(eval("0 ÷ 0.0"), Err(CalcError::DivisionByZero));
+    }
+
+    #[test]
+    fn malformed_input_is_rejected() {
+        assert_eq!(eval("1.2.3"), Err(CalcError::InvalidNumber("1.2.3".into())));
+        assert_eq!(eval("."), Err(CalcError::InvalidNumber(".".into())));
+        assert_eq!(eval("2 ^ 3"), Err(CalcError::UnexpectedChar('^')));
+        assert_eq!(eval("1 +"), Err(CalcError::UnexpectedEnd));
+        assert_eq!(eval("(1 + 2"), Err(CalcError::UnexpectedEnd));
+        assert_eq!(eval(""), Err(CalcError::UnexpectedEnd));
+        assert_eq!(eval("1 + × 2"), Err(CalcError::UnexpectedToken("×".into())));
+        assert_eq!(eval("(1))"), Err(CalcError::UnexpectedToken(")".into())));
+        assert_eq!(eval("2 (3)"), Err(CalcError::UnexpectedToken("(".into())));
+        assert_eq!(
+            tokenize("1−2"),
+            Ok(vec![Token::Number(Decimal::parse("1").unwrap()), Token::Minus, Token::Number(Decimal::parse("2").unwrap())])
+        );
+    }
+
+    #[test]
+    fn overflow_is_an_error_not_a_panic() {
+        let max = i128::MAX.to_string();
+        assert_eq!(eval(&format!("{}0", max)), Err(CalcError::Overflow));
+        assert_eq!(eval(&format!("{} + 1", max)), Err(CalcError::Overflow));
+        assert_eq!(eval(&format!("{} × 2", max)), Err(CalcError::Overflow));
+        // i128::MIN 取负
+        assert_eq!(eval(&format!("−(−{} − 1)", max)), Err(CalcError::Overflow));
+        // 结果能表示时大数除法不报错
+        assert_eq!(eval("100000000000000000000000000 ÷ 4"), Ok("25000000000000000000000000".into()));
+        assert_eq!(eval(&format!("{} ÷ 1", max)), Ok(max.clone()));
+        assert_eq!(eval(&format!("{} ÷ 0.5", max)), Err(CalcError::Overflow));
+    }
+}
@@ -0,0 +1,444 @@
+// Calculator 示例 - 展示 Action 驱动的键盘与按钮输入
+//
+// - 计算逻辑在 engine 模块中（纯 Rust，不依赖 GPUI）
+// - 每个按键都是一个 Action：屏幕按钮通过 dispatch_action 分发，
+//   键盘（包括小键盘）通过 KeyBinding 绑定到同一批 Action
+// - 输入 0.1 + 0.2 得到 0.3：引擎使用十进制定点数而不是 f64
+
+mod engine;
+
+use engine::{CalcError, Decimal};
+use gpui::prelude::FluentBuilder;
+use gpui::*;
+use gpui_components::{ActiveTheme, ButtonSize, ButtonVariant, StyledButton, Theme};
+
+actions!(
+    calculator,
+    [
+        Digit0,
+        Digit1,
+        Digit2,
+        Digit3,
+        Digit4,
+        Digit5,
+        Digit6,
+        Digit7,
+        Digit8,
+        Digit9,
+        DecimalPoint,
+        Add,
+        Subtract,
+        Multiply,
+        Divide,
+        LeftParen,
+        RightParen,
+        Evaluate,
+        Backspace,
+        Clear,
+    ]
+);
+
+/// 快捷键只在计算器获得焦点时生效
+const KEY_CONTEXT: &str = "Calculator";
+
+/// 小键盘按键的 key_char 与主键盘相同（"1"、"+"、"*"...），
+/// 所以同一个绑定同时覆盖两者
+fn bind_keys(cx: &mut App) {
+    cx.bind_keys([
+        KeyBinding::new("0", Digit0, Some(KEY_CONTEXT)),
+        KeyBinding::new("1", Digit1, Some(KEY_CONTEXT)),
+        KeyBinding::new("2", Digit2, Some(KEY_CONTEXT)),
+        KeyBinding::new("3", Digit3, Some(KEY_CONTEXT)),
+        KeyBinding::new("4", Digit4, Some(KEY_CONTEXT)),
+        KeyBinding::new("5", Digit5, Some(KEY_CONTEXT)),
+        KeyBinding::new("6", Digit6, Some(KEY_CONTEXT)),
+        KeyBinding::new("7", Digit7, Some(KEY_CONTEXT)),
+        KeyBinding::new("8", Digit8, Some(KEY_CONTEXT)),
+        KeyBinding::new("9", Digit9, Some(KEY_CONTEXT)),
+        KeyBinding::new(".", DecimalPoint, Some(KEY_CONTEXT)),
+        KeyBinding::new(",", DecimalPoint, Some(KEY_CONTEXT)),
+        KeyBinding::new("+", Add, Some(KEY_CONTEXT)),
+        KeyBinding::new("-", Subtract, Some(KEY_CONTEXT)),
+        KeyBinding::new("*", Multiply, Some(KEY_CONTEXT)),
+        KeyBinding::new("x", Multiply, Some(KEY_CONTEXT)),
+        KeyBinding::new("/", Divide, Some(KEY_CONTEXT)),
+        KeyBinding::new("(", LeftParen, Some(KEY_CONTEXT)),
+        KeyBinding::new(")", RightParen, Some(KEY_CONTEXT)),
+        KeyBinding::new("enter", Evaluate, Some(KEY_CONTEXT)),
+        KeyBinding::new("=", Evaluate, Some(KEY_CONTEXT)),
+        KeyBinding::new("backspace", Backspace, Some(KEY_CONTEXT)),
+        KeyBinding::new("escape", Clear, Some(KEY_CONTEXT)),
+        KeyBinding::new("delete", Clear, Some(KEY_CONTEXT)),
+    ]);
+}
+
+/// 显示用的运算符，引擎同样能识别
+const OPERATORS: [char; 4] = ['+', '−', '×', '÷'];
+
+struct Calculator {
+    focus_handle: FocusHandle,
+    /// 正在输入的算式，例如 "12.5 × (3 − 1)" 去掉空格后的形式
+    expression: String,
+    /// 按下 = 之后的结果：(算式, 结果)，再次输入时清除
+    result: Option<(String, Result<Decimal, CalcError>)>,
+}
+
+impl Calculator {
+    fn new(cx: &mut Context<Self>) -> Self {
+        Self {
+            focus_handle: cx.focus_handle(),
+            expression: String::new(),
+            result: None,
+        }
+    }
+
+    /// 开始新的输入：上一次成功的结果被丢弃，失败时保留算式方便修改
+    fn start_editing(&mut self) {
+        if let Some((_, Ok(_))) = self.result.take() {
+            self.expression.clear();
+        }
+    }
+
+    /// 当前正在输入的数字（最后一个运算符或括号之后的部分）
+    fn current_number(&self) -> &str {
+        let start = self
+            .expression
+            .rfind(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .map(|ix| ix + self.expression[ix..].chars().next().map_or(1, char::len_utf8))
+            .unwrap_or(0);
+        &self.expression[start..]
+    }
+
+    fn push_digit(&mut self, digit: char, cx: &mut Context<Self>) {
+        self.start_editing();
+        // 避免 "007"：当前数字只有一个 0 时直接替换
+        if self.current_number() == "0" {
+            self.expression.pop();
+        }
+        self.expression.push(digit);
+        cx.notify();
+    }
+
+    fn push_decimal_point(&mut self, cx: &mut Context<Self>) {
+        self.start_editing();
+        let number = self.current_number();
+        if number.contains('.') {
+            return;
+        }
+        if number.is_empty() {
+            self.expression.push('0');
+        }
+        self.expression.push('.');
+        cx.notify();
+    }
+
+    fn push_operator(&mut self, operator: char, cx: &mut Context<Self>) {
+        // 在上一次结果的基础上继续计算
+        if let Some((_, Ok(value))) = self.result.take() {
+            self.expression = value.to_string().replace('-', "−");
+        }
+
+        // 连续输入运算符时替换末尾的整串运算符（"3×−" 再按 + 得到 "3+"）；
+        // × ÷ 之后允许负号（3 × −2），所以按 − 时保留它们
+        let operators_start = self.expression.trim_end_matches(OPERATORS).len();
+        let pending = self.expression.split_off(operators_start);
+        if operator == '−'
+            && let Some(mul_div) = pending.chars().next().filter(|c| *c == '×' || *c == '÷')
+        {
+            self.expression.push(mul_div);
+        }
+
+        // 开头或左括号后只允许负号
+        if operator != '−' && matches!(self.expression.chars().last(), None | Some('(')) {
+            cx.notify();
+            return;
+        }
+        self.expression.push(operator);
+        cx.notify();
+    }
+
+    fn push_paren(&mut self, paren: char, cx: &mut Context<Self>) {
+        self.start_editing();
+        self.expression.push(paren);
+        cx.notify();
+    }
+
+    fn backspace(&mut self, _: &Backspace, _: &mut Window, cx: &mut Context<Self>) {
+        self.start_editing();
+        self.expression.pop();
+        cx.notify();
+    }
+
+    fn clear(&mut self, _: &Clear, _: &mut Window, cx: &mut Context<Self>) {
+        self.expression.clear();
+        self.result = None;
+        cx.notify();
+    }
+
+    fn evaluate(&mut self, _: &Evaluate, _: &mut Window, cx: &mut Context<Self>) {
+        if self.expression.is_empty() {
+            return;
+        }
+        let result = engine::evaluate(&self.expression);
+        self.result = Some((self.expression.clone(), result));
+        cx.notify();
+    }
+
+    /// 输入过程中的实时预览，算式不完整时不显示
+    fn preview(&self) -> Option<Decimal> {
+        if self.result.is_some() {
+            return None;
+        }
+        engine::evaluate(&self.expression).ok()
+    }
+}
+
+/// 计算器按钮：点击时分发对应的 Action，与键盘走同一条处理路径
+fn calc_button(
+    id: &'static str,
+    label: &'static str,
+    action: impl Action,
+    variant: ButtonVariant,
+) -> StyledButton {
+    StyledButton::new(id, label)
+        .variant(variant)
+        .size(ButtonSize::Large)
+        .flex_1()
+        .h(px(56.0))
+        .text_xl()
+        .on_click(move |_, window, cx| window.dispatch_action(action.boxed_clone(), cx))
+}
+
+impl Focusable for Calculator {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for Calculator {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = cx.theme().clone();
+
+        // 显示区：上面一行是算式/预览，下面一行是主显示
+        let (secondary, primary, error) = match &self.result {
+            Some((expression, Ok(value))) => (
+                format!("{} =", expression),
+                value.to_string(),
+                None,
+            ),
+            Some((expression, Err(error))) => {
+                (String::new(), expression.clone(), Some(error.to_string()))
+            }
+            None => (
+                self.preview()
+                    .map(|value| format!("= {}", value))
+                    .unwrap_or_default(),
+                if self.expression.is_empty() {
+                    "0".to_string()
+                } else {
+                    self.expression.clone()
+                },
+                None,
+            ),
+        };
+
+        let number = ButtonVariant::Secondary;
+        let operator = ButtonVariant::Outline;
+
+        div()
+            .key_context(KEY_CONTEXT)
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(|this, _: &Digit0, _, cx| this.push_digit('0', cx)))
+            .on_action(cx.listener(|this, _: &Digit1, _, cx| this.push_digit('1', cx)))
+            .on_action(cx.listener(|this, _: &Digit2, _, cx| this.push_digit('2', cx)))
+            .on_action(cx.listener(|this, _: &Digit3, _, cx| this.push_digit('3', cx)))
+            .on_action(cx.listener(|this, _: &Digit4, _, cx| this.push_digit('4', cx)))
+            .on_action(cx.listener(|this, _: &Digit5, _, cx| this.push_digit('5', cx)))
+            .on_action(cx.listener(|this, _: &Digit6, _, cx| this.push_digit('6', cx)))
+            .on_action(cx.listener(|this, _: &Digit7, _, cx| this.push_digit('7', cx)))
+            .on_action(cx.listener(|this, _: &Digit8, _, cx| this.push_digit('8', cx)))
+            .on_action(cx.listener(|this, _: &Digit9, _, cx| this.push_digit('9', cx)))
+            .on_action(cx.listener(|this, _: &DecimalPoint, _, cx| this.push_decimal_point(cx)))
+            .on_action(cx.listener(|this, _: &Add, _, cx| this.push_operator('+', cx)))
+            .on_action(cx.listener(|this, _: &Subtract, _, cx| this.push_operator('−', cx)))
+            .on_action(cx.listener(|this, _: &Multiply, _, cx| this.push_operator('×', cx)))
+            .on_action(cx.listener(|this, _: &Divide, _, cx| this.push_operator('÷', cx)))
+            .on_action(cx.listener(|this, _: &LeftParen, _, cx| this.push_paren('(', cx)))
+            .on_action(cx.listener(|this, _: &RightParen, _, cx| this.push_paren(')', cx)))
+            .on_action(cx.listener(Self::evaluate))
+            .on_action(cx.listener(Self::backspace))
+            .on_action(cx.listener(Self::clear))
+            // 点击任意位置（包括按钮）都让计算器保持焦点，键盘输入不会丢失
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|this, _, window, _| this.focus_handle.focus(window)),
+            )
+            .size_full()
+            .flex()
+            .flex_col()
+            .gap(theme.spacing.md)
+            .p(theme.spacing.lg)
+            .bg(theme.background)
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .items_end()
+                    .gap(theme.spacing.xs)
+                    .p(theme.spacing.md)
+                    .rounded(theme.radius.lg)
+                    .bg(theme.surface)
+                    .border_1()
+                    .border_color(theme.border)
+                    .child(
+                        div()
+                            .h(px(20.0))
+                            .text_sm()
+                            .text_color(theme.subtext)
+                            .child(secondary),
+                    )
+                    .child(
+                        div()
+                            .text_3xl()
+                            .font_weight(FontWeight::BOLD)
+                            .text_color(theme.text)
+                            .child(primary),
+                    )
+                    .when_some(error, |this, error| {
+                        this.child(
+                            div()
+                                .text_sm()
+                                .text_color(theme.danger)
+                                .child(format!("⚠️ {}", error)),
+                        )
+                    }),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap(theme.spacing.sm)
+                    .child(
+                        div()
+                            .flex()
+                            .gap(theme.spacing.sm)
+                            .child(calc_button("clear", "C", Clear, ButtonVariant::Danger))
+                            .child(calc_button("lparen", "(", LeftParen, operator))
+                            .child(calc_button("rparen", ")", RightParen, operator))
+                            .child(calc_button("divide", "÷", Divide, operator)),
+                    )
+                    .child(
+                        div()
+                            .flex()
+                            .gap(theme.spacing.sm)
+                            .child(calc_button("7", "7", Digit7, number))
+                            .child(calc_button("8", "8", Digit8, number))
+                            .child(calc_button("9", "9", Digit9, number))
+                            .child(calc_button("multiply", "×", Multiply, operator)),
+                    )
+                    .child(
+                        div()
+                            .flex()
+                            .gap(theme.spacing.sm)
+                            .child(calc_button("4", "4", Digit4, number))
+                            .child(calc_button("5", "5", Digit5, number))
+                            .child(calc_button("6", "6", Digit6, number))
+                            .child(calc_button("subtract", "−", Subtract, operator)),
+                    )
+                    .child(
+                        div()
+                            .flex()
+                            .gap(theme.spacing.sm)
+                            .child(calc_button("1", "1", Digit1, number))
+                            .child(calc_button("2", "2", Digit2, number))
+                            .child(calc_button("3", "3", Digit3, number))
+                            .child(calc_button("add", "+", Add, operator)),
+                    )
+                    .child(
+                        div()
+                            .flex()
+                            .gap(theme.spacing.sm)
+                            .child(calc_button("backspace", "⌫", Backspace, ButtonVariant::Ghost))
+                            .child(calc_button("0", "0", Digit0, number))
+                            .child(calc_button("point", ".", DecimalPoint, number))
+                            .child(calc_button("evaluate", "=", Evaluate, ButtonVariant::Primary)),
+                    ),
+            )
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(theme.subtext)
+                    .child("⌨️ 数字键/小键盘输入，Enter 或 = 计算，Backspace 回退，Esc 清除"),
+            )
+    }
+}
+
+fn main() {
+    Application::new().run(|cx| {
+        Theme::init(cx);
+        bind_keys(cx);
+
+        cx.open_window(
+            WindowOptions {
+                window_bounds: Some(WindowBounds::Windowed(Bounds {
+                    origin: Point {
+                        x: px(300.0),
+                        y: px(200.0),
+                    },
+                    size: Size {
+                        width: px(360.0),
+                        height: px(560.0),
+                    },
+                })),
+                titlebar: Some(TitlebarOptions {
+                    title: Some("Calculator".into()),
+                    appears_transparent: false,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            |window, cx| {
+                let view = cx.new(Calculator::new);
+                view.read(cx).focus_handle.focus(window);
+                view
+            },
+        )
+        .unwrap();
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    // 不能 use super::*：gpui::* 中的 test 宏会遮蔽内置的 #[test]
+    use super::Calculator;
+    use gpui::{AppContext, TestAppContext};
+
+    /// 依次输入 keys（数字和运算符），返回算式
+    fn type_keys(keys: &str, cx: &mut TestAppContext) -> String {
+        let calculator = cx.new(Calculator::new);
+        calculator.update(cx, |calculator, cx| {
+            for key in keys.chars() {
+                match key {
+                    '0'..='9' => calculator.push_digit(key, cx),
+                    _ => calculator.push_operator(key, cx),
+                }
+            }
+            calculator.expression.clone()
+        })
+    }
+
+    #[gpui::test]
+    fn operator_replaces_the_pending_operators(cx: &mut TestAppContext) {
+        assert_eq!(type_keys("3+×", cx), "3×");
+        assert_eq!(type_keys("3×−", cx), "3×−");
+        // 替换整串 "×−"，而不是只替换最后的负号
+        assert_eq!(type_keys("3×−+", cx), "3+");
+        assert_eq!(type_keys("3×−÷", cx), "3÷");
+        assert_eq!(type_keys("3×−−", cx), "3×−");
+        assert_eq!(type_keys("3+−", cx), "3−");
+        // 开头只允许负号
+        assert_eq!(type_keys("×", cx), "");
+        assert_eq!(type_keys("−×", cx), "");
+        assert_eq!(type_keys("−2", cx), "−2");
+    }
+}
@@ -1,9 +1,53 @@
 // Counter 示例 - 展示状态管理和事件处理
+// 计数保存在 Store 中：按钮只发送消息，新状态由纯函数 reduce 计算
 
 use gpui::*;
+use gpui_components::{ButtonSize, ButtonVariant, Store, StyledButton};
+
+#[derive(Clone, Default, PartialEq)]
+struct CounterState {
+    count: i32,
+}
+
+enum CounterMsg {
+    Increment,
+    Decrement,
+    Reset,
+}
+
+fn reduce(state: &CounterState, msg: &CounterMsg) -> CounterState {
+    let count = match msg {
+        CounterMsg::Increment => state.count + 1,
+        CounterMsg::Decrement => state.count - 1,
+        CounterMsg::Reset => 0,
+    };
+    CounterState { count }
+}
 
 struct CounterView {
+    store: Entity<Store<CounterState, CounterMsg>>,
+    /// 从 Store 中选出的计数，变化时由订阅更新
     count: i32,
+    _subscription: Subscription,
+}
+
+impl CounterView {
+    fn new(cx: &mut Context<Self>) -> Self {
+        let store = cx.new(|_| Store::new(CounterState::default(), reduce));
+        let (count, subscription) = Store::select(&store, cx, |state| state.count, |view: &mut Self, count, cx| {
+            view.count = *count;
+            cx.notify();
+        });
+        Self {
+            store,
+            count,
+            _subscription: subscription,
+        }
+    }
+
+    fn dispatch(&mut self, msg: CounterMsg, cx: &mut Context<Self>) {
+        self.store.update(cx, |store, cx| store.dispatch(msg, cx));
+    }
 }
 
 impl Render for CounterView {
@@ -17,24 +61,15 @@ impl Render for CounterView {
             .bg(rgb(0xF5F5F5))
             .child(
                 // 减少按钮
-                div()
-                    .id("decrement")
-                    .flex()
-                    .items_center()
-                    .justify_center()
+                StyledButton::new("decrement", "-")
+                    .variant(ButtonVariant::Danger)
+                    .size(ButtonSize::Large)
                     .w(px(48.0))
                     .h(px(48.0))
-                    .bg(rgb(0xEF4444))
-                    .text_color(rgb(0xFFFFFF))
                     .text_xl()
                     .rounded_lg()
-                    .cursor_pointer()
-                    .hover(|style| style.bg(rgb(0xDC2626)))
-                    .active(|style| style.bg(rgb(0xB91C1C)))
-                    .child("-")
                     .on_click(cx.listener(|view, _event, _window, cx| {
-                        view.count -= 1;
-                        cx.notify();
+                        view.dispatch(CounterMsg::Decrement, cx);
                     })),
             )
             .child(
@@ -57,46 +92,25 @@ impl Render for CounterView {
             )
             .child(
                 // 增加按钮
-                div()
-                    .id("increment")
-                    .flex()
-                    .items_center()
-                    .justify_center()
+                StyledButton::new("increment", "+")
+                    .variant(ButtonVariant::Success)
+                    .size(ButtonSize::Large)
                     .w(px(48.0))
                     .h(px(48.0))
-                    .bg(rgb(0x10B981))
-                    .text_color(rgb(0xFFFFFF))
                     .text_xl()
                     .rounded_lg()
-                    .cursor_pointer()
-                    .hover(|style| style.bg(rgb(0x059669)))
-                    .active(|style| style.bg(rgb(0x047857)))
-                    .child("+")
                     .on_click(cx.listener(|view, _event, _window, cx| {
-                        view.count += 1;
-                        cx.notify();
+                        view.dispatch(CounterMsg::Increment, cx);
                     })),
             )
             .child(
                 // 重置按钮
-                div()
-                    .id("reset")
-                    .flex()
-                    .items_center()
-                    .justify_center()
-                    .px_4()
+                StyledButton::new("reset", "Reset")
+                    .variant(ButtonVariant::Secondary)
                     .h(px(36.0))
                     .ml_4()
-                    .bg(rgb(0x6B7280))
-                    .text_color(rgb(0xFFFFFF))
-                    .rounded_md()
-                    .cursor_pointer()
-                    .hover(|style| style.bg(rgb(0x4B5563)))
-                    .active(|style| style.bg(rgb(0x374151)))
-                    .child("Reset")
                     .on_click(cx.listener(|view, _event, _window, cx| {
-                        view.count = 0;
-                        cx.notify();
+                        view.dispatch(CounterMsg::Reset, cx);
                     })),
             )
     }
@@ -123,7 +137,7 @@ fn main() {
                 }),
                 ..Default::default()
             },
-            |_window, cx| cx.new(|_cx| CounterView { count: 0 }),
+            |_window, cx| cx.new(CounterView::new),
         )
         .unwrap();
     });
@@ -0,0 +1,164 @@
+// 笔记示例 - 展示多行编辑器 TextArea
+//
+// - 软换行：调整窗口宽度，长行会自动折行
+// - 行号栏：工具栏开关
+// - 撤销/重做：Ctrl+Z / Ctrl+Shift+Z（macOS 上是 Cmd），工具栏按钮通过分发 Action 触发
+// - 内容超出高度后垂直滚动，光标移动时自动滚动到可见区域
+
+use gpui::*;
+use gpui_components::text_area::{self, Redo, Undo};
+use gpui_components::{ActiveTheme, ButtonVariant, StyledButton, TextArea, TextAreaEvent, Theme};
+
+const SAMPLE: &str = "# GPUI 学习笔记
+
+TextArea 是共享组件库里的多行编辑器，按行存储文本，编辑时只替换受影响的行。这一段故意写得很长，用来演示软换行：缩小窗口宽度，文字会按可用宽度自动折行，上下方向键按视觉行移动。
+
+快捷键：
+    Enter        换行
+    Tab          插入四个空格
+    Ctrl+Z       撤销（一秒内的连续输入合并为一步）
+    Ctrl+Shift+Z 重做
+";
+
+struct NotesApp {
+    editor: Entity<TextArea>,
+    _subscription: Subscription,
+}
+
+impl NotesApp {
+    fn new(cx: &mut Context<Self>) -> Self {
+        let editor = cx.new(|cx| {
+            let mut editor = TextArea::new(cx)
+                .placeholder("写点什么...")
+                .line_numbers(true);
+            editor.set_text(SAMPLE, cx);
+            editor
+        });
+        // 内容变化时刷新状态栏
+        let subscription = cx.subscribe(&editor, |_this, _editor, event, cx| match event {
+            TextAreaEvent::Changed => cx.notify(),
+        });
+
+        Self {
+            editor,
+            _subscription: subscription,
+        }
+    }
+
+    /// 先把焦点还给编辑器，再分发 Action，由编辑器的 on_action 处理
+    fn dispatch_to_editor(&self, action: Box<dyn Action>, window: &mut Window, cx: &mut App) {
+        self.editor.read(cx).focus(window);
+        window.dispatch_action(action, cx);
+    }
+}
+
+impl Render for NotesApp {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = cx.theme().clone();
+        let editor = self.editor.read(cx);
+        let text = editor.text();
+        let show_line_numbers = editor.shows_line_numbers();
+        let (can_undo, can_redo) = (editor.can_undo(), editor.can_redo());
+        let line_count = editor.line_count();
+
+        div()
+            .size_full()
+            .flex()
+            .flex_col()
+            .gap(theme.spacing.md)
+            .p(theme.spacing.lg)
+            .bg(theme.background)
+            .text_color(theme.text)
+            .child(
+                // 工具栏
+                div()
+                    .flex()
+                    .items_center()
+                    .gap(theme.spacing.sm)
+                    .child(
+                        StyledButton::new(
+                            "toggle-line-numbers",
+                            if show_line_numbers { "隐藏行号" } else { "显示行号" },
+                        )
+                        .variant(ButtonVariant::Outline)
+                        .on_click(cx.listener(|this, _, _, cx| {
+                            this.editor.update(cx, |editor, cx| {
+                                let show = !editor.shows_line_numbers();
+                                editor.set_line_numbers(show, cx);
+                            });
+                        })),
+                    )
+                    .child(
+                        StyledButton::new("undo", "↶ 撤销")
+                            .variant(ButtonVariant::Secondary)
+                            .disabled(!can_undo)
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.dispatch_to_editor(Box::new(Undo), window, cx);
+                            })),
+                    )
+                    .child(
+                        StyledButton::new("redo", "↷ 重做")
+                            .variant(ButtonVariant::Secondary)
+                            .disabled(!can_redo)
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.dispatch_to_editor(Box::new(Redo), window, cx);
+                            })),
+                    )
+                    .child(
+                        StyledButton::new("clear", "清空")
+                            .variant(ButtonVariant::Ghost)
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.editor.update(cx, |editor, cx| editor.set_text("", cx));
+                                this.editor.read(cx).focus(window);
+                            })),
+                    ),
+            )
+            // 编辑器放在固定高度（flex_1）的容器中，超出部分滚动
+            .child(div().flex_1().min_h_0().child(self.editor.clone()))
+            .child(
+                // 状态栏
+                div()
+                    .text_xs()
+                    .text_color(theme.subtext)
+                    .child(format!(
+                        "{} 行 · {} 个字符",
+                        line_count,
+                        text.chars().count()
+                    )),
+            )
+    }
+}
+
+fn main() {
+    Application::new().run(|cx| {
+        Theme::init(cx);
+        text_area::init(cx);
+
+        cx.open_window(
+            WindowOptions {
+                window_bounds: Some(WindowBounds::Windowed(Bounds {
+                    origin: Point {
+                        x: px(300.0),
+                        y: px(200.0),
+                    },
+                    size: Size {
+                        width: px(720.0),
+                        height: px(560.0),
+                    },
+                })),
+                titlebar: Some(TitlebarOptions {
+                    title: Some("Notes".into()),
+                    appears_transparent: false,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            |window, cx| {
+                let view = cx.new(NotesApp::new);
+                view.read(cx).editor.read(cx).focus(window);
+                view
+            },
+        )
+        .unwrap();
+    });
+}
@@ -1,51 +1,902 @@
-// Todo List 示例 - 综合应用（占位）
-// 本示例将在学习完前几章后实现
+// Todo List 示例 - 综合应用
+// =========================
+// 把前几章的知识串起来的完整小应用：
+// 1. 状态管理：每个任务是一个 Entity<TodoItem>，TodoApp 持有列表（第三章）
+// 2. 子传父：TodoItem 通过 EventEmitter 发出 TodoItemEvent（第三章）
+// 3. 样式系统：StyledButton + 全局 Theme（第四章、第六章）
+// 4. 事件处理：点击、双击编辑、TextInput 文本输入（第五章）
+// 5. Action 系统：actions! + KeyBinding 绑定快捷键，key_context 区分作用域（第六章）
+// 6. 异步持久化：启动时后台加载，修改后防抖保存到用户数据目录（第六章）
+//
+// 快捷键：
+// - 输入框：Enter 添加，Esc 清空，↓ 跳到列表
+// - 列表：↑/↓ 选择，Space 切换完成，Enter 编辑，Delete 删除，Esc 回到输入框
+// - 全局：Ctrl-1/2/3 切换 全部/未完成/已完成
 
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use gpui::prelude::FluentBuilder;
 use gpui::*;
+use gpui_components::{
+    ActiveTheme, ButtonSize, ButtonVariant, StyledButton, TextInput, TextInputEvent, Theme,
+};
+use serde::{Deserialize, Serialize};
+
+// ============================================================================
+// 第一部分：Action 定义与快捷键
+// ============================================================================
+
+actions!(
+    todo,
+    [
+        Cancel,          // 取消输入（清空输入框 / 放弃编辑）
+        FocusList,       // 从输入框跳到列表
+        FocusInput,      // 从列表回到输入框
+        SelectPrev,      // 选中上一项
+        SelectNext,      // 选中下一项
+        ToggleSelected,  // 切换选中项的完成状态
+        EditSelected,    // 编辑选中项
+        DeleteSelected,  // 删除选中项
+        ShowAll,         // 过滤：全部
+        ShowActive,      // 过滤：未完成
+        ShowCompleted,   // 过滤：已完成
+    ]
+);
+
+/// 注册快捷键
+///
+/// 第三个参数是 key_context 谓词：只有焦点位于对应 key_context 的元素内部时绑定才生效。
+/// 编辑中的任务也在列表里，用 `!TodoInput` 排除，避免空格被 ToggleSelected 吃掉。
+/// Enter/退格/方向键等文字编辑快捷键由 TextInput 自己注册（context 为 "TextInput"）。
+fn bind_keys(cx: &mut App) {
+    gpui_components::text_input::init(cx);
+
+    cx.bind_keys([
+        KeyBinding::new("escape", Cancel, Some("TodoInput")),
+        KeyBinding::new("down", FocusList, Some("TodoInput && !TodoList")),
+        KeyBinding::new("up", SelectPrev, Some("TodoList && !TodoInput")),
+        KeyBinding::new("down", SelectNext, Some("TodoList && !TodoInput")),
+        KeyBinding::new("space", ToggleSelected, Some("TodoList && !TodoInput")),
+        KeyBinding::new("enter", EditSelected, Some("TodoList && !TodoInput")),
+        KeyBinding::new("delete", DeleteSelected, Some("TodoList && !TodoInput")),
+        KeyBinding::new("escape", FocusInput, Some("TodoList && !TodoInput")),
+        KeyBinding::new("ctrl-1", ShowAll, Some("TodoApp")),
+        KeyBinding::new("ctrl-2", ShowActive, Some("TodoApp")),
+        KeyBinding::new("ctrl-3", ShowCompleted, Some("TodoApp")),
+    ]);
+}
+
+// ============================================================================
+// 第二部分：TodoItem - 单个任务
+// ============================================================================
+
+/// 任务发出的事件 - TodoApp 订阅后更新统计、删除任务
+#[derive(Clone, Debug)]
+enum TodoItemEvent {
+    Toggled { id: usize, completed: bool },
+    Edited { id: usize, title: SharedString },
+    Deleted { id: usize },
+}
+
+impl EventEmitter<TodoItemEvent> for TodoItem {}
+
+struct TodoItem {
+    id: usize,
+    title: SharedString,
+    completed: bool,
+    /// 编辑中的输入框及其事件订阅，None 表示不在编辑状态
+    editor: Option<(Entity<TextInput>, Subscription)>,
+}
+
+impl TodoItem {
+    fn new(id: usize, title: impl Into<SharedString>, completed: bool) -> Self {
+        Self {
+            id,
+            title: title.into(),
+            completed,
+            editor: None,
+        }
+    }
+
+    fn toggle(&mut self, cx: &mut Context<Self>) {
+        self.completed = !self.completed;
+        cx.emit(TodoItemEvent::Toggled {
+            id: self.id,
+            completed: self.completed,
+        });
+        cx.notify();
+    }
+
+    fn delete(&mut self, cx: &mut Context<Self>) {
+        cx.emit(TodoItemEvent::Deleted { id: self.id });
+    }
+
+    fn start_editing(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let title = self.title.clone();
+        let editor = cx.new(|cx| {
+            let mut input = TextInput::new(cx);
+            input.set_text(title, cx);
+            input
+        });
+        let subscription = cx.subscribe(&editor, |view, _editor, event: &TextInputEvent, cx| {
+            if let TextInputEvent::Submitted(text) = event {
+                view.finish_editing(text.clone(), cx);
+            }
+        });
+        editor.read(cx).focus(window);
+        self.editor = Some((editor, subscription));
+        cx.notify();
+    }
+
+    /// 保存编辑：内容为空时视为删除
+    fn finish_editing(&mut self, text: SharedString, cx: &mut Context<Self>) {
+        self.editor = None;
+        let title = text.trim();
+        if title.is_empty() {
+            self.delete(cx);
+        } else if title != self.title.as_ref() {
+            self.title = SharedString::from(title.to_string());
+            cx.emit(TodoItemEvent::Edited {
+                id: self.id,
+                title: self.title.clone(),
+            });
+        }
+        cx.notify();
+    }
+
+    fn cancel(&mut self, _: &Cancel, _window: &mut Window, cx: &mut Context<Self>) {
+        self.editor = None;
+        cx.notify();
+    }
+}
+
+impl Render for TodoItem {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = cx.theme().clone();
+        let id = self.id;
+
+        // 编辑状态：显示输入框，Enter 保存，Esc 放弃
+        if let Some((editor, _)) = &self.editor {
+            return div()
+                .key_context("TodoInput")
+                .on_action(cx.listener(Self::cancel))
+                .child(editor.clone())
+                .into_any_element();
+        }
+
+        div()
+            .id(("todo", id))
+            .flex()
+            .items_center()
+            .gap(theme.spacing.md)
+            .px(theme.spacing.md)
+            .py(theme.spacing.sm)
+            .rounded(theme.radius.md)
+            .hover(|s| s.bg(theme.surface_muted))
+            // 复选框
+            .child(
+                div()
+                    .id(("todo-check", id))
+                    .flex()
+                    .items_center()
+                    .justify_center()
+                    .size(px(20.0))
+                    .rounded(theme.radius.sm)
+                    .border_1()
+                    .border_color(if self.completed { theme.success } else { theme.border })
+                    .when(self.completed, |el| {
+                        el.bg(theme.success)
+                            .text_color(theme.accent_foreground)
+                            .text_xs()
+                            .child("✓")
+                    })
+                    .cursor_pointer()
+                    .on_click(cx.listener(|view, _event, _window, cx| view.toggle(cx))),
+            )
+            // 标题 - 双击进入编辑
+            .child(
+                div()
+                    .id(("todo-title", id))
+                    .flex_1()
+                    .text_color(if self.completed { theme.subtext } else { theme.text })
+                    .when(self.completed, |el| el.line_through())
+                    .child(self.title.clone())
+                    .on_click(cx.listener(|view, event: &ClickEvent, window, cx| {
+                        if event.click_count() >= 2 {
+                            view.start_editing(window, cx);
+                        }
+                    })),
+            )
+            // 删除按钮
+            .child(
+                StyledButton::new(("todo-delete", id), "✕")
+                    .variant(ButtonVariant::Ghost)
+                    .size(ButtonSize::Small)
+                    .on_click(cx.listener(|view, _event, _window, cx| view.delete(cx))),
+            )
+            .into_any_element()
+    }
+}
+
+// ============================================================================
+// 第三部分：持久化 - 读写 JSON 文件
+// ============================================================================
+//
+// 这些函数都是同步的纯 I/O，由 TodoApp 放到 cx.background_executor() 上执行，
+// 不会阻塞 UI 线程。
+
+/// 保存修改前等待的时间：连续修改只会触发一次写盘
+const SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// 写入磁盘的任务格式
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct StoredTodo {
+    id: usize,
+    title: String,
+    completed: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredTodos {
+    todos: Vec<StoredTodo>,
+}
+
+/// 启动时读取数据文件的结果
+enum LoadOutcome {
+    /// 第一次运行，还没有数据文件
+    Missing,
+    Loaded(Vec<StoredTodo>),
+    /// 文件内容无法解析，已经备份到 backup
+    Corrupted { backup: PathBuf, error: String },
+}
+
+/// 数据文件路径：<用户数据目录>/gpui-study/todos.json
+///
+/// Linux 上是 ~/.local/share，macOS 上是 ~/Library/Application Support，
+/// Windows 上是 %APPDATA%。
+fn data_file() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("gpui-study")
+        .join("todos.json")
+}
+
+fn load_todos(path: &Path) -> Result<LoadOutcome, String> {
+    let source = match std::fs::read_to_string(path) {
+        Ok(source) => source,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(LoadOutcome::Missing),
+        Err(err) => return Err(format!("读取 {} 失败: {}", path.display(), err)),
+    };
+
+    match serde_json::from_str::<StoredTodos>(&source) {
+        Ok(stored) => Ok(LoadOutcome::Loaded(stored.todos)),
+        Err(parse_error) => {
+            // 把损坏的文件改名保存，而不是直接覆盖，方便用户手动恢复
+            let stamp = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default();
+            let backup = path.with_extension(format!("json.corrupt-{}", stamp));
+            std::fs::rename(path, &backup)
+                .map_err(|err| format!("备份损坏的数据文件失败: {}", err))?;
+            Ok(LoadOutcome::Corrupted {
+                backup,
+                error: parse_error.to_string(),
+            })
+        }
+    }
+}
+
+/// 先写临时文件再重命名，避免写到一半退出时留下半个文件
+fn save_todos(path: &Path, todos: &StoredTodos) -> Result<(), String> {
+    let write = || -> std::io::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let json = serde_json::to_string_pretty(todos)?;
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, json)?;
+        std::fs::rename(&tmp, path)
+    };
+    write().map_err(|err| format!("保存到 {} 失败: {}", path.display(), err))
+}
+
+// ============================================================================
+// 第四部分：TodoApp - 任务列表
+// ============================================================================
+
+/// 过滤条件
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Filter {
+    All,
+    Active,
+    Completed,
+}
+
+impl Filter {
+    fn label(self) -> &'static str {
+        match self {
+            Filter::All => "全部",
+            Filter::Active => "未完成",
+            Filter::Completed => "已完成",
+        }
+    }
+
+    fn matches(self, completed: bool) -> bool {
+        match self {
+            Filter::All => true,
+            Filter::Active => !completed,
+            Filter::Completed => completed,
+        }
+    }
+}
+
+/// 列表中的一项：任务实体 + 对它的事件订阅
+///
+/// Subscription 被 drop 时自动取消订阅，所以删除任务时一起移除即可。
+struct TodoEntry {
+    id: usize,
+    item: Entity<TodoItem>,
+    _subscription: Subscription,
+}
+
+struct TodoApp {
+    todos: Vec<TodoEntry>,
+    next_id: usize,
+    /// 新任务输入框
+    input: Entity<TextInput>,
+    filter: Filter,
+    /// 键盘选中的任务 id
+    selected: Option<usize>,
+    /// 最近一次操作的提示（来自 TodoItemEvent 或读写文件的结果）
+    status: Option<Notice>,
+    list_focus: FocusHandle,
+    storage: Storage,
+    /// 有还没写入磁盘的修改
+    unsaved: bool,
+    storage_path: PathBuf,
+    /// 加载任务和防抖保存任务，drop 即取消
+    _load_task: Option<Task<()>>,
+    _save_task: Option<Task<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+/// 数据文件的状态，决定能不能写盘
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Storage {
+    /// 正在从磁盘加载，列表还不完整
+    Loading,
+    /// 加载完成（或文件不存在），修改会保存
+    Ready,
+    /// 读取失败（权限、编码等），磁盘上的文件可能仍然有效：
+    /// 本次运行不再写盘，避免用空列表覆盖它
+    SaveDisabled,
+}
+
+/// 底部提示信息
+#[derive(Clone)]
+enum Notice {
+    Info(SharedString),
+    Error(SharedString),
+}
+
+impl TodoApp {
+    fn new(cx: &mut Context<Self>) -> Self {
+        Self::with_storage_path(data_file(), cx)
+    }
+
+    /// 使用指定的数据文件（测试中指向临时目录）
+    fn with_storage_path(storage_path: PathBuf, cx: &mut Context<Self>) -> Self {
+        cx.observe_global::<Theme>(|_view, cx| cx.notify()).detach();
+
+        // 退出前立即保存还在防抖等待中的修改；没有未保存的修改时不写盘
+        let quit_subscription = cx.on_app_quit(|view, cx| {
+            view._save_task = None;
+            let pending = (view.unsaved && view.storage == Storage::Ready)
+                .then(|| (view.storage_path.clone(), view.snapshot(cx)));
+            cx.background_executor().spawn(async move {
+                if let Some((path, snapshot)) = pending
+                    && let Err(error) = save_todos(&path, &snapshot)
+                {
+                    // 应用正在退出，界面已经无法显示错误
+                    eprintln!("⚠️ {}", error);
+                }
+            })
+        });
+
+        // 新任务输入框：按 Enter 时收到 Submitted 事件
+        let input = cx.new(|cx| TextInput::new(cx).placeholder("需要做什么？按 Enter 添加"));
+        let input_subscription = cx.subscribe(&input, |view, input, event: &TextInputEvent, cx| {
+            if let TextInputEvent::Submitted(text) = event {
+                let title = text.trim().to_string();
+                if !title.is_empty() && view.storage != Storage::Loading {
+                    view.add_todo(title, cx);
+                    input.update(cx, |input, cx| input.clear(cx));
+                }
+            }
+        });
+
+        let mut app = Self {
+            todos: Vec::new(),
+            next_id: 0,
+            input,
+            filter: Filter::All,
+            selected: None,
+            status: None,
+            list_focus: cx.focus_handle(),
+            storage: Storage::Loading,
+            unsaved: false,
+            storage_path,
+            _load_task: None,
+            _save_task: None,
+            _subscriptions: vec![input_subscription, quit_subscription],
+        };
+        app.load(cx);
+        app
+    }
+
+    /// 在后台线程读取数据文件，完成后填充列表
+    fn load(&mut self, cx: &mut Context<Self>) {
+        self.storage = Storage::Loading;
+        cx.notify();
+
+        let path = self.storage_path.clone();
+        let task = cx.spawn(async move |weak_view: WeakEntity<Self>, cx: &mut AsyncApp| {
+            let outcome = cx
+                .background_executor()
+                .spawn({
+                    let path = path.clone();
+                    async move { load_todos(&path) }
+                })
+                .await;
+
+            let _ = weak_view.update(cx, |view, cx| {
+                view.storage = Storage::Ready;
+                match outcome {
+                    Ok(LoadOutcome::Loaded(todos)) => {
+                        let count = todos.len();
+                        for todo in todos {
+                            view.insert_todo(todo.id, todo.title, todo.completed, cx);
+                        }
+                        view.status = Some(Notice::Info(
+                            format!("已从 {} 加载 {} 项任务", path.display(), count).into(),
+                        ));
+                    }
+                    Ok(LoadOutcome::Missing) => {
+                        // 第一次运行：放几条示例任务
+                        for title in ["阅读第三章：状态管理", "完成第四章测验", "用 GPUI 写一个 Todo List"] {
+                            view.add_todo(title, cx);
+                        }
+                    }
+                    Ok(LoadOutcome::Corrupted { backup, error }) => {
+                        view.status = Some(Notice::Error(
+                            format!("数据文件已损坏（{}），已备份到 {}", error, backup.display()).into(),
+                        ));
+                    }
+                    Err(error) => {
+                        view.storage = Storage::SaveDisabled;
+                        view.status = Some(Notice::Error(
+                            format!("{}，本次修改不会保存，以免覆盖原文件", error).into(),
+                        ));
+                    }
+                }
+                cx.notify();
+            });
+        });
+        self._load_task = Some(task);
+    }
+
+    /// 当前列表的快照，用于写盘
+    fn snapshot(&self, cx: &App) -> StoredTodos {
+        StoredTodos {
+            todos: self
+                .todos
+                .iter()
+                .map(|entry| {
+                    let item = entry.item.read(cx);
+                    StoredTodo {
+                        id: item.id,
+                        title: item.title.to_string(),
+                        completed: item.completed,
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// 防抖保存：每次修改都替换掉上一个还没执行的保存任务
+    fn schedule_save(&mut self, cx: &mut Context<Self>) {
+        // 加载完成前、或读取失败后不保存，避免用不完整的列表覆盖磁盘上的数据
+        if self.storage != Storage::Ready {
+            return;
+        }
+        self.unsaved = true;
+
+        let path = self.storage_path.clone();
+        let task = cx.spawn(async move |weak_view: WeakEntity<Self>, cx: &mut AsyncApp| {
+            cx.background_executor().timer(SAVE_DEBOUNCE).await;
+
+            // 取快照时清除标记；写盘期间的新修改会重新设置它
+            let Ok(snapshot) = weak_view.update(cx, |view, cx| {
+                view.unsaved = false;
+                view.snapshot(cx)
+            }) else {
+                return;
+            };
+            let result = cx
+                .background_executor()
+                .spawn(async move { save_todos(&path, &snapshot) })
+                .await;
+
+            if let Err(error) = result {
+                let _ = weak_view.update(cx, |view, cx| {
+                    view.unsaved = true;
+                    view.status = Some(Notice::Error(error.into()));
+                    cx.notify();
+                });
+            }
+        });
+        self._save_task = Some(task);
+    }
+
+    /// 用户新建任务
+    fn add_todo(&mut self, title: impl Into<SharedString>, cx: &mut Context<Self>) {
+        let id = self.next_id;
+        self.insert_todo(id, title, false, cx);
+        self.schedule_save(cx);
+    }
+
+    /// 创建任务实体并订阅它的事件（加载和新建共用）
+    fn insert_todo(
+        &mut self,
+        id: usize,
+        title: impl Into<SharedString>,
+        completed: bool,
+        cx: &mut Context<Self>,
+    ) {
+        self.next_id = self.next_id.max(id + 1);
+
+        let item = cx.new(|_| TodoItem::new(id, title, completed));
+        let subscription = cx.subscribe(&item, Self::on_item_event);
+        self.todos.push(TodoEntry {
+            id,
+            item,
+            _subscription: subscription,
+        });
+        cx.notify();
+    }
+
+    /// 子组件事件 -> 父组件更新
+    fn on_item_event(
+        &mut self,
+        _item: Entity<TodoItem>,
+        event: &TodoItemEvent,
+        cx: &mut Context<Self>,
+    ) {
+        let status = match event {
+            TodoItemEvent::Toggled { id, completed } => {
+                format!("任务 #{} 已{}", id, if *completed { "完成" } else { "恢复" })
+            }
+            TodoItemEvent::Edited { id, title } => format!("任务 #{} 已改名为「{}」", id, title),
+            TodoItemEvent::Deleted { id } => {
+                self.remove_todo(*id);
+                format!("任务 #{} 已删除", id)
+            }
+        };
+        self.status = Some(Notice::Info(status.into()));
+        self.schedule_save(cx);
+        cx.notify();
+    }
+
+    fn remove_todo(&mut self, id: usize) {
+        self.todos.retain(|entry| entry.id != id);
+        if self.selected == Some(id) {
+            self.selected = None;
+        }
+    }
+
+    /// 当前过滤条件下可见的任务
+    fn visible_todos<'a>(&'a self, cx: &'a App) -> impl Iterator<Item = &'a TodoEntry> + 'a {
+        let filter = self.filter;
+        self.todos
+            .iter()
+            .filter(move |entry| filter.matches(entry.item.read(cx).completed))
+    }
+
+    fn active_count(&self, cx: &App) -> usize {
+        self.todos
+            .iter()
+            .filter(|entry| !entry.item.read(cx).completed)
+            .count()
+    }
+
+    fn selected_item(&self) -> Option<Entity<TodoItem>> {
+        let id = self.selected?;
+        self.todos
+            .iter()
+            .find(|entry| entry.id == id)
+            .map(|entry| entry.item.clone())
+    }
+
+    fn set_filter(&mut self, filter: Filter, cx: &mut Context<Self>) {
+        self.filter = filter;
+        // 选中项被过滤掉时取消选中
+        if let Some(id) = self.selected
+            && !self.visible_todos(cx).any(|entry| entry.id == id)
+        {
+            self.selected = None;
+        }
+        cx.notify();
+    }
+
+    fn clear_completed(&mut self, cx: &mut Context<Self>) {
+        let completed: Vec<usize> = self
+            .todos
+            .iter()
+            .filter(|entry| entry.item.read(cx).completed)
+            .map(|entry| entry.id)
+            .collect();
+        for id in completed {
+            self.remove_todo(id);
+        }
+        self.schedule_save(cx);
+        cx.notify();
+    }
+
+    /// 在可见任务之间移动选中项，offset 为 -1 或 1
+    fn move_selection(&mut self, offset: isize, cx: &mut Context<Self>) {
+        let ids: Vec<usize> = self.visible_todos(cx).map(|entry| entry.id).collect();
+        if ids.is_empty() {
+            self.selected = None;
+        } else {
+            let next = match self.selected.and_then(|id| ids.iter().position(|&i| i == id)) {
+                Some(ix) => (ix as isize + offset).clamp(0, ids.len() as isize - 1) as usize,
+                None if offset < 0 => ids.len() - 1,
+                None => 0,
+            };
+            self.selected = Some(ids[next]);
+        }
+        cx.notify();
+    }
+
+    // ------------------------------------------------------------------------
+    // Action 处理器
+    // ------------------------------------------------------------------------
+
+    fn cancel(&mut self, _: &Cancel, _window: &mut Window, cx: &mut Context<Self>) {
+        self.input.update(cx, |input, cx| input.clear(cx));
+    }
+
+    fn focus_list(&mut self, _: &FocusList, window: &mut Window, cx: &mut Context<Self>) {
+        self.list_focus.focus(window);
+        if self.selected.is_none() {
+            self.move_selection(1, cx);
+        }
+        cx.notify();
+    }
+
+    fn focus_input(&mut self, _: &FocusInput, window: &mut Window, cx: &mut Context<Self>) {
+        self.input.read(cx).focus(window);
+        cx.notify();
+    }
+
+    fn select_prev(&mut self, _: &SelectPrev, _window: &mut Window, cx: &mut Context<Self>) {
+        self.move_selection(-1, cx);
+    }
+
+    fn select_next(&mut self, _: &SelectNext, _window: &mut Window, cx: &mut Context<Self>) {
+        self.move_selection(1, cx);
+    }
+
+    fn toggle_selected(&mut self, _: &ToggleSelected, _window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(item) = self.selected_item() {
+            item.update(cx, |item, cx| item.toggle(cx));
+        }
+    }
+
+    fn edit_selected(&mut self, _: &EditSelected, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(item) = self.selected_item() {
+            item.update(cx, |item, cx| item.start_editing(window, cx));
+        }
+    }
+
+    fn delete_selected(&mut self, _: &DeleteSelected, _window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(item) = self.selected_item() {
+            // 先移动选中项，再删除（删除会通过 Deleted 事件回到 on_item_event）
+            let id = self.selected;
+            self.move_selection(1, cx);
+            if self.selected == id {
+                self.move_selection(-1, cx);
+            }
+            item.update(cx, |item, cx| item.delete(cx));
+        }
+    }
+
+    fn show_all(&mut self, _: &ShowAll, _window: &mut Window, cx: &mut Context<Self>) {
+        self.set_filter(Filter::All, cx);
+    }
 
-struct TodoApp;
+    fn show_active(&mut self, _: &ShowActive, _window: &mut Window, cx: &mut Context<Self>) {
+        self.set_filter(Filter::Active, cx);
+    }
+
+    fn show_completed(&mut self, _: &ShowCompleted, _window: &mut Window, cx: &mut Context<Self>) {
+        self.set_filter(Filter::Completed, cx);
+    }
+
+    // ------------------------------------------------------------------------
+    // 渲染辅助
+    // ------------------------------------------------------------------------
+
+    /// 新任务输入框 - 外面包一层 TodoInput 上下文，处理 Esc 和 ↓
+    fn render_input(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .key_context("TodoInput")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::focus_list))
+            .child(self.input.clone())
+    }
+
+    /// 过滤按钮 + 统计 + 清除已完成
+    fn render_footer(&self, theme: &Theme, cx: &mut Context<Self>) -> impl IntoElement {
+        let active = self.active_count(cx);
+        let completed = self.todos.len() - active;
+
+        div()
+            .flex()
+            .items_center()
+            .justify_between()
+            .gap(theme.spacing.sm)
+            .text_sm()
+            .text_color(theme.subtext)
+            .child(format!("剩余 {} 项", active))
+            .child(
+                div()
+                    .flex()
+                    .gap(theme.spacing.xs)
+                    .children([Filter::All, Filter::Active, Filter::Completed].map(|filter| {
+                        StyledButton::new(filter.label(), filter.label())
+                            .variant(if self.filter == filter {
+                                ButtonVariant::Primary
+                            } else {
+                                ButtonVariant::Ghost
+                            })
+                            .size(ButtonSize::Small)
+                            .on_click(cx.listener(move |view, _event, _window, cx| {
+                                view.set_filter(filter, cx);
+                            }))
+                    })),
+            )
+            .child(
+                StyledButton::new("clear-completed", "清除已完成")
+                    .variant(ButtonVariant::Outline)
+                    .size(ButtonSize::Small)
+                    .disabled(completed == 0)
+                    .on_click(cx.listener(|view, _event, _window, cx| view.clear_completed(cx))),
+            )
+    }
+}
 
 impl Render for TodoApp {
-    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let theme = cx.theme().clone();
+        let list_focused = self.list_focus.contains_focused(window, cx);
+
+        let rows: Vec<AnyElement> = self
+            .visible_todos(cx)
+            .map(|entry| {
+                let is_selected = list_focused && self.selected == Some(entry.id);
+                div()
+                    .rounded(theme.radius.md)
+                    .border_1()
+                    .border_color(if is_selected { theme.accent } else { transparent_black() })
+                    .child(entry.item.clone())
+                    .into_any_element()
+            })
+            .collect();
+        let is_empty = rows.is_empty();
+
         div()
+            .id("todo-app")
+            .key_context("TodoApp")
+            .on_action(cx.listener(Self::show_all))
+            .on_action(cx.listener(Self::show_active))
+            .on_action(cx.listener(Self::show_completed))
             .size_full()
             .flex()
-            .items_center()
             .justify_center()
-            .bg(rgb(0xF9FAFB))
+            .bg(theme.background)
+            .p(theme.spacing.xl)
             .child(
                 div()
                     .flex()
                     .flex_col()
-                    .gap_4()
-                    .p_8()
-                    .bg(rgb(0xFFFFFF))
-                    .rounded_lg()
+                    .gap(theme.spacing.lg)
+                    .w_full()
+                    .max_w(px(560.0))
+                    .p(theme.spacing.xl)
+                    .bg(theme.surface)
+                    .rounded(theme.radius.lg)
                     .shadow_lg()
                     .child(
                         div()
                             .text_2xl()
                             .font_weight(FontWeight::BOLD)
-                            .text_color(rgb(0x1F2937))
-                            .child("📝 Todo List")
+                            .text_color(theme.text)
+                            .child("📝 Todo List"),
                     )
+                    .child(self.render_input(cx))
+                    // 任务列表
                     .child(
                         div()
-                            .text_color(rgb(0x6B7280))
-                            .child("这个综合示例正在开发中...")
+                            .id("todo-list")
+                            .key_context("TodoList")
+                            .track_focus(&self.list_focus)
+                            .on_action(cx.listener(Self::focus_input))
+                            .on_action(cx.listener(Self::select_prev))
+                            .on_action(cx.listener(Self::select_next))
+                            .on_action(cx.listener(Self::toggle_selected))
+                            .on_action(cx.listener(Self::edit_selected))
+                            .on_action(cx.listener(Self::delete_selected))
+                            .flex_1()
+                            .flex()
+                            .flex_col()
+                            .gap(theme.spacing.xs)
+                            .overflow_y_scroll()
+                            // 加载状态 - 与第六章 AsyncDemo 一样根据状态切换显示
+                            .when(self.storage == Storage::Loading, |el| {
+                                el.items_center()
+                                    .justify_center()
+                                    .py(theme.spacing.xl)
+                                    .text_sm()
+                                    .text_color(theme.subtext)
+                                    .child("⏳ 正在加载任务...")
+                            })
+                            .when(is_empty && self.storage != Storage::Loading, |el| {
+                                el.items_center()
+                                    .justify_center()
+                                    .py(theme.spacing.xl)
+                                    .text_sm()
+                                    .text_color(theme.subtext)
+                                    .child("没有任务 🎉")
+                            })
+                            .children(rows),
                     )
+                    .child(self.render_footer(&theme, cx))
+                    .when_some(self.status.clone(), |el, notice| {
+                        el.child(match notice {
+                            Notice::Info(message) => {
+                                div().text_xs().text_color(theme.subtext).child(message)
+                            }
+                            Notice::Error(message) => div()
+                                .p(theme.spacing.sm)
+                                .rounded(theme.radius.md)
+                                .bg(theme.danger)
+                                .text_xs()
+                                .text_color(theme.accent_foreground)
+                                .child(format!("⚠️ {}", message)),
+                        })
+                    })
                     .child(
                         div()
-                            .text_sm()
-                            .text_color(rgb(0x9CA3AF))
-                            .child("请先完成前几章的学习！")
-                    )
+                            .text_xs()
+                            .text_color(theme.subtext)
+                            .child("⌨️ ↓ 进入列表 | ↑/↓ 选择 | Space 完成 | Enter 编辑 | Delete 删除 | Ctrl-1/2/3 过滤 | 双击标题编辑"),
+                    ),
             )
     }
 }
 
+// ============================================================================
+// 主函数
+// ============================================================================
+
 fn main() {
     Application::new().run(|cx| {
+        Theme::init(cx);
+        bind_keys(cx);
+
         cx.open_window(
             WindowOptions {
                 window_bounds: Some(WindowBounds::Windowed(Bounds {
@@ -54,19 +905,363 @@ fn main() {
                         y: px(200.0),
                     },
                     size: Size {
-                        width: px(500.0),
-                        height: px(600.0),
+                        width: px(640.0),
+                        height: px(680.0),
                     },
                 })),
                 titlebar: Some(TitlebarOptions {
-                    title: Some("Todo List - 即将上线".into()),
+                    title: Some("Todo List".into()),
                     appears_transparent: false,
                     ..Default::default()
                 }),
                 ..Default::default()
             },
-            |_window, cx| cx.new(|_cx| TodoApp),
+            |window, cx| {
+                let view = cx.new(TodoApp::new);
+                // 启动后直接可以输入
+                view.read(cx).input.read(cx).focus(window);
+                view
+            },
         )
         .unwrap();
     });
 }
+
+#[cfg(test)]
+mod tests {
+    // 不能 use super::*：gpui::* 中的 test 宏会遮蔽内置的 #[test]
+    use super::{
+        bind_keys, load_todos, save_todos, DeleteSelected, EditSelected, Filter, FocusList,
+        LoadOutcome, Notice, SelectNext, SelectPrev, ShowActive, ShowAll, ShowCompleted, Storage,
+        StoredTodo, StoredTodos, TodoApp, ToggleSelected, SAVE_DEBOUNCE,
+    };
+    use gpui::{AppContext, Entity, Modifiers, TestAppContext, VisualTestContext};
+    use gpui_components::Theme;
+    use std::path::{Path, PathBuf};
+
+    /// 每个测试使用自己的临时目录，返回其中的数据文件路径
+    fn data_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("gpui_todo_{}_{}", name, std::process::id()));
+        std::fs::remove_dir_all(&dir).ok();
+        dir.join("todos.json")
+    }
+
+    fn todo(id: usize, title: &str, completed: bool) -> StoredTodo {
+        StoredTodo { id, title: title.into(), completed }
+    }
+
+    /// 创建一个以 path 为数据文件的 TodoApp，并等待后台加载完成
+    ///
+    /// 持久化测试不需要窗口；退出（cx.quit()）时也不用关闭窗口。
+    fn load_app(path: &Path, cx: &mut TestAppContext) -> Entity<TodoApp> {
+        cx.update(Theme::init);
+        let app = cx.new(|cx| TodoApp::with_storage_path(path.to_path_buf(), cx));
+        cx.run_until_parked();
+        app
+    }
+
+    /// 在窗口中打开 TodoApp，数据文件里有三项任务：#0 未完成、#1 已完成、#2 未完成
+    ///
+    /// 之后把焦点放到新任务输入框上，与 main() 启动时一样。
+    fn open_app<'a>(
+        name: &str,
+        cx: &'a mut TestAppContext,
+    ) -> (Entity<TodoApp>, &'a mut VisualTestContext) {
+        let path = data_path(name);
+        let todos = vec![
+            todo(0, "阅读第三章", false),
+            todo(1, "完成第四章测验", true),
+            todo(2, "写 Todo List", false),
+        ];
+        save_todos(&path, &StoredTodos { todos }).unwrap();
+
+        cx.update(|cx| {
+            Theme::init(cx);
+            bind_keys(cx);
+        });
+        let (app, cx) = cx.add_window_view(|_, cx| TodoApp::with_storage_path(path, cx));
+        cx.run_until_parked();
+        cx.update(|window, cx| app.read(cx).input.read(cx).focus(window));
+        cx.run_until_parked();
+        (app, cx)
+    }
+
+    /// 列表中的任务：(id, 标题, 是否完成)
+    fn todos(app: &Entity<TodoApp>, cx: &mut VisualTestContext) -> Vec<(usize, String, bool)> {
+        app.read_with(cx, |app, cx| {
+            app.snapshot(cx)
+                .todos
+                .into_iter()
+                .map(|todo| (todo.id, todo.title, todo.completed))
+                .collect()
+        })
+    }
+
+    fn visible_ids(app: &Entity<TodoApp>, cx: &mut VisualTestContext) -> Vec<usize> {
+        app.read_with(cx, |app, cx| app.visible_todos(cx).map(|entry| entry.id).collect())
+    }
+
+    fn selected(app: &Entity<TodoApp>, cx: &mut VisualTestContext) -> Option<usize> {
+        app.read_with(cx, |app, _| app.selected)
+    }
+
+    /// 编辑选中的任务：把编辑框内容替换为 text 后按 Enter
+    fn edit_selected(app: &Entity<TodoApp>, text: &str, cx: &mut VisualTestContext) {
+        cx.dispatch_action(EditSelected);
+        let editor = app.read_with(cx, |app, cx| {
+            let item = app.selected_item().expect("没有选中的任务");
+            item.read(cx).editor.as_ref().expect("没有进入编辑状态").0.clone()
+        });
+        editor.update(cx, |editor, cx| editor.set_text(text.to_string(), cx));
+        cx.run_until_parked();
+        cx.simulate_keystrokes("enter");
+    }
+
+    fn click_clear_completed(cx: &mut VisualTestContext) {
+        let bounds = cx.debug_bounds("button-clear-completed").expect("找不到清除按钮");
+        cx.simulate_click(bounds.center(), Modifiers::none());
+    }
+
+    /// 磁盘上保存的任务标题
+    fn saved_titles(path: &Path) -> Vec<String> {
+        match load_todos(path) {
+            Ok(LoadOutcome::Loaded(todos)) => todos.into_iter().map(|todo| todo.title).collect(),
+            _ => panic!("没有读到保存的任务"),
+        }
+    }
+
+    #[test]
+    fn missing_file_is_reported_as_missing() {
+        let path = data_path("missing");
+        assert!(matches!(load_todos(&path), Ok(LoadOutcome::Missing)));
+    }
+
+    #[test]
+    fn saved_todos_load_back() {
+        let path = data_path("round_trip");
+        let todos = vec![todo(0, "阅读第三章", true), todo(3, "写 Todo List 😀", false)];
+
+        // 目录不存在时自动创建，写完后不留下临时文件
+        save_todos(&path, &StoredTodos { todos: todos.clone() }).unwrap();
+        assert!(!path.with_extension("json.tmp").exists());
+
+        let Ok(LoadOutcome::Loaded(loaded)) = load_todos(&path) else {
+            panic!("没有读回保存的任务");
+        };
+        let fields = |todos: &[StoredTodo]| -> Vec<_> {
+            todos.iter().map(|todo| (todo.id, todo.title.clone(), todo.completed)).collect()
+        };
+        assert_eq!(fields(&loaded), fields(&todos));
+        std::fs::remove_dir_all(path.parent().unwrap()).ok();
+    }
+
+    #[test]
+    fn corrupted_file_is_moved_aside() {
+        let path = data_path("corrupted");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, "{ \"todos\": [").unwrap();
+
+        let Ok(LoadOutcome::Corrupted { backup, .. }) = load_todos(&path) else {
+            panic!("损坏的文件没有被识别");
+        };
+        // 原文件被改名为备份，内容原样保留；之后保存会创建新文件，不会覆盖备份
+        assert!(!path.exists());
+        assert_eq!(std::fs::read_to_string(&backup).unwrap(), "{ \"todos\": [");
+        save_todos(&path, &StoredTodos { todos: Vec::new() }).unwrap();
+        assert_eq!(std::fs::read_to_string(&backup).unwrap(), "{ \"todos\": [");
+        std::fs::remove_dir_all(path.parent().unwrap()).ok();
+    }
+
+    #[test]
+    fn unreadable_file_is_an_error() {
+        // 不是合法的 UTF-8：读取失败，文件保持原样（TodoApp 因此停用保存）
+        let path = data_path("invalid_utf8");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, [0xFF, 0xFE, 0x00]).unwrap();
+
+        assert!(load_todos(&path).is_err());
+        assert_eq!(std::fs::read(&path).unwrap(), [0xFF, 0xFE, 0x00]);
+        std::fs::remove_dir_all(path.parent().unwrap()).ok();
+    }
+
+    #[gpui::test]
+    fn changes_are_saved_after_the_debounce(cx: &mut TestAppContext) {
+        let path = data_path("debounce");
+        save_todos(&path, &StoredTodos { todos: vec![todo(0, "阅读第三章", false)] }).unwrap();
+        let app = load_app(&path, cx);
+        assert_eq!(app.read_with(cx, |app, _| app.storage), Storage::Ready);
+
+        // 连续修改：防抖期间不写盘
+        app.update(cx, |app, cx| {
+            app.add_todo("完成第四章测验", cx);
+            app.add_todo("写 Todo List", cx);
+        });
+        cx.run_until_parked();
+        assert_eq!(saved_titles(&path), ["阅读第三章"]);
+        assert!(app.read_with(cx, |app, _| app.unsaved));
+
+        // 等待超过 SAVE_DEBOUNCE 后一次写入全部修改
+        cx.executor().advance_clock(SAVE_DEBOUNCE);
+        assert_eq!(saved_titles(&path), ["阅读第三章", "完成第四章测验", "写 Todo List"]);
+        assert!(!app.read_with(cx, |app, _| app.unsaved));
+        std::fs::remove_dir_all(path.parent().unwrap()).ok();
+    }
+
+    #[gpui::test]
+    fn quit_flushes_pending_changes(cx: &mut TestAppContext) {
+        let path = data_path("quit_flush");
+        save_todos(&path, &StoredTodos { todos: vec![todo(0, "阅读第三章", false)] }).unwrap();
+        let app = load_app(&path, cx);
+
+        app.update(cx, |app, cx| app.add_todo("写 Todo List", cx));
+        cx.run_until_parked();
+        assert_eq!(saved_titles(&path), ["阅读第三章"]);
+
+        // 还在防抖等待中就退出：on_app_quit 立即写盘
+        cx.quit();
+        assert_eq!(saved_titles(&path), ["阅读第三章", "写 Todo List"]);
+        std::fs::remove_dir_all(path.parent().unwrap()).ok();
+    }
+
+    #[gpui::test]
+    fn unreadable_file_disables_saving(cx: &mut TestAppContext) {
+        let path = data_path("save_disabled");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, [0xFF, 0xFE, 0x00]).unwrap();
+        let app = load_app(&path, cx);
+
+        app.read_with(cx, |app, _| {
+            assert_eq!(app.storage, Storage::SaveDisabled);
+            assert!(matches!(app.status, Some(Notice::Error(_))));
+        });
+
+        // 修改照常生效，但既不防抖保存，也不在退出时写盘
+        app.update(cx, |app, cx| app.add_todo("写 Todo List", cx));
+        assert_eq!(app.read_with(cx, |app, _| app.todos.len()), 1);
+        assert!(!app.read_with(cx, |app, _| app.unsaved));
+        cx.executor().advance_clock(SAVE_DEBOUNCE);
+        cx.quit();
+        assert_eq!(std::fs::read(&path).unwrap(), [0xFF, 0xFE, 0x00]);
+        std::fs::remove_dir_all(path.parent().unwrap()).ok();
+    }
+
+    #[gpui::test]
+    fn enter_in_the_input_adds_a_todo(cx: &mut TestAppContext) {
+        let (app, cx) = open_app("add", cx);
+
+        cx.simulate_input("买牛奶");
+        cx.simulate_keystrokes("enter");
+        assert_eq!(todos(&app, cx).last(), Some(&(3, "买牛奶".to_string(), false)));
+        // 添加后清空输入框
+        assert_eq!(app.read_with(cx, |app, cx| app.input.read(cx).text()), "");
+
+        // 只有空白的输入不会添加任务
+        cx.simulate_input("   ");
+        cx.simulate_keystrokes("enter");
+        assert_eq!(todos(&app, cx).len(), 4);
+    }
+
+    #[gpui::test]
+    fn editing_renames_and_empty_edit_deletes(cx: &mut TestAppContext) {
+        let (app, cx) = open_app("edit", cx);
+        cx.dispatch_action(FocusList);
+        assert_eq!(selected(&app, cx), Some(0));
+
+        // 首尾空白被去掉
+        edit_selected(&app, "  重读第三章 ", cx);
+        assert_eq!(todos(&app, cx)[0], (0, "重读第三章".to_string(), false));
+
+        // 编辑框关闭后焦点随之消失，重新聚焦列表；内容为空时删除任务
+        cx.update(|window, cx| app.read(cx).list_focus.focus(window));
+        edit_selected(&app, "", cx);
+        assert_eq!(todos(&app, cx).iter().map(|todo| todo.0).collect::<Vec<_>>(), [1, 2]);
+        assert_eq!(selected(&app, cx), None);
+    }
+
+    #[gpui::test]
+    fn toggle_selected_flips_completion(cx: &mut TestAppContext) {
+        let (app, cx) = open_app("toggle", cx);
+        cx.dispatch_action(FocusList);
+        cx.dispatch_action(SelectNext);
+        assert_eq!(selected(&app, cx), Some(1));
+
+        cx.dispatch_action(ToggleSelected);
+        assert!(!todos(&app, cx)[1].2);
+        cx.dispatch_action(ToggleSelected);
+        assert!(todos(&app, cx)[1].2);
+    }
+
+    #[gpui::test]
+    fn filters_show_matching_todos(cx: &mut TestAppContext) {
+        let (app, cx) = open_app("filters", cx);
+        cx.dispatch_action(FocusList);
+        cx.dispatch_action(SelectNext);
+
+        // 选中的 #1 已完成，被过滤掉后取消选中
+        cx.dispatch_action(ShowActive);
+        assert_eq!(app.read_with(cx, |app, _| app.filter), Filter::Active);
+        assert_eq!(visible_ids(&app, cx), [0, 2]);
+        assert_eq!(selected(&app, cx), None);
+
+        cx.dispatch_action(ShowCompleted);
+        assert_eq!(visible_ids(&app, cx), [1]);
+
+        cx.dispatch_action(ShowAll);
+        assert_eq!(visible_ids(&app, cx), [0, 1, 2]);
+
+        // 键盘选择只在可见任务之间移动
+        cx.dispatch_action(ShowActive);
+        cx.dispatch_action(SelectNext);
+        cx.dispatch_action(SelectNext);
+        assert_eq!(selected(&app, cx), Some(2));
+    }
+
+    #[gpui::test]
+    fn clear_completed_button_removes_completed_todos(cx: &mut TestAppContext) {
+        let (app, cx) = open_app("clear_completed", cx);
+        let ids = |cx: &mut VisualTestContext| -> Vec<usize> {
+            todos(&app, cx).iter().map(|todo| todo.0).collect()
+        };
+
+        click_clear_completed(cx);
+        assert_eq!(ids(cx), [0, 2]);
+
+        // 完成 #0 后再清除
+        cx.dispatch_action(FocusList);
+        cx.dispatch_action(ToggleSelected);
+        cx.run_until_parked();
+        click_clear_completed(cx);
+        assert_eq!(ids(cx), [2]);
+    }
+
+    #[gpui::test]
+    fn keyboard_selection_and_delete(cx: &mut TestAppContext) {
+        let (app, cx) = open_app("keyboard", cx);
+
+        // 进入列表时选中第一项，上下移动停在两端
+        cx.dispatch_action(FocusList);
+        assert_eq!(selected(&app, cx), Some(0));
+        cx.dispatch_action(SelectPrev);
+        assert_eq!(selected(&app, cx), Some(0));
+        cx.dispatch_action(SelectNext);
+        cx.dispatch_action(SelectNext);
+        cx.dispatch_action(SelectNext);
+        assert_eq!(selected(&app, cx), Some(2));
+        cx.dispatch_action(SelectPrev);
+        assert_eq!(selected(&app, cx), Some(1));
+
+        // 删除中间一项：选中后面的邻居
+        cx.dispatch_action(DeleteSelected);
+        assert_eq!(todos(&app, cx).iter().map(|todo| todo.0).collect::<Vec<_>>(), [0, 2]);
+        assert_eq!(selected(&app, cx), Some(2));
+
+        // 删除最后一项：选中前面的邻居
+        cx.dispatch_action(DeleteSelected);
+        assert_eq!(selected(&app, cx), Some(0));
+
+        // 删除唯一的一项：不再有选中项
+        cx.dispatch_action(DeleteSelected);
+        assert!(todos(&app, cx).is_empty());
+        assert_eq!(selected(&app, cx), None);
+    }
+}